@@ -0,0 +1,254 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use anyhow::{Result, anyhow};
+use eframe::egui::Pos2;
+
+use super::{Action, ActionKind, ActionLog, CaptureInfo, CaptureSource, run_command, save_to};
+use crate::capture::{self, RectPx, RgbaImage};
+use crate::history::{HistoryEntry, PendingHistory};
+use crate::ipc::Response;
+use crate::upload;
+
+/// 只能在界面线程执行的一步
+pub enum UiStep {
+    Copy {
+        image: Arc<RgbaImage>,
+        file: Option<PathBuf>,
+    },
+    CopyText(String),
+    Pin {
+        image: Arc<RgbaImage>,
+        at: Pos2,
+    },
+    Edit,
+    /// 历史记录已写入
+    AddHistory(HistoryEntry),
+    /// 截图已保存到文件
+    Saved(PathBuf),
+}
+
+/// 执行动作之前在后台完成的准备：查询窗口信息、写入历史记录或文件
+#[derive(Default)]
+pub struct Prepare {
+    /// 屏幕截图的区域，查询所在的显示器和中心处的窗口标题
+    pub probe: Option<RectPx>,
+    pub history: Option<PendingHistory>,
+    /// 没有历史记录但需要文件时写入的路径
+    pub file: Option<PathBuf>,
+    /// 等待文件路径的 ctl 客户端
+    pub reply: Option<Sender<Response>>,
+}
+
+/// 后台线程请求界面执行的一步，执行完用 `reply` 返回结果
+pub struct UiRequest {
+    pub step: UiStep,
+    reply: Sender<Result<String>>,
+}
+
+impl UiRequest {
+    pub fn reply(self, result: Result<String>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// 在后台线程按顺序执行的动作链
+///
+/// 复制、钉图等需要界面的动作通过 `try_request` 交给界面线程，后台线程等它完成后再继续。
+pub struct ActionChain {
+    requests: Receiver<UiRequest>,
+    current: Arc<Mutex<Option<String>>>,
+    thread: Option<JoinHandle<Option<String>>>,
+}
+
+impl ActionChain {
+    pub fn start(
+        actions: Vec<Action>,
+        image: RgbaImage,
+        info: CaptureInfo,
+        pin_at: Pos2,
+        log: ActionLog,
+        prepare: Prepare,
+    ) -> Self {
+        let (sender, requests) = mpsc::channel();
+        let current = Arc::new(Mutex::new(None));
+        let thread = {
+            let current = current.clone();
+            thread::spawn(move || {
+                let mut chain = Worker {
+                    image: Arc::new(image),
+                    info,
+                    pin_at,
+                    ui: sender,
+                };
+                chain.prepare(prepare);
+                chain.run(&actions, &log, &current)
+            })
+        };
+        Self {
+            requests,
+            current,
+            thread: Some(thread),
+        }
+    }
+
+    /// 等待界面执行的一步
+    pub fn try_request(&self) -> Option<UiRequest> {
+        self.requests.try_recv().ok()
+    }
+
+    /// 正在执行的动作
+    pub fn current(&self) -> Option<String> {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// 整条链结束时返回第一个错误
+    pub fn try_finish(&mut self) -> Option<Option<String>> {
+        if !self.thread.as_ref()?.is_finished() {
+            return None;
+        }
+        let thread = self.thread.take()?;
+        Some(
+            thread
+                .join()
+                .unwrap_or_else(|_| Some("actions panicked".to_string())),
+        )
+    }
+}
+
+/// 后台线程里执行动作链的状态
+struct Worker {
+    image: Arc<RgbaImage>,
+    info: CaptureInfo,
+    pin_at: Pos2,
+    ui: Sender<UiRequest>,
+}
+
+impl Worker {
+    fn prepare(&mut self, prepare: Prepare) {
+        if let Some(rect) = prepare.probe {
+            let (x, y) = ((rect.x + rect.w / 2) as i32, (rect.y + rect.h / 2) as i32);
+            self.info.window_title = capture::window_title_at(x, y);
+        }
+        if let Some(mut pending) = prepare.history {
+            pending.meta.window_title = self.info.window_title.clone();
+            pending.meta.monitor = prepare.probe.and_then(capture::monitor_name);
+            let entry = HistoryEntry::write(
+                pending.dir,
+                &self.image,
+                &pending.original,
+                &pending.annotations,
+                pending.meta,
+            );
+            match entry {
+                Ok(entry) => {
+                    self.info.path = Some(entry.image_path());
+                    let _ = self.ask(UiStep::AddHistory(entry));
+                }
+                Err(e) => eprintln!("save history failed: {e:#}"),
+            }
+        }
+        if self.info.path.is_none()
+            && let Some(file) = prepare.file
+        {
+            let saved = file
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(anyhow::Error::from)
+                .and_then(|()| Ok(self.image.save(&file)?));
+            match saved {
+                Ok(()) => self.info.path = Some(file),
+                Err(e) => eprintln!("save {} failed: {e}", file.display()),
+            }
+        }
+
+        if let Some(path) = &self.info.path {
+            let _ = self.ask(UiStep::Saved(path.clone()));
+        }
+        if let Some(reply) = prepare.reply {
+            let _ = reply.send(match &self.info.path {
+                Some(path) => Response::path(path.clone()),
+                None => Response::error("save capture failed"),
+            });
+        }
+    }
+
+    /// 按顺序执行匹配条件的动作，结果记录到动作日志，返回第一个错误
+    fn run(
+        &self,
+        actions: &[Action],
+        log: &ActionLog,
+        current: &Mutex<Option<String>>,
+    ) -> Option<String> {
+        let mut first_error = None;
+        for action in actions {
+            if !action.enabled || !action.condition.matches(&self.info) {
+                continue;
+            }
+            // 编辑结果再在编辑器中打开会不停循环
+            if action.kind == ActionKind::Edit && self.info.source == CaptureSource::Edit {
+                continue;
+            }
+            let name = action.kind.name();
+            *current.lock().unwrap() = Some(name.clone());
+            let result = self.run_action(&action.kind).map_err(|e| format!("{e:#}"));
+            *current.lock().unwrap() = None;
+
+            let failed = result.is_err();
+            if let Err(e) = &result {
+                first_error.get_or_insert_with(|| format!("{name} failed: {e}"));
+            }
+            log.push(name, result);
+            if failed && action.stop_on_failure {
+                break;
+            }
+        }
+        first_error
+    }
+
+    fn run_action(&self, kind: &ActionKind) -> Result<String> {
+        match kind {
+            ActionKind::Copy => self
+                .ask(UiStep::Copy {
+                    image: self.image.clone(),
+                    file: self.info.path.clone(),
+                })
+                .map(|_| "copied".to_string()),
+            ActionKind::Save { dir, name } => save_to(dir, name, &self.image, &self.info)
+                .map(|path| format!("saved {}", path.display())),
+            ActionKind::Pin => self.ask(UiStep::Pin {
+                image: self.image.clone(),
+                at: self.pin_at,
+            }),
+            ActionKind::Command {
+                program,
+                args,
+                stdin_png,
+            } => run_command(program, args, stdin_png.then_some(&*self.image), &self.info),
+            ActionKind::Edit => self.ask(UiStep::Edit),
+            ActionKind::Upload { uploader } => {
+                let mut png = Vec::new();
+                self.image
+                    .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+                let url = upload::upload(uploader, &png, &self.info.file_name())?;
+                self.ask(UiStep::CopyText(url.clone()))?;
+                Ok(url)
+            }
+        }
+    }
+
+    /// 让界面线程执行一步并等待结果
+    fn ask(&self, step: UiStep) -> Result<String> {
+        let (reply, result) = mpsc::channel();
+        self.ui
+            .send(UiRequest { step, reply })
+            .map_err(|_| anyhow!("window closed"))?;
+        match result.recv() {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("window closed")),
+        }
+    }
+}
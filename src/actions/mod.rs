@@ -0,0 +1,318 @@
+mod chain;
+mod panel;
+
+use std::io::{Cursor, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::RgbaImage;
+use crate::upload::UploaderConfig;
+
+pub use chain::{ActionChain, Prepare, UiRequest, UiStep};
+
+/// 截图的来源，用于动作的触发条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaptureSource {
+    Region,
+    FullScreen,
+    Scrolling,
+    Edit, // 编辑已有图像
+}
+
+impl CaptureSource {
+    pub fn all() -> &'static [CaptureSource] {
+        &[
+            CaptureSource::Region,
+            CaptureSource::FullScreen,
+            CaptureSource::Scrolling,
+            CaptureSource::Edit,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaptureSource::Region => "Region",
+            CaptureSource::FullScreen => "Full screen",
+            CaptureSource::Scrolling => "Scrolling",
+            CaptureSource::Edit => "Edit",
+        }
+    }
+}
+
+/// 截图完成后要执行的动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ActionKind {
+    Copy,
+    /// 保存到目录，文件名支持 `{timestamp}`、`{width}`、`{height}`
+    Save {
+        dir: PathBuf,
+        name: String,
+    },
+    Pin,
+    /// 运行外部命令，参数中的 `{path}` 等会被替换；可把 PNG 写入标准输入
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        stdin_png: bool,
+    },
+    /// 在标注编辑器中重新打开，编辑器里截取的图像不会触发
+    Edit,
+    /// 上传到自定义服务器，并把链接复制到剪贴板
+    Upload {
+        uploader: UploaderConfig,
+    },
+}
+
+impl ActionKind {
+    pub fn name(&self) -> String {
+        match self {
+            ActionKind::Copy => "Copy to clipboard".to_string(),
+            ActionKind::Save { .. } => "Save to folder".to_string(),
+            ActionKind::Pin => "Pin to screen".to_string(),
+            ActionKind::Command { program, .. } => format!("Run {program}"),
+            ActionKind::Edit => "Open in editor".to_string(),
+            ActionKind::Upload { uploader } => format!("Upload to {}", uploader.name),
+        }
+    }
+
+    /// 新建动作时的默认参数
+    pub fn templates() -> Vec<ActionKind> {
+        vec![
+            ActionKind::Copy,
+            ActionKind::Save {
+                dir: dirs::picture_dir().unwrap_or_else(|| PathBuf::from(".")),
+                name: "pxshot-{timestamp}.png".to_string(),
+            },
+            ActionKind::Pin,
+            ActionKind::Command {
+                program: "xdg-open".to_string(),
+                args: vec!["{path}".to_string()],
+                stdin_png: false,
+            },
+            ActionKind::Edit,
+            ActionKind::Upload {
+                uploader: UploaderConfig::default(),
+            },
+        ]
+    }
+}
+
+/// 动作的触发条件，未设置的条件不做限制
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Condition {
+    pub sources: Vec<CaptureSource>, // 为空时匹配所有来源
+    pub annotated: Option<bool>,
+    pub window_contains: String, // 窗口标题包含的文字
+    pub min_width: u32,
+    pub min_height: u32,
+}
+
+impl Condition {
+    pub fn matches(&self, info: &CaptureInfo) -> bool {
+        (self.sources.is_empty() || self.sources.contains(&info.source))
+            && self.annotated.is_none_or(|a| a == info.annotated)
+            && (self.window_contains.is_empty()
+                || info.window_title.as_deref().is_some_and(|title| {
+                    title
+                        .to_lowercase()
+                        .contains(&self.window_contains.to_lowercase())
+                }))
+            && info.width >= self.min_width
+            && info.height >= self.min_height
+    }
+}
+
+/// 动作链中的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Action {
+    #[serde(flatten)]
+    pub kind: ActionKind,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub condition: Condition,
+    /// 失败后不再执行后面的动作
+    #[serde(default)]
+    pub stop_on_failure: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+impl Action {
+    pub fn new(kind: ActionKind) -> Self {
+        Self {
+            kind,
+            enabled: true,
+            condition: Condition::default(),
+            stop_on_failure: false,
+        }
+    }
+
+    /// 默认动作链：只复制到剪贴板
+    pub fn defaults() -> Vec<Action> {
+        vec![Action::new(ActionKind::Copy)]
+    }
+}
+
+/// 传给动作的截图信息
+#[derive(Debug, Clone)]
+pub struct CaptureInfo {
+    pub source: CaptureSource,
+    pub annotated: bool,
+    pub width: u32,
+    pub height: u32,
+    pub window_title: Option<String>,
+    pub timestamp: u64,
+    pub path: Option<PathBuf>, // 已保存的图像文件
+}
+
+impl CaptureInfo {
+    /// 替换 `{path}`、`{width}`、`{height}`、`{timestamp}`、`{title}`
+    pub fn expand(&self, template: &str) -> String {
+        self.expand_with(template, |value| value)
+    }
+
+    /// 用作文件名时，替换进去的值去掉路径分隔符和控制字符
+    pub fn expand_file_name(&self, template: &str) -> String {
+        self.expand_with(template, sanitize_file_name)
+    }
+
+    fn expand_with(&self, template: &str, value: impl Fn(String) -> String) -> String {
+        let path = self
+            .path
+            .as_deref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let title = self.window_title.clone().unwrap_or_default();
+        template
+            .replace("{path}", &value(path))
+            .replace("{width}", &value(self.width.to_string()))
+            .replace("{height}", &value(self.height.to_string()))
+            .replace("{timestamp}", &value(self.timestamp.to_string()))
+            .replace("{title}", &value(title))
+    }
+
+    /// 上传时使用的文件名
+    pub fn file_name(&self) -> String {
+        self.path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("pxshot-{}.png", self.timestamp))
+    }
+}
+
+/// 单个动作的执行结果
+#[derive(Debug, Clone)]
+pub struct ActionReport {
+    pub name: String,
+    pub result: Result<String, String>,
+}
+
+/// 最近一次动作链的执行结果；后台动作完成后追加
+#[derive(Debug, Clone, Default)]
+pub struct ActionLog(Arc<Mutex<Vec<ActionReport>>>);
+
+impl ActionLog {
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    pub fn push(&self, name: String, result: Result<String, String>) {
+        self.0.lock().unwrap().push(ActionReport { name, result });
+    }
+
+    pub fn reports(&self) -> Vec<ActionReport> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// 保存到目录，返回写入的路径
+pub fn save_to(dir: &Path, name: &str, image: &RgbaImage, info: &CaptureInfo) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let path = dir.join(info.expand_file_name(name));
+    image.save(&path)?;
+    Ok(path)
+}
+
+/// 把路径分隔符和控制字符换成 `_`，`.` 和 `..` 也不能单独作为文件名
+fn sanitize_file_name(value: String) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match value.as_str() {
+        "." | ".." => "_".to_string(),
+        _ => value,
+    }
+}
+
+/// 运行外部命令并等待结束，非零退出码视为失败
+pub fn run_command(
+    program: &str,
+    args: &[String],
+    stdin_png: Option<&RgbaImage>,
+    info: &CaptureInfo,
+) -> Result<String> {
+    let args: Vec<String> = args.iter().map(|arg| info.expand(arg)).collect();
+    let png = match stdin_png {
+        Some(image) => {
+            let mut png = Vec::new();
+            image.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+            Some(png)
+        }
+        None => None,
+    };
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(if png.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("cannot run {program}"))?;
+
+    // 边写标准输入边读输出，命令不读完输入就退出也不算失败
+    let writer = png.map(|png| {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        thread::spawn(move || match stdin.write_all(&png) {
+            Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(()),
+        })
+    });
+
+    let output = child.wait_with_output()?;
+    if let Some(writer) = writer {
+        writer
+            .join()
+            .map_err(|_| anyhow::anyhow!("stdin writer panicked"))?
+            .context("write stdin")?;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{program} exited with {}: {}", output.status, stderr.trim());
+    }
+    Ok(stdout)
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use eframe::egui::{self, Color32, Pos2};
+
+use super::chain::{ActionChain, Prepare, UiStep};
+use super::{Action, ActionKind, CaptureInfo, CaptureSource, Condition};
+use crate::App;
+use crate::capture::RgbaImage;
+use crate::mode::Mode;
+use crate::upload;
+
+impl App {
+    /// 在后台线程按顺序执行匹配条件的动作，结果记录到动作日志
+    ///
+    /// 写历史记录等准备工作也在后台线程上，在动作之前完成。
+    pub(crate) fn run_actions(
+        &mut self,
+        image: &RgbaImage,
+        info: &CaptureInfo,
+        pin_at: Pos2,
+        prepare: Prepare,
+    ) {
+        self.action_log.clear();
+        self.action_chains.push(ActionChain::start(
+            self.config.actions.clone(),
+            image.clone(),
+            info.clone(),
+            pin_at,
+            self.action_log.clone(),
+            prepare,
+        ));
+    }
+
+    /// 执行动作链交给界面的步骤，链结束后显示第一个错误
+    pub(crate) fn poll_actions(&mut self, ctx: &egui::Context) {
+        if self.action_chains.is_empty() {
+            return;
+        }
+        ctx.request_repaint_after(Duration::from_millis(100));
+
+        let mut chains = std::mem::take(&mut self.action_chains);
+        for chain in &chains {
+            while let Some(request) = chain.try_request() {
+                let result = self.run_ui_step(&request.step);
+                request.reply(result);
+            }
+        }
+        chains.retain_mut(|chain| match chain.try_finish() {
+            Some(error) => {
+                if error.is_some() {
+                    self.status = error;
+                }
+                false
+            }
+            None => true,
+        });
+        // 执行期间可能又开始了新的动作链
+        chains.append(&mut self.action_chains);
+        self.action_chains = chains;
+        if self.action_chains.is_empty() && self.exit_when_done && matches!(self.mode, Mode::Idle) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    fn run_ui_step(&mut self, step: &UiStep) -> anyhow::Result<String> {
+        match step {
+            UiStep::Copy { image, file } => self
+                .clipboard
+                .copy(&self.config.clipboard, image, file.as_deref())
+                .map(|()| "copied".to_string()),
+            UiStep::CopyText(url) => self.copy_link(url).map(|()| "copied".to_string()),
+            UiStep::Pin { image, at } => {
+                self.pins.pin(RgbaImage::clone(image), Some(*at));
+                Ok("pinned".to_string())
+            }
+            UiStep::Edit => match &self.last_project {
+                Some(project) => {
+                    // 截图流程结束后的下一帧再打开
+                    self.pending_open = Some(project.clone());
+                    Ok("reopened".to_string())
+                }
+                None => Err(anyhow::anyhow!("no editable project")),
+            },
+            UiStep::AddHistory(entry) => {
+                if let Some(history) = &mut self.history {
+                    history.insert(entry.clone());
+                    self.apply_history_retention();
+                }
+                Ok("saved".to_string())
+            }
+            UiStep::Saved(path) => {
+                self.last_capture_path = Some(path.clone());
+                Ok("saved".to_string())
+            }
+        }
+    }
+
+    /// 把上传得到的链接复制到剪贴板
+    fn copy_link(&mut self, url: &str) -> anyhow::Result<()> {
+        self.clipboard.copy_text(url)
+    }
+
+    /// 最近一次动作链的结果
+    pub(crate) fn action_log_ui(&self, ui: &mut egui::Ui) {
+        for report in self.action_log.reports() {
+            match &report.result {
+                Ok(message) => ui.label(format!("✔ {}: {message}", report.name)),
+                Err(e) => ui.colored_label(Color32::LIGHT_RED, format!("✖ {}: {e}", report.name)),
+            };
+        }
+        for chain in &self.action_chains {
+            if let Some(name) = chain.current() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("{name}…"));
+                });
+            }
+        }
+    }
+
+    /// 编辑动作链：启用、排序、参数和触发条件
+    pub(crate) fn actions_ui(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        let mut move_up = None;
+        let mut remove = None;
+        let count = self.config.actions.len();
+
+        for (i, action) in self.config.actions.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.checkbox(&mut action.enabled, "").changed();
+                    ui.label(format!("{}. {}", i + 1, action.kind.name()));
+                    if ui.add_enabled(i > 0, egui::Button::new("⏶")).clicked() {
+                        move_up = Some(i);
+                    }
+                    if ui
+                        .add_enabled(i + 1 < count, egui::Button::new("⏷"))
+                        .clicked()
+                    {
+                        move_up = Some(i + 1);
+                    }
+                    if ui.button("✖").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                egui::CollapsingHeader::new("Options")
+                    .id_salt("options")
+                    .show(ui, |ui| changed |= action_options_ui(ui, action));
+            });
+        }
+
+        if let Some(i) = move_up {
+            self.config.actions.swap(i - 1, i);
+            changed = true;
+        }
+        if let Some(i) = remove {
+            self.config.actions.remove(i);
+            changed = true;
+        }
+
+        ui.menu_button("Add action", |ui| {
+            for kind in ActionKind::templates() {
+                if ui.button(kind.name()).clicked() {
+                    self.config.actions.push(Action::new(kind));
+                    changed = true;
+                    ui.close();
+                }
+            }
+        });
+
+        if changed && let Err(e) = self.config.save() {
+            self.status = Some(format!("save config failed: {e}"));
+        }
+    }
+}
+
+/// 单个动作的参数和条件，返回是否有修改
+fn action_options_ui(ui: &mut egui::Ui, action: &mut Action) -> bool {
+    let mut changed = false;
+
+    match &mut action.kind {
+        ActionKind::Save { dir, name } => {
+            let mut dir_text = dir.display().to_string();
+            ui.horizontal(|ui| {
+                ui.label("Folder");
+                if ui.text_edit_singleline(&mut dir_text).changed() {
+                    *dir = dir_text.into();
+                    changed = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("File name");
+                changed |= ui.text_edit_singleline(name).changed();
+            });
+        }
+        ActionKind::Command {
+            program,
+            args,
+            stdin_png,
+        } => {
+            ui.horizontal(|ui| {
+                ui.label("Program");
+                changed |= ui.text_edit_singleline(program).changed();
+            });
+            // 每个参数单独编辑，参数里可以有空格
+            ui.label("Arguments")
+                .on_hover_text("{path} {width} {height} {timestamp} {title}");
+            let mut remove = None;
+            for (i, arg) in args.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    changed |= ui.text_edit_singleline(arg).changed();
+                    if ui.button("✖").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                args.remove(i);
+                changed = true;
+            }
+            if ui.button("Add argument").clicked() {
+                args.push(String::new());
+                changed = true;
+            }
+            changed |= ui.checkbox(stdin_png, "Pipe PNG to stdin").changed();
+        }
+        ActionKind::Upload { uploader } => changed |= upload::uploader_ui(ui, uploader),
+        ActionKind::Copy | ActionKind::Pin | ActionKind::Edit => {}
+    }
+
+    changed |= ui
+        .checkbox(&mut action.stop_on_failure, "Stop the chain if this fails")
+        .changed();
+    changed | condition_ui(ui, &mut action.condition)
+}
+
+fn condition_ui(ui: &mut egui::Ui, condition: &mut Condition) -> bool {
+    let mut changed = false;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Only for");
+        for source in CaptureSource::all() {
+            let mut on = condition.sources.contains(source);
+            if ui.checkbox(&mut on, source.name()).changed() {
+                condition.sources.retain(|s| s != source);
+                if on {
+                    condition.sources.push(*source);
+                }
+                changed = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Annotated");
+        for (value, label) in [(None, "Any"), (Some(true), "Yes"), (Some(false), "No")] {
+            changed |= ui
+                .selectable_value(&mut condition.annotated, value, label)
+                .changed();
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Window title contains");
+        changed |= ui
+            .text_edit_singleline(&mut condition.window_contains)
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Min size");
+        changed |= ui
+            .add(egui::DragValue::new(&mut condition.min_width))
+            .changed();
+        ui.label("×");
+        changed |= ui
+            .add(egui::DragValue::new(&mut condition.min_height))
+            .changed();
+    });
+    changed
+}
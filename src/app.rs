@@ -1,25 +1,80 @@
 use eframe::egui;
 use egui::Pos2;
 use egui::viewport::{ViewportCommand, WindowLevel};
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::capture::{RgbaImage, capture_region};
-use crate::clipboard;
-use crate::mode::Mode;
-use crate::tools::arrow::{ArrowDrawer, ArrowToolPanel};
+use crate::actions::{ActionChain, ActionKind, ActionLog, CaptureInfo, CaptureSource, Prepare};
+use crate::capture::{self, RgbaImage, capture_region};
+use crate::clipboard::{self, ClipboardService};
+use crate::config::{self, Config};
+use crate::document::{AnnotationDoc, PROJECT_EXTENSION, Project};
+use crate::export;
+use crate::history::{CaptureMeta, GalleryState, History, PendingHistory};
+use crate::hotkey::{HotkeyAction, HotkeyListener};
+use crate::ipc::Response;
+use crate::mode::{CaptureTarget, Mode};
+use crate::overlay::{AspectRatio, FrozenFrame, SizeEditor, px_rect_to_points};
+use crate::pin::PinManager;
+use crate::record::RecordSession;
+use crate::scroll::ScrollSession;
+use crate::tools::arrow::{ArrowDrawer, ArrowToolPanel, Rasterizer};
 
 #[derive(Default)]
 pub struct App {
     pub(crate) mode: Mode,
     pub(crate) screenshot: Option<RgbaImage>,
+    // 最近一次截图的可编辑项目（原始像素 + 标注）
+    pub(crate) last_project: Option<Project>,
+    pub(crate) project_path: String,
+    pub(crate) status: Option<String>,
+    // 启动时要打开的图像（`pxshot edit`）或要执行的截图（`pxshot capture`），以及结果的输出路径
+    pub(crate) pending_open: Option<Project>,
+    pending_capture: Option<(CaptureTarget, Duration)>,
+    edit_output: Option<PathBuf>,
+    pub(crate) exit_when_done: bool,
     pub(crate) texture: Option<egui::TextureHandle>,
     image_loaders_installed: bool,
+    // 选区宽高比与尺寸输入
+    pub(crate) aspect_ratio: AspectRatio,
+    pub(crate) size_editor: SizeEditor,
     // Arrow 工具面板
     pub(crate) arrow_panel: ArrowToolPanel,
     pub(crate) show_arrow_panel: bool,
     // Arrow 绘制器
     pub(crate) arrow_drawer: ArrowDrawer,
     pub(crate) arrow_mode_active: bool,
+    // 钉在屏幕上的截图
+    pub(crate) pins: PinManager,
+    // 编辑已有图像时的背景帧
+    pub(crate) frozen: Option<FrozenFrame>,
+    // 配置与截图历史
+    pub(crate) config: Config,
+    pub(crate) history: Option<History>,
+    pub(crate) gallery: GalleryState,
+    // 定时截图
+    pub(crate) delay_secs: u32,
+    pub(crate) delay_full_screen: bool,
+    pub(crate) countdown_shown: bool,
+    // 进行中的长截图
+    pub(crate) scroll: Option<ScrollSession>,
+    // 进行中的录屏
+    pub(crate) recording: Option<RecordSession>,
+    // 常驻模式下的全局快捷键
+    hotkeys: Option<HotkeyListener>,
+    // 控制 socket，以及等待截图结果的命令
+    #[cfg(unix)]
+    pub(crate) ipc: Option<crate::ipc::IpcServer>,
+    pub(crate) ipc_reply: Option<std::sync::mpsc::Sender<Response>>,
+    pub(crate) last_capture_path: Option<PathBuf>,
+    // 截图后动作链的执行结果
+    pub(crate) action_log: ActionLog,
+    // 后台执行的动作链
+    pub(crate) action_chains: Vec<ActionChain>,
+    // 常驻剪贴板，退出后交给剪贴板管理器
+    pub(crate) clipboard: ClipboardService,
+    // 这次按下 Ctrl+V 时是否已经粘贴过
+    paste_handled: bool,
 }
 
 fn image_to_texture(ctx: &egui::Context, img: &RgbaImage) -> egui::TextureHandle {
@@ -30,9 +85,168 @@ fn image_to_texture(ctx: &egui::Context, img: &RgbaImage) -> egui::TextureHandle
 }
 
 impl App {
-    const CAPTURE_DELAY_MS: u64 = 300;
+    /// 隐藏窗口到截图之间的等待时间
+    pub(crate) const CAPTURE_DELAY_MS: u64 = 300;
+    /// 主窗口的默认大小
+    pub const WINDOW_SIZE: [f32; 2] = [520.0, 320.0];
+
+    /// 读取配置并打开截图历史
+    pub fn new() -> Self {
+        let config = Config::load();
+        let history = if config.history.enabled {
+            History::default_dir().and_then(|dir| {
+                History::open(dir)
+                    .map_err(|e| eprintln!("open history failed: {e}"))
+                    .ok()
+            })
+        } else {
+            None
+        };
+
+        let mut app = Self {
+            config,
+            history,
+            delay_secs: 3,
+            ..Default::default()
+        };
+        app.apply_history_retention();
+        app.arrow_panel.load_settings(&app.config.arrow);
+        app
+    }
+
+    /// 启动后直接编辑图像，完成或取消后退出程序
+    pub fn open_on_start(&mut self, project: Project, output: Option<PathBuf>) {
+        self.pending_open = Some(project);
+        self.edit_output = output;
+        self.exit_when_done = true;
+    }
+
+    /// 启动后延迟截图，完成或取消后退出程序
+    pub fn capture_on_start(
+        &mut self,
+        target: CaptureTarget,
+        delay: Duration,
+        output: Option<PathBuf>,
+    ) {
+        self.pending_capture = Some((target, delay));
+        self.edit_output = output;
+        self.exit_when_done = true;
+    }
+
+    /// 常驻模式：注册全局快捷键，窗口保持隐藏直到被触发
+    pub fn start_daemon(&mut self) -> anyhow::Result<()> {
+        let bindings = self.config.hotkeys.bindings()?;
+        if bindings.is_empty() {
+            anyhow::bail!("no hotkeys configured");
+        }
+        let summary: Vec<String> = bindings
+            .iter()
+            .map(|(hotkey, action)| format!("{hotkey}: {action:?}"))
+            .collect();
+        self.hotkeys = Some(HotkeyListener::start(bindings)?);
+        println!("pxshot daemon listening ({})", summary.join(", "));
+        Ok(())
+    }
+
+    /// 让后台线程（快捷键等）能唤醒界面
+    pub fn attach(&self, ctx: &egui::Context) {
+        if let Some(hotkeys) = &self.hotkeys {
+            hotkeys.set_waker(ctx);
+        }
+        #[cfg(unix)]
+        if let Some(ipc) = &self.ipc {
+            ipc.set_waker(ctx);
+        }
+    }
+
+    /// 截图结束后回复等待中的控制命令
+    pub(crate) fn reply_ipc(&mut self, response: Response) {
+        if let Some(reply) = self.ipc_reply.take() {
+            let _ = reply.send(response);
+        }
+    }
+
+    fn handle_hotkey(&mut self, ctx: &egui::Context, action: HotkeyAction) {
+        // 录屏和长截图时热键用来结束，控制窗口可能因为放不下而隐藏
+        match self.mode {
+            Mode::Recording { .. } => {
+                if let Some(session) = &self.recording {
+                    session.stop();
+                }
+                return;
+            }
+            Mode::Scrolling { .. } => {
+                if let Some(session) = &self.scroll {
+                    session.stop();
+                }
+                return;
+            }
+            _ => {}
+        }
+        match action {
+            HotkeyAction::Region => self.start_region_capture(ctx),
+            HotkeyAction::FullScreen => {
+                self.start_delayed_capture(ctx, CaptureTarget::FullScreen, Duration::ZERO);
+            }
+            HotkeyAction::LastRegion => self.repeat_last_region(ctx),
+        }
+    }
+
+    /// 打开图像文件、项目文件或内存中的文件内容
+    fn open_dropped(&mut self, ctx: &egui::Context, file: &egui::DroppedFile) {
+        let project = match (&file.path, &file.bytes) {
+            (Some(path), _) => Project::open(path),
+            (None, Some(bytes)) => Project::open_bytes(bytes),
+            (None, None) => return,
+        };
+        match project {
+            Ok(project) => {
+                self.status = None;
+                self.edit_image(ctx, project.image, &project.annotations);
+            }
+            Err(e) => self.status = Some(format!("open {} failed: {e:#}", file.name)),
+        }
+    }
+
+    fn paste_from_clipboard(&mut self, ctx: &egui::Context) {
+        match clipboard::paste_image() {
+            Ok(image) => {
+                self.status = None;
+                self.edit_image(ctx, image, &AnnotationDoc::default());
+            }
+            Err(e) => self.status = Some(format!("no image in clipboard: {e}")),
+        }
+    }
+
+    /// 在遮罩编辑器中打开已有图像，并还原其标注
+    pub fn edit_image(&mut self, ctx: &egui::Context, image: RgbaImage, doc: &AnnotationDoc) {
+        let ppp = ctx.pixels_per_point();
+        let frozen = FrozenFrame::new(image);
+        let rect = frozen.rect(ppp);
+
+        self.arrow_drawer.load(doc.arrows_at(rect.min, ppp));
+        self.frozen = Some(frozen);
+        self.enter_overlay(ctx);
+        self.mode = Mode::Selected {
+            rect,
+            dragging: None,
+        };
+    }
+
+    /// 打开遮罩开始框选
+    pub(crate) fn start_region_capture(&mut self, ctx: &egui::Context) {
+        self.screenshot = None;
+        self.texture = None;
+        self.enter_overlay(ctx);
+        self.mode = Mode::Selecting {
+            start: Pos2::ZERO,
+            end: Pos2::ZERO,
+        };
+    }
 
     pub fn enter_overlay(&mut self, ctx: &egui::Context) {
+        self.size_editor = SizeEditor::default();
+        ctx.send_viewport_cmd(ViewportCommand::Visible(true));
         ctx.send_viewport_cmd(ViewportCommand::Decorations(false));
         ctx.send_viewport_cmd(ViewportCommand::Fullscreen(true));
         ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::AlwaysOnTop));
@@ -50,6 +264,11 @@ impl App {
 
     pub fn cancel_overlay(&mut self, ctx: &egui::Context) {
         self.mode = Mode::Idle;
+        self.frozen = None;
+        self.reply_ipc(Response::error("cancelled"));
+        if self.exit_when_done {
+            ctx.send_viewport_cmd(ViewportCommand::Close);
+        }
         self.exit_overlay(ctx);
     }
 
@@ -58,15 +277,51 @@ impl App {
             ui.heading("pxshot");
 
             if ui.button("Region screenshot").clicked() {
-                self.screenshot = None;
-                self.texture = None;
-                self.enter_overlay(ctx);
-                self.mode = Mode::Selecting {
-                    start: Pos2::new(0.0, 0.0),
-                    end: Pos2::new(0.0, 0.0),
-                };
+                self.start_region_capture(ctx);
             }
 
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut self.delay_secs)
+                        .range(1..=60)
+                        .prefix("in ")
+                        .suffix(" s"),
+                );
+                ui.selectable_value(&mut self.delay_full_screen, false, "Region");
+                ui.selectable_value(&mut self.delay_full_screen, true, "Full screen");
+                if ui.button("Delayed screenshot").clicked() {
+                    let target = if self.delay_full_screen {
+                        CaptureTarget::FullScreen
+                    } else {
+                        CaptureTarget::Interactive
+                    };
+                    let delay = Duration::from_secs(self.delay_secs.into());
+                    self.start_delayed_capture(ctx, target, delay);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let last = self.last_region();
+                ui.add_enabled_ui(last.is_some(), |ui| {
+                    let repeat = ui
+                        .button("Repeat last region")
+                        .on_hover_text("Ctrl+R")
+                        .clicked();
+                    // 在输入框里打字时不响应快捷键
+                    let shortcut = !ctx.wants_keyboard_input()
+                        && ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::R));
+                    if repeat || shortcut {
+                        self.repeat_last_region(ctx);
+                    }
+                    if ui.button("Adjust last region").clicked() {
+                        self.adjust_last_region(ctx);
+                    }
+                });
+                if let Some(r) = last {
+                    ui.weak(format!("{}×{} at {},{}", r.w, r.h, r.x, r.y));
+                }
+            });
+
             if let Some(img) = &self.screenshot
                 && self.texture.is_none()
             {
@@ -80,14 +335,398 @@ impl App {
                 let display_size = tex.size_vec2() / ppp;
                 ui.image((tex.id(), display_size));
 
-                if ui.button("Save screenshot.png").clicked()
-                    && let Some(img) = &self.screenshot
-                {
-                    let _ = img.save("screenshot.png");
+                ui.horizontal(|ui| {
+                    if ui.button("Save screenshot.png").clicked()
+                        && let Some(img) = &self.screenshot
+                    {
+                        let _ = img.save("screenshot.png");
+                    }
+                    if ui.button("Pin").clicked()
+                        && let Some(img) = &self.screenshot
+                    {
+                        self.pins.pin(img.clone(), None);
+                    }
+                    if let Some(project) = &self.last_project
+                        && ui
+                            .button(format!("Save screenshot.{PROJECT_EXTENSION}"))
+                            .clicked()
+                    {
+                        let path = format!("screenshot.{PROJECT_EXTENSION}");
+                        self.status = Some(match project.save(&path) {
+                            Ok(()) => format!("saved {path}"),
+                            Err(e) => format!("save project failed: {e}"),
+                        });
+                    }
+                    if let Some(project) = &self.last_project
+                        && ui.button("Save screenshot.svg").clicked()
+                    {
+                        self.status = Some(match export::save_svg(project, "screenshot.svg") {
+                            Ok(()) => "saved screenshot.svg".to_string(),
+                            Err(e) => format!("export svg failed: {e}"),
+                        });
+                    }
+                });
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Paste image").clicked() {
+                    self.paste_from_clipboard(ctx);
+                }
+                ui.label("or drop an image / project file here");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Project");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.project_path)
+                        .hint_text(format!("path/to/file.{PROJECT_EXTENSION}")),
+                );
+                if ui.button("Open").clicked() {
+                    match Project::load(self.project_path.trim()) {
+                        Ok(project) => {
+                            self.status = None;
+                            self.edit_image(ctx, project.image, &project.annotations);
+                        }
+                        Err(e) => self.status = Some(format!("open project failed: {e:#}")),
+                    }
                 }
+            });
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+            self.action_log_ui(ui);
+
+            // 拖放文件或 Ctrl+V 粘贴图像到编辑器
+            let (dropped, hovering) = ctx.input(|i| {
+                (
+                    i.raw.dropped_files.first().cloned(),
+                    !i.raw.hovered_files.is_empty(),
+                )
+            });
+            let pasted = ctx.input(|i| self.paste_shortcut(&i.events));
+            if hovering {
+                ui.painter().rect_stroke(
+                    ui.max_rect(),
+                    4.0,
+                    egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 122, 255)),
+                    egui::StrokeKind::Inside,
+                );
+            }
+            if let Some(file) = dropped {
+                self.open_dropped(ctx, &file);
+            } else if pasted && !ctx.wants_keyboard_input() {
+                self.paste_from_clipboard(ctx);
+            }
+
+            ui.separator();
+            egui::CollapsingHeader::new("History")
+                .default_open(true)
+                .show(ui, |ui| self.history_ui(ui, ctx));
+            egui::CollapsingHeader::new("After capture").show(ui, |ui| {
+                self.actions_ui(ui);
+                self.rasterizer_ui(ui);
+            });
+            egui::CollapsingHeader::new("Recording").show(ui, |ui| self.record_settings_ui(ui));
+
+            if !self.pins.is_empty() {
+                ui.separator();
+                ui.label("Pins");
+                for pin in self.pins.pins_mut() {
+                    ui.horizontal(|ui| {
+                        ui.label(pin.title());
+                        ui.checkbox(&mut pin.click_through, "Click-through");
+                        ui.add(egui::Slider::new(&mut pin.opacity, 0.1..=1.0).text("Opacity"));
+                        if ui.button("Close").clicked() {
+                            pin.open = false;
+                        }
+                    });
+                }
+            }
+        });
+    }
+}
+
+impl App {
+    /// 保留可编辑的项目，并为历史记录分配目录；文件由动作链在后台写入
+    fn record_history(
+        &mut self,
+        img: &RgbaImage,
+        original: &RgbaImage,
+        rect_px: capture::RectPx,
+        rect_points: egui::Rect,
+        ppp: f32,
+    ) -> Option<PendingHistory> {
+        let doc = AnnotationDoc::from_arrows(&self.arrow_drawer.arrows, rect_points.min, ppp);
+        self.last_project = Some(Project::new(original.clone(), doc.clone()));
+
+        let dir = match self.history.as_ref()?.reserve() {
+            Ok(dir) => dir,
+            Err(e) => {
+                eprintln!("save history failed: {e}");
+                return None;
+            }
+        };
+        Some(PendingHistory {
+            dir,
+            original: original.clone(),
+            annotations: doc,
+            meta: CaptureMeta::new(rect_px, img),
+        })
+    }
+}
+
+impl App {
+    /// 上一次确认的选区
+    pub fn last_region(&self) -> Option<capture::RectPx> {
+        self.config.last_region.as_ref().map(|r| r.rect)
+    }
+
+    /// 记住确认的选区，供“重复上次选区”使用
+    pub(crate) fn remember_region(&mut self, rect: capture::RectPx) {
+        // 编辑已有图像时选区不是屏幕坐标
+        if !self.frozen.as_ref().is_none_or(|f| f.is_screen) {
+            return;
+        }
+        let region = capture::LastRegion {
+            rect,
+            monitor: capture::monitor_name(rect),
+        };
+        if self.config.last_region.as_ref() == Some(&region) {
+            return;
+        }
+        self.config.last_region = Some(region);
+        if let Err(e) = self.config.save() {
+            eprintln!("save config failed: {e}");
+        }
+    }
+
+    /// 直接截取上次的选区
+    pub(crate) fn repeat_last_region(&mut self, ctx: &egui::Context) {
+        let Some(region) = self.config.last_region.clone() else {
+            return;
+        };
+        self.status = self.check_last_monitor(&region);
+        self.start_delayed_capture(ctx, CaptureTarget::Region(region.rect), Duration::ZERO);
+    }
+
+    /// 打开遮罩并预先选中上次的选区，便于微调
+    pub(crate) fn adjust_last_region(&mut self, ctx: &egui::Context) {
+        let Some(region) = self.config.last_region.clone() else {
+            return;
+        };
+        self.status = self.check_last_monitor(&region);
+        self.screenshot = None;
+        self.texture = None;
+        self.enter_overlay(ctx);
+        self.mode = Mode::Selected {
+            rect: px_rect_to_points(region.rect, ctx.pixels_per_point()),
+            dragging: None,
+        };
+    }
+
+    /// 显示器布局变化时给出提示
+    fn check_last_monitor(&self, region: &capture::LastRegion) -> Option<String> {
+        let monitor = region.monitor.as_ref()?;
+        match capture::monitor_name(region.rect) {
+            Some(current) if &current == monitor => None,
+            current => Some(format!(
+                "last region was on {monitor}, now on {}",
+                current.as_deref().unwrap_or("no monitor")
+            )),
+        }
+    }
+
+    /// 截取整屏，并在冻结帧上打开选区遮罩
+    fn open_captured_screen(&mut self, ctx: &egui::Context) {
+        match capture::capture_screen() {
+            Ok(image) => {
+                self.frozen = Some(FrozenFrame::from_screen(image));
+                self.enter_overlay(ctx);
+                self.mode = Mode::Selecting {
+                    start: Pos2::ZERO,
+                    end: Pos2::ZERO,
+                };
+            }
+            Err(e) => {
+                eprintln!("capture failed: {e:?}");
+                self.reset_after_capture(ctx);
+            }
+        }
+    }
+
+    /// 截取（或从冻结帧裁剪）选区，渲染标注后复制、钉图、保存
+    fn finish_capture(
+        &mut self,
+        ctx: &egui::Context,
+        rect_px: capture::RectPx,
+        rect_points: Option<egui::Rect>,
+        source: CaptureSource,
+        pin: bool,
+    ) {
+        // 从已编辑的图片里截取时视为编辑结果
+        let source = match &self.frozen {
+            Some(frozen) if !frozen.is_screen => CaptureSource::Edit,
+            _ => source,
+        };
+        let captured = match &self.frozen {
+            Some(frozen) => Ok(frozen.crop(rect_px)),
+            None => capture_region(rect_px),
+        };
+
+        match captured {
+            Ok(img) => self.deliver_capture(ctx, img, rect_px, rect_points, source, pin),
+            Err(e) => eprintln!("capture failed: {e:?}"),
+        }
+
+        self.reset_after_capture(ctx);
+    }
+
+    /// 渲染标注，然后复制、钉图、记录历史并保存输出
+    pub(crate) fn deliver_capture(
+        &mut self,
+        ctx: &egui::Context,
+        mut img: RgbaImage,
+        rect_px: capture::RectPx,
+        rect_points: Option<egui::Rect>,
+        source: CaptureSource,
+        pin: bool,
+    ) {
+        let ppp = ctx.pixels_per_point();
+        let rect_points = rect_points.unwrap_or_else(|| px_rect_to_points(rect_px, ppp));
+        let original = img.clone();
+        let annotated = self.arrow_drawer.has_arrows();
+
+        // 渲染箭头到图像上
+        if self.arrow_drawer.has_arrows() {
+            // 选区左上角的逻辑坐标
+            let offset_x = rect_points.min.x;
+            let offset_y = rect_points.min.y;
+            self.arrow_drawer.render_all_to_image(
+                &mut img,
+                offset_x,
+                offset_y,
+                ppp,
+                self.config.rasterizer,
+            );
+        }
+
+        let history = self.record_history(&img, &original, rect_px, rect_points, ppp);
+
+        // 外部命令需要文件路径，没有历史记录时写入临时文件
+        let needs_file = self
+            .config
+            .actions
+            .iter()
+            .any(|a| a.enabled && matches!(a.kind, ActionKind::Command { .. }));
+        let reply = self.ipc_reply.take();
+        let prepare = Prepare {
+            // 屏幕截图时附带显示器和窗口标题
+            probe: self
+                .frozen
+                .as_ref()
+                .is_none_or(|f| f.is_screen)
+                .then_some(rect_px),
+            file: (history.is_none() && (needs_file || reply.is_some()))
+                .then(Self::temp_capture_path),
+            history,
+            reply,
+        };
+        let info = CaptureInfo {
+            source,
+            annotated,
+            width: img.width(),
+            height: img.height(),
+            window_title: None,
+            timestamp: CaptureMeta::new(rect_px, &img).timestamp,
+            path: None,
+        };
+        self.run_actions(&img, &info, rect_points.min, prepare);
+        if pin {
+            // 钉图窗口放在原选区的位置
+            self.pins.pin(img.clone(), Some(rect_points.min));
+        }
+        if let Some(path) = self.edit_output.take()
+            && let Some(project) = &self.last_project
+            && let Err(e) = export::save_output(&path, &img, project)
+        {
+            eprintln!("save {} failed: {e}", path.display());
+        }
+        self.screenshot = Some(img);
+        self.texture = None;
+    }
+
+    /// 选择导出标注的渲染后端，只有一个可用时不显示
+    fn rasterizer_ui(&mut self, ui: &mut egui::Ui) {
+        if Rasterizer::available().len() < 2 {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Annotation renderer");
+            let mut changed = false;
+            for &rasterizer in Rasterizer::available() {
+                changed |= ui
+                    .selectable_value(&mut self.config.rasterizer, rasterizer, rasterizer.name())
+                    .changed();
+            }
+            if changed && let Err(e) = self.config.save() {
+                self.status = Some(format!("save config failed: {e}"));
             }
         });
     }
+
+    /// 是否按下了粘贴快捷键
+    ///
+    /// egui-winit 只在剪贴板里有文字时才发出 `Paste`，并且吞掉 Ctrl+V 的按下事件，
+    /// 剪贴板里只有图像时只能看到 V 的松开事件；按下时已经粘贴过的，松开时不再重复。
+    fn paste_shortcut(&mut self, events: &[egui::Event]) -> bool {
+        let mut pasted = false;
+        for event in events {
+            let (pressed, command) = match event {
+                egui::Event::Paste(_) => (true, true),
+                egui::Event::Key {
+                    key: egui::Key::V,
+                    pressed,
+                    modifiers,
+                    ..
+                } => (*pressed, modifiers.command),
+                _ => continue,
+            };
+            if !pressed {
+                pasted |= command && !self.paste_handled;
+                self.paste_handled = false;
+            } else if command {
+                pasted |= !self.paste_handled;
+                self.paste_handled = true;
+            }
+        }
+        pasted
+    }
+
+    /// 没有历史记录时保存截图的临时文件，放在本次运行的临时目录里
+    fn temp_capture_path() -> PathBuf {
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        config::temp_dir().join(format!("capture-{millis}.png"))
+    }
+
+    /// 清理标注和遮罩状态，回到空闲模式
+    pub(crate) fn reset_after_capture(&mut self, ctx: &egui::Context) {
+        self.reply_ipc(Response::error("capture failed"));
+        // 清理箭头状态
+        self.arrow_drawer.clear();
+        self.arrow_mode_active = false;
+        self.show_arrow_panel = false;
+        self.frozen = None;
+
+        self.mode = Mode::Idle;
+        self.exit_overlay(ctx);
+        // 动作链还在执行时等它结束再退出
+        if self.exit_when_done && self.action_chains.is_empty() {
+            ctx.send_viewport_cmd(ViewportCommand::Close);
+        }
+    }
 }
 
 impl eframe::App for App {
@@ -96,6 +735,11 @@ impl eframe::App for App {
         [0.0, 0.0, 0.0, 0.0]
     }
 
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.clipboard.shutdown();
+        let _ = std::fs::remove_dir_all(config::temp_dir());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // loaders 只装一次
         if !self.image_loaders_installed {
@@ -103,51 +747,73 @@ impl eframe::App for App {
             self.image_loaders_installed = true;
         }
 
+        // 钉图窗口在任何模式下都显示
+        self.pins
+            .show(ctx, &mut self.clipboard, &self.config.clipboard);
+
+        if let Some(project) = self.pending_open.take() {
+            self.edit_image(ctx, project.image, &project.annotations);
+        }
+        if let Some((target, delay)) = self.pending_capture.take() {
+            self.start_delayed_capture(ctx, target, delay);
+        }
+        #[cfg(unix)]
+        self.poll_ipc(ctx);
+        self.poll_actions(ctx);
+        // 正在截图时忽略快捷键，录屏和长截图时用来结束
+        if matches!(
+            self.mode,
+            Mode::Idle | Mode::Recording { .. } | Mode::Scrolling { .. }
+        ) && let Some(action) = self.hotkeys.as_ref().and_then(HotkeyListener::try_recv)
+        {
+            self.handle_hotkey(ctx, action);
+        }
+
         match self.mode {
             Mode::Idle => self.idle_ui(ctx),
             Mode::Selecting { .. } => self.overlay_selecting_ui(ctx),
             Mode::Selected { .. } => self.overlay_selected_ui(ctx),
+            Mode::Scrolling { rect_points } => self.scrolling_ui(ctx, rect_points),
+            Mode::Recording { rect_points } => self.recording_ui(ctx, rect_points),
             Mode::PendingCapture {
-                rect_px,
+                target,
                 rect_points,
-                hidden_at,
+                due,
+                pin,
             } => {
                 // 驱动帧循环
                 ctx.request_repaint_after(Duration::from_millis(16));
 
-                if hidden_at.elapsed() < Duration::from_millis(Self::CAPTURE_DELAY_MS) {
+                let remaining = due.saturating_duration_since(Instant::now());
+                if remaining > Duration::from_millis(Self::CAPTURE_DELAY_MS) {
+                    self.countdown_ui(ctx, remaining);
+                    return;
+                }
+                // 最后一段时间留给窗口隐藏，避免倒计时出现在截图里
+                self.hide_countdown(ctx);
+                if !remaining.is_zero() {
                     return;
                 }
 
-                match capture_region(rect_px) {
-                    Ok(mut img) => {
-                        // 渲染箭头到图像上
-                        if self.arrow_drawer.has_arrows() {
-                            let ppp = ctx.pixels_per_point();
-                            // 选区左上角的逻辑坐标
-                            let offset_x = rect_points.min.x;
-                            let offset_y = rect_points.min.y;
-                            self.arrow_drawer
-                                .render_all_to_image(&mut img, offset_x, offset_y, ppp);
-                        }
-
-                        if let Err(e) = clipboard::copy_image(&img) {
-                            eprintln!("copy to clipboard failed: {e}");
+                match target {
+                    CaptureTarget::Interactive => self.open_captured_screen(ctx),
+                    CaptureTarget::FullScreen => match capture::screen_rect() {
+                        Ok(rect_px) => self.finish_capture(
+                            ctx,
+                            rect_px,
+                            rect_points,
+                            CaptureSource::FullScreen,
+                            pin,
+                        ),
+                        Err(e) => {
+                            eprintln!("capture failed: {e:?}");
+                            self.reset_after_capture(ctx);
                         }
-                        self.screenshot = Some(img);
-                        self.texture = None;
+                    },
+                    CaptureTarget::Region(rect_px) => {
+                        self.finish_capture(ctx, rect_px, rect_points, CaptureSource::Region, pin)
                     }
-                    Err(e) => eprintln!("capture failed: {e:?}"),
                 }
-
-                // 清理箭头状态
-                self.arrow_drawer.arrows.clear();
-                self.arrow_drawer.state = crate::tools::arrow::DrawState::Idle;
-                self.arrow_mode_active = false;
-                self.show_arrow_panel = false;
-
-                self.mode = Mode::Idle;
-                self.exit_overlay(ctx);
             }
         }
     }
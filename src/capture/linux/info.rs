@@ -0,0 +1,121 @@
+use anyhow::Result;
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, MapState, Window};
+use x11rb::rust_connection::RustConnection;
+
+use crate::capture::RectPx;
+
+/// 整个根窗口（所有显示器）的范围
+pub fn screen_rect_x11() -> Result<RectPx> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let screen = &conn.setup().roots[screen_num];
+    Ok(RectPx {
+        x: 0,
+        y: 0,
+        w: screen.width_in_pixels as u32,
+        h: screen.height_in_pixels as u32,
+    })
+}
+
+/// 查找包含选区中心的显示器名称
+pub fn monitor_name_x11(r: RectPx) -> Result<Option<String>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+
+    let cx = (r.x + r.w / 2) as i32;
+    let cy = (r.y + r.h / 2) as i32;
+
+    let reply = conn.randr_get_monitors(root, true)?.reply()?;
+    for m in reply.monitors {
+        let (x, y) = (m.x as i32, m.y as i32);
+        if cx >= x && cx < x + m.width as i32 && cy >= y && cy < y + m.height as i32 {
+            let name = conn.get_atom_name(m.name)?.reply()?;
+            return Ok(Some(String::from_utf8_lossy(&name.name).into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// 查找屏幕坐标处最上层窗口的标题
+pub fn window_title_at_x11(x: i32, y: i32) -> Result<Option<String>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+
+    // query_tree 按从下到上的叠放顺序返回子窗口；先发出所有请求再读回复，避免逐个往返
+    let tree = conn.query_tree(root)?.reply()?;
+    let cookies = tree
+        .children
+        .iter()
+        .rev()
+        .map(|&win| {
+            Ok((
+                win,
+                conn.get_window_attributes(win)?,
+                conn.get_geometry(win)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let atoms = NameAtoms::new(&conn)?;
+    for (win, attrs, geom) in cookies {
+        let (attrs, geom) = (attrs.reply()?, geom.reply()?);
+        if attrs.map_state != MapState::VIEWABLE {
+            continue;
+        }
+        let (wx, wy) = (geom.x as i32, geom.y as i32);
+        if x >= wx && x < wx + geom.width as i32 && y >= wy && y < wy + geom.height as i32 {
+            return window_name(&conn, &atoms, win, 3);
+        }
+    }
+    Ok(None)
+}
+
+/// 读取窗口名称用到的原子
+struct NameAtoms {
+    net_wm_name: Atom,
+    utf8: Atom,
+}
+
+impl NameAtoms {
+    fn new(conn: &RustConnection) -> Result<Self> {
+        let net_wm_name = conn.intern_atom(true, b"_NET_WM_NAME")?;
+        let utf8 = conn.intern_atom(true, b"UTF8_STRING")?;
+        Ok(Self {
+            net_wm_name: net_wm_name.reply()?.atom,
+            utf8: utf8.reply()?.atom,
+        })
+    }
+}
+
+/// 读取窗口名称；窗口管理器的装饰框没有名称时向下查找子窗口
+fn window_name(
+    conn: &RustConnection,
+    atoms: &NameAtoms,
+    win: Window,
+    depth: u32,
+) -> Result<Option<String>> {
+    let prop = conn
+        .get_property(false, win, atoms.net_wm_name, atoms.utf8, 0, 1024)?
+        .reply()?;
+    if !prop.value.is_empty() {
+        return Ok(Some(String::from_utf8_lossy(&prop.value).into_owned()));
+    }
+
+    let prop = conn
+        .get_property(false, win, AtomEnum::WM_NAME, AtomEnum::STRING, 0, 1024)?
+        .reply()?;
+    if !prop.value.is_empty() {
+        return Ok(Some(String::from_utf8_lossy(&prop.value).into_owned()));
+    }
+
+    if depth == 0 {
+        return Ok(None);
+    }
+    let tree = conn.query_tree(win)?.reply()?;
+    for &child in tree.children.iter().rev() {
+        if let Some(name) = window_name(conn, atoms, child, depth - 1)? {
+            return Ok(Some(name));
+        }
+    }
+    Ok(None)
+}
@@ -1,9 +1,32 @@
 use anyhow::Result;
 
-use crate::capture::{RectPx, RgbaImage};
+use crate::capture::{CursorImage, RectPx, RgbaImage};
 
+mod info;
 mod x11;
 
+pub use x11::Grabber;
+
 pub fn capture_region(rect: RectPx) -> Result<RgbaImage> {
     x11::capture_region_x11(rect)
 }
+
+pub fn cursor_image() -> Result<CursorImage> {
+    x11::cursor_image_x11()
+}
+
+pub fn scroll_at(x: i32, y: i32, clicks: u32) -> Result<()> {
+    x11::scroll_x11(x, y, clicks)
+}
+
+pub fn screen_rect() -> Result<RectPx> {
+    info::screen_rect_x11()
+}
+
+pub fn monitor_name(rect: RectPx) -> Result<Option<String>> {
+    info::monitor_name_x11(rect)
+}
+
+pub fn window_title_at(x: i32, y: i32) -> Result<Option<String>> {
+    info::window_title_at_x11(x, y)
+}
@@ -1,36 +1,152 @@
+use std::cell::OnceCell;
+
 use anyhow::Result;
 use image::{ImageBuffer, Rgba};
+use x11rb::CURRENT_TIME;
 use x11rb::connection::Connection;
-use x11rb::protocol::xproto::{ConnectionExt, ImageFormat};
+use x11rb::protocol::xfixes::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, ConnectionExt, ImageFormat, MOTION_NOTIFY_EVENT,
+    Window,
+};
+use x11rb::protocol::xtest::ConnectionExt as _;
+use x11rb::rust_connection::RustConnection;
 
-use crate::capture::{RectPx, RgbaImage};
+use crate::capture::{CursorImage, RectPx, RgbaImage};
 
 pub fn capture_region_x11(r: RectPx) -> Result<RgbaImage> {
-    let (conn, screen_num) = x11rb::connect(None)?;
-    let screen = &conn.setup().roots[screen_num];
-    let root = screen.root;
+    Grabber::new()?.capture_region(r)
+}
 
-    let reply = conn
-        .get_image(
-            ImageFormat::Z_PIXMAP,
+/// 用 XFixes 读取当前鼠标指针的图像
+pub fn cursor_image_x11() -> Result<CursorImage> {
+    Grabber::new()?.cursor_image()
+}
+
+/// 保持一个 X 连接，连续截图时不必每次重新连接
+pub struct Grabber {
+    conn: RustConnection,
+    root: Window,
+    xfixes: OnceCell<bool>, // 服务器支持 XFixes 4.0，第一次读指针时协商
+}
+
+impl Grabber {
+    pub fn new() -> Result<Self> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        Ok(Self {
+            conn,
             root,
-            r.x as i16,
-            r.y as i16,
-            r.w as u16,
-            r.h as u16,
-            u32::MAX,
-        )?
-        .reply()?;
-
-    // 常见桌面：每像素 4 字节 B,G,R,X
-    let mut out = Vec::with_capacity((r.w * r.h * 4) as usize);
-    for px in reply.data.chunks_exact(4) {
-        let b = px[0];
-        let g = px[1];
-        let rr = px[2];
-        out.extend_from_slice(&[rr, g, b, 255]);
+            xfixes: OnceCell::new(),
+        })
+    }
+
+    pub fn capture_region(&self, r: RectPx) -> Result<RgbaImage> {
+        let reply = self
+            .conn
+            .get_image(
+                ImageFormat::Z_PIXMAP,
+                self.root,
+                r.x as i16,
+                r.y as i16,
+                r.w as u16,
+                r.h as u16,
+                u32::MAX,
+            )?
+            .reply()?;
+
+        // 常见桌面：每像素 4 字节 B,G,R,X
+        let mut out = Vec::with_capacity((r.w * r.h * 4) as usize);
+        for px in reply.data.chunks_exact(4) {
+            let b = px[0];
+            let g = px[1];
+            let rr = px[2];
+            out.extend_from_slice(&[rr, g, b, 255]);
+        }
+
+        ImageBuffer::<Rgba<u8>, _>::from_raw(r.w, r.h, out)
+            .ok_or_else(|| anyhow::anyhow!("bad buffer size"))
     }
 
-    ImageBuffer::<Rgba<u8>, _>::from_raw(r.w, r.h, out)
-        .ok_or_else(|| anyhow::anyhow!("bad buffer size"))
+    /// 当前鼠标指针的图像和位置
+    pub fn cursor_image(&self) -> Result<CursorImage> {
+        // XFixes 要先协商版本才能使用
+        let xfixes = self.xfixes.get_or_init(|| {
+            self.conn
+                .xfixes_query_version(4, 0)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some()
+        });
+        if !xfixes {
+            anyhow::bail!("XFixes is not available");
+        }
+        let reply = self.conn.xfixes_get_cursor_image()?.reply()?;
+
+        // 每个像素是预乘 alpha 的 ARGB
+        let mut out = Vec::with_capacity(reply.cursor_image.len() * 4);
+        for argb in &reply.cursor_image {
+            let [b, g, r, a] = argb.to_le_bytes();
+            let unmul = |c: u8| {
+                if a == 0 {
+                    0
+                } else {
+                    (c as u32 * 255 / a as u32).min(255) as u8
+                }
+            };
+            out.extend_from_slice(&[unmul(r), unmul(g), unmul(b), a]);
+        }
+        let image = ImageBuffer::from_raw(reply.width.into(), reply.height.into(), out)
+            .ok_or_else(|| anyhow::anyhow!("bad cursor size"))?;
+
+        Ok(CursorImage {
+            x: i32::from(reply.x) - i32::from(reply.xhot),
+            y: i32::from(reply.y) - i32::from(reply.yhot),
+            image,
+        })
+    }
+}
+
+/// 用 XTest 在 (x, y) 处向下滚动滚轮，然后把指针移回原处
+pub fn scroll_x11(x: i32, y: i32, clicks: u32) -> Result<()> {
+    // 滚轮向下对应 5 号按键
+    const WHEEL_DOWN: u8 = 5;
+
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+    let pointer = conn.query_pointer(root)?.reply()?;
+
+    conn.xtest_fake_input(
+        MOTION_NOTIFY_EVENT,
+        0,
+        CURRENT_TIME,
+        root,
+        x as i16,
+        y as i16,
+        0,
+    )?;
+    for _ in 0..clicks {
+        conn.xtest_fake_input(BUTTON_PRESS_EVENT, WHEEL_DOWN, CURRENT_TIME, root, 0, 0, 0)?;
+        conn.xtest_fake_input(
+            BUTTON_RELEASE_EVENT,
+            WHEEL_DOWN,
+            CURRENT_TIME,
+            root,
+            0,
+            0,
+            0,
+        )?;
+    }
+    conn.xtest_fake_input(
+        MOTION_NOTIFY_EVENT,
+        0,
+        CURRENT_TIME,
+        root,
+        pointer.root_x,
+        pointer.root_y,
+        0,
+    )?;
+    // 等待服务器处理完所有事件
+    conn.get_input_focus()?.reply()?;
+    Ok(())
 }
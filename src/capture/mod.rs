@@ -1,9 +1,10 @@
 use anyhow::Result;
 use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
 
 pub type RgbaImage = ImageBuffer<Rgba<u8>, Vec<u8>>;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RectPx {
     pub x: u32,
     pub y: u32,
@@ -11,6 +12,20 @@ pub struct RectPx {
     pub h: u32,
 }
 
+/// 鼠标指针图像，(x, y) 为图像左上角的屏幕坐标
+pub struct CursorImage {
+    pub x: i32,
+    pub y: i32,
+    pub image: RgbaImage,
+}
+
+/// 上一次确认的选区及其所在显示器
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRegion {
+    pub rect: RectPx,
+    pub monitor: Option<String>,
+}
+
 pub fn capture_region(rect: RectPx) -> Result<RgbaImage> {
     #[cfg(all(unix, not(target_os = "macos")))]
     {
@@ -31,6 +46,111 @@ pub fn capture_region(rect: RectPx) -> Result<RgbaImage> {
     Err(anyhow::anyhow!("unsupported platform"))
 }
 
+/// 整个屏幕的物理像素范围（仅 X11 支持）
+pub fn screen_rect() -> Result<RectPx> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        return crate::capture::linux::screen_rect();
+    }
+
+    #[allow(unreachable_code)]
+    Err(anyhow::anyhow!("unsupported platform"))
+}
+
+/// 截取整个屏幕
+pub fn capture_screen() -> Result<RgbaImage> {
+    capture_region(screen_rect()?)
+}
+
+/// 当前鼠标指针的图像和位置（仅 X11 支持）
+pub fn cursor_image() -> Result<CursorImage> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        return crate::capture::linux::cursor_image();
+    }
+
+    #[allow(unreachable_code)]
+    Err(anyhow::anyhow!("unsupported platform"))
+}
+
+/// 连续截图时复用的连接，例如录屏时每帧截图和读取指针
+pub struct Grabber {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    x11: crate::capture::linux::Grabber,
+}
+
+impl Grabber {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            x11: crate::capture::linux::Grabber::new()?,
+        })
+    }
+
+    pub fn capture_region(&self, rect: RectPx) -> Result<RgbaImage> {
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            return self.x11.capture_region(rect);
+        }
+
+        #[allow(unreachable_code)]
+        capture_region(rect)
+    }
+
+    /// 当前鼠标指针的图像和位置（仅 X11 支持）
+    pub fn cursor_image(&self) -> Result<CursorImage> {
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            return self.x11.cursor_image();
+        }
+
+        #[allow(unreachable_code)]
+        Err(anyhow::anyhow!("unsupported platform"))
+    }
+}
+
+/// 在屏幕坐标处模拟向下滚动滚轮（仅 X11 支持）
+pub fn scroll_at(x: i32, y: i32, clicks: u32) -> Result<()> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        return crate::capture::linux::scroll_at(x, y, clicks);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (x, y, clicks);
+        Err(anyhow::anyhow!("unsupported platform"))
+    }
+}
+
+/// 选区所在显示器的名称（仅 X11 支持）
+pub fn monitor_name(rect: RectPx) -> Option<String> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        return crate::capture::linux::monitor_name(rect).ok().flatten();
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = rect;
+        None
+    }
+}
+
+/// 屏幕坐标处最上层窗口的标题（仅 X11 支持）
+pub fn window_title_at(x: i32, y: i32) -> Option<String> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        return crate::capture::linux::window_title_at(x, y).ok().flatten();
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (x, y);
+        None
+    }
+}
+
 #[cfg(all(unix, not(target_os = "macos")))]
 pub mod linux;
 
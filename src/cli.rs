@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+
+use crate::capture::RectPx;
+use crate::ipc::Request;
+use crate::mode::CaptureTarget;
+use crate::overlay::parse_size_input;
+
+pub const USAGE: &str = "\
+Usage:
+  pxshot                              Open the main window
+  pxshot edit <image> [-o <output>]   Annotate an image or .pxshot project
+  pxshot capture [options]            Take a screenshot, optionally after a countdown
+  pxshot daemon                       Stay hidden and capture on global hotkeys (X11)
+  pxshot ctl <command> [args]         Control a running instance:
+      capture-region [WxH+X+Y]  capture-full  open-file <path>
+      pin [path]  get-last  quit
+
+Options:
+  -o, --output <path>   Also save the result to <path> (.png, .jpg, .svg or .pxshot)
+  -d, --delay <secs>    Wait before capturing (capture only)
+      --full            Capture the whole screen without selecting (capture only)
+      --region <WxH+X+Y>
+                        Capture a fixed region without selecting (capture only)
+      --last-region     Capture the previously confirmed region (capture only)
+  -h, --help            Show this help";
+
+/// 子命令
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Gui,
+    Edit {
+        input: PathBuf,
+        output: Option<PathBuf>,
+    },
+    Capture {
+        target: CaptureTarget,
+        delay: Duration,
+        output: Option<PathBuf>,
+        last_region: bool, // 由调用方从配置中取出上次的选区
+    },
+    Daemon,
+    Ctl(Request),
+    Help,
+}
+
+/// 解析命令行参数（不含程序名）
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command> {
+    let mut args = args.into_iter();
+    let Some(first) = args.next() else {
+        return Ok(Command::Gui);
+    };
+
+    match first.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "edit" => {
+            let mut input = None;
+            let mut output = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "-o" | "--output" => {
+                        let Some(path) = args.next() else {
+                            bail!("{arg} needs a path");
+                        };
+                        output = Some(PathBuf::from(path));
+                    }
+                    _ if arg.starts_with('-') => bail!("unknown option {arg}"),
+                    _ if input.is_none() => input = Some(PathBuf::from(arg)),
+                    _ => bail!("unexpected argument {arg}"),
+                }
+            }
+            let Some(input) = input else {
+                bail!("edit needs an image path");
+            };
+            Ok(Command::Edit { input, output })
+        }
+        "daemon" => match args.next() {
+            Some(arg) => bail!("unexpected argument {arg}"),
+            None => Ok(Command::Daemon),
+        },
+        "ctl" => {
+            let Some(cmd) = args.next() else {
+                bail!("ctl needs a command");
+            };
+            let arg = args.next();
+            if let Some(extra) = args.next() {
+                bail!("unexpected argument {extra}");
+            }
+            let absolute = |path: String| std::path::absolute(path).map_err(anyhow::Error::from);
+            let request = match (cmd.as_str(), arg) {
+                ("capture-region", None) => Request::CaptureRegion { region: None },
+                ("capture-region", Some(text)) => {
+                    // 必须给出位置，否则会截到屏幕左上角
+                    let Some(input) = parse_size_input(&text) else {
+                        bail!("capture-region needs WxH+X+Y");
+                    };
+                    let Some((x, y)) = input.pos else {
+                        bail!("capture-region needs WxH+X+Y");
+                    };
+                    Request::CaptureRegion {
+                        region: Some(RectPx {
+                            x,
+                            y,
+                            w: input.w,
+                            h: input.h,
+                        }),
+                    }
+                }
+                ("capture-full", None) => Request::CaptureFull,
+                ("open-file", Some(path)) => Request::OpenFile {
+                    path: absolute(path)?,
+                },
+                ("open-file", None) => bail!("open-file needs a path"),
+                ("pin", path) => Request::Pin {
+                    path: path.map(absolute).transpose()?,
+                },
+                ("get-last", None) => Request::GetLast,
+                ("quit", None) => Request::Quit,
+                ("capture-full" | "get-last" | "quit", Some(arg)) => {
+                    bail!("unexpected argument {arg}")
+                }
+                (other, _) => bail!("unknown ctl command {other}"),
+            };
+            Ok(Command::Ctl(request))
+        }
+        "capture" => {
+            let mut target = CaptureTarget::Interactive;
+            let mut delay = Duration::ZERO;
+            let mut output = None;
+            let mut last_region = false;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "-o" | "--output" => {
+                        let Some(path) = args.next() else {
+                            bail!("{arg} needs a path");
+                        };
+                        output = Some(PathBuf::from(path));
+                    }
+                    "-d" | "--delay" => {
+                        let secs = args.next().and_then(|s| s.parse::<f32>().ok());
+                        match secs {
+                            Some(secs) if (0.0..=3600.0).contains(&secs) => {
+                                delay = Duration::from_secs_f32(secs);
+                            }
+                            _ => bail!("{arg} needs a number of seconds"),
+                        }
+                    }
+                    "--full" => target = CaptureTarget::FullScreen,
+                    "--last-region" => last_region = true,
+                    "--region" => {
+                        let Some(input) = args.next().as_deref().and_then(parse_size_input) else {
+                            bail!("--region needs WxH+X+Y");
+                        };
+                        let Some((x, y)) = input.pos else {
+                            bail!("--region needs WxH+X+Y");
+                        };
+                        target = CaptureTarget::Region(RectPx {
+                            x,
+                            y,
+                            w: input.w,
+                            h: input.h,
+                        });
+                    }
+                    _ => bail!("unexpected argument {arg}"),
+                }
+            }
+            if last_region && target != CaptureTarget::Interactive {
+                bail!("--last-region cannot be combined with --full or --region");
+            }
+            Ok(Command::Capture {
+                target,
+                delay,
+                output,
+                last_region,
+            })
+        }
+        other => bail!("unknown command {other}"),
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Result;
+use arboard::{Clipboard, ImageData};
+use base64::Engine;
+use image::ImageBuffer;
+use serde::{Deserialize, Serialize};
+
+use crate::capture::RgbaImage;
+use crate::config;
+
+/// 复制时额外提供的格式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub offer_file: bool, // text/uri-list
+    pub offer_html: bool, // text/html 内嵌 data URI
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            offer_file: true,
+            offer_html: false,
+        }
+    }
+}
+
+/// (MIME 类型, 数据) 列表
+type Targets = Vec<(String, Vec<u8>)>;
+
+enum Backend {
+    #[cfg(target_os = "linux")]
+    X11(x11::X11Clipboard),
+    // 保持 Clipboard 存活，避免数据随对象释放而丢失
+    Arboard(Clipboard),
+}
+
+/// 常驻的剪贴板服务，首次复制时创建后端
+#[derive(Default)]
+pub struct ClipboardService {
+    backend: Option<Backend>,
+}
+
+impl ClipboardService {
+    /// 复制图像；`file` 为已保存的文件路径，没有时写入临时文件
+    pub fn copy(
+        &mut self,
+        config: &ClipboardConfig,
+        img: &RgbaImage,
+        file: Option<&Path>,
+    ) -> Result<()> {
+        let backend = match &mut self.backend {
+            Some(backend) => backend,
+            None => self.backend.insert(Self::connect()?),
+        };
+
+        match backend {
+            #[cfg(target_os = "linux")]
+            Backend::X11(x11) => {
+                let (targets, temp) = Self::targets(config, img, file)?;
+                x11.set(targets, temp)
+            }
+            Backend::Arboard(clipboard) => {
+                clipboard.set_image(ImageData {
+                    width: img.width() as usize,
+                    height: img.height() as usize,
+                    bytes: img.as_raw().into(),
+                })?;
+                Ok(())
+            }
+        }
+    }
+
+    /// 复制文本，例如上传后的链接
+    pub fn copy_text(&mut self, text: &str) -> Result<()> {
+        let backend = match &mut self.backend {
+            Some(backend) => backend,
+            None => self.backend.insert(Self::connect()?),
+        };
+
+        match backend {
+            #[cfg(target_os = "linux")]
+            Backend::X11(x11) => x11.set(
+                vec![
+                    ("UTF8_STRING".to_string(), text.as_bytes().to_vec()),
+                    ("text/plain".to_string(), text.as_bytes().to_vec()),
+                ],
+                None,
+            ),
+            Backend::Arboard(clipboard) => {
+                clipboard.set_text(text)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// 退出前调用，把内容交给剪贴板管理器
+    pub fn shutdown(&mut self) {
+        match self.backend.take() {
+            #[cfg(target_os = "linux")]
+            Some(Backend::X11(x11)) => x11.shutdown(),
+            _ => {}
+        }
+    }
+
+    fn connect() -> Result<Backend> {
+        #[cfg(target_os = "linux")]
+        if std::env::var_os("DISPLAY").is_some() {
+            match x11::X11Clipboard::new() {
+                Ok(x11) => return Ok(Backend::X11(x11)),
+                Err(e) => eprintln!("X11 clipboard unavailable, falling back: {e}"),
+            }
+        }
+        Ok(Backend::Arboard(Clipboard::new()?))
+    }
+
+    /// 生成 (MIME, 数据) 列表，以及为此写入的临时文件
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn targets(
+        config: &ClipboardConfig,
+        img: &RgbaImage,
+        file: Option<&Path>,
+    ) -> Result<(Targets, Option<PathBuf>)> {
+        let mut png = Vec::new();
+        img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+
+        let mut targets = Vec::new();
+        let mut temp = None;
+        if config.offer_file {
+            let path = match file {
+                Some(path) => path.to_path_buf(),
+                None => {
+                    let path = Self::temp_path()?;
+                    std::fs::write(&path, &png)?;
+                    temp.insert(path).clone()
+                }
+            };
+            let path = path.canonicalize().unwrap_or(path);
+            let uri = file_uri(&path);
+            targets.push((
+                "text/uri-list".to_string(),
+                format!("{uri}\r\n").into_bytes(),
+            ));
+            targets.push((
+                "x-special/gnome-copied-files".to_string(),
+                format!("copy\n{uri}").into_bytes(),
+            ));
+            let text = path.display().to_string().into_bytes();
+            targets.push(("UTF8_STRING".to_string(), text.clone()));
+            targets.push(("text/plain".to_string(), text));
+        }
+        if config.offer_html {
+            let data = base64::engine::general_purpose::STANDARD.encode(&png);
+            let html = format!(
+                "<img src=\"data:image/png;base64,{data}\" width=\"{}\" height=\"{}\">",
+                img.width(),
+                img.height()
+            );
+            targets.push(("text/html".to_string(), html.into_bytes()));
+        }
+        // 图像放在最前，作为首选格式
+        targets.insert(0, ("image/png".to_string(), png));
+        Ok((targets, temp))
+    }
+
+    /// 每次复制单独的临时文件，之前提供的文件路径仍指向当时的图像
+    fn temp_path() -> Result<PathBuf> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let dir = config::temp_dir();
+        std::fs::create_dir_all(&dir)?;
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        Ok(dir.join(format!("clipboard-{n}.png")))
+    }
+}
+
+/// 把路径编码为 `file://` URI
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+/// 从剪贴板读取图像
+pub fn paste_image() -> Result<RgbaImage> {
+    let mut clipboard = Clipboard::new()?;
+    let data = clipboard.get_image()?;
+
+    ImageBuffer::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| anyhow::anyhow!("bad clipboard image"))
+}
@@ -0,0 +1,417 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use x11rb::CURRENT_TIME;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt as _,
+    CreateWindowAux, EventMask, PropMode, Property, SELECTION_NOTIFY_EVENT, SelectionNotifyEvent,
+    SelectionRequestEvent, Window, WindowClass,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// 发给剪贴板线程的请求
+enum Request {
+    Set(Vec<(String, Vec<u8>)>, Option<PathBuf>, Sender<Result<()>>),
+    Shutdown(Sender<()>),
+}
+
+/// X11 剪贴板所有者：后台线程持有 CLIPBOARD 选区并按需提供多种格式
+///
+/// 后台线程阻塞在 X 事件上，发请求后给它的窗口发一条消息把它唤醒。
+pub struct X11Clipboard {
+    tx: Sender<Request>,
+    waker: Arc<Waker>,
+}
+
+impl X11Clipboard {
+    pub fn new() -> Result<Self> {
+        let server = Server::connect()?;
+        let waker = server.waker.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("pxshot-clipboard".into())
+            .spawn(move || server.run(rx))?;
+        Ok(Self { tx, waker })
+    }
+
+    fn send(&self, request: Request) -> Result<()> {
+        self.tx
+            .send(request)
+            .map_err(|_| anyhow!("clipboard thread stopped"))?;
+        self.waker.wake()
+    }
+
+    /// 设置剪贴板内容：(MIME 类型, 数据) 列表
+    ///
+    /// `temp_file` 为只为这次复制写入的文件，失去剪贴板或内容被替换时删除。
+    pub fn set(&self, targets: Vec<(String, Vec<u8>)>, temp_file: Option<PathBuf>) -> Result<()> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.send(Request::Set(targets, temp_file, reply_tx))?;
+        reply_rx
+            .recv_timeout(Duration::from_secs(2))
+            .map_err(|_| anyhow!("clipboard thread not responding"))?
+    }
+
+    /// 把内容交给剪贴板管理器，使退出后仍可粘贴
+    pub fn shutdown(&self) {
+        let (done_tx, done_rx) = mpsc::channel();
+        if self.send(Request::Shutdown(done_tx)).is_ok() {
+            let _ = done_rx.recv_timeout(Duration::from_secs(3));
+        }
+    }
+}
+
+impl Drop for X11Clipboard {
+    fn drop(&mut self) {
+        // 后台线程还在等事件时让它交出内容后退出
+        let (done_tx, _) = mpsc::channel();
+        let _ = self.send(Request::Shutdown(done_tx));
+    }
+}
+
+/// 用单独的连接给剪贴板窗口发 ClientMessage，唤醒阻塞在 `wait_for_event` 上的线程
+struct Waker {
+    conn: RustConnection,
+    window: Window,
+    atom: Atom,
+}
+
+impl Waker {
+    fn wake(&self) -> Result<()> {
+        let event = ClientMessageEvent::new(32, self.window, self.atom, [0u32; 5]);
+        self.conn
+            .send_event(false, self.window, EventMask::NO_EVENT, event)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+struct Atoms {
+    clipboard: Atom,
+    targets: Atom,
+    incr: Atom,
+    clipboard_manager: Atom,
+    save_targets: Atom,
+    property: Atom,
+    wake: Atom,
+}
+
+/// 进行中的 INCR 分段传输
+struct Transfer {
+    requestor: Window,
+    property: Atom,
+    target: Atom,
+    data: Arc<Vec<u8>>,
+    offset: usize,
+}
+
+struct Server {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+    data: Vec<(Atom, Arc<Vec<u8>>)>,
+    temp_file: Option<PathBuf>,
+    transfers: Vec<Transfer>,
+    chunk: usize,
+    waker: Arc<Waker>,
+}
+
+impl Server {
+    const HANDOFF_TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn connect() -> Result<Self> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let screen = &conn.setup().roots[screen_num];
+        let window = conn.generate_id()?;
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            screen.root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            screen.root_visual,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?;
+
+        let intern =
+            |name: &[u8]| -> Result<Atom> { Ok(conn.intern_atom(false, name)?.reply()?.atom) };
+        let atoms = Atoms {
+            clipboard: intern(b"CLIPBOARD")?,
+            targets: intern(b"TARGETS")?,
+            incr: intern(b"INCR")?,
+            clipboard_manager: intern(b"CLIPBOARD_MANAGER")?,
+            save_targets: intern(b"SAVE_TARGETS")?,
+            property: intern(b"PXSHOT_CLIPBOARD")?,
+            wake: intern(b"PXSHOT_CLIPBOARD_WAKE")?,
+        };
+        // 超过单次请求上限的数据需要用 INCR 分段
+        let chunk = (conn.maximum_request_bytes() / 4).min(1 << 18);
+        conn.flush()?;
+
+        let waker = Arc::new(Waker {
+            conn: x11rb::connect(None)?.0,
+            window,
+            atom: atoms.wake,
+        });
+        Ok(Self {
+            conn,
+            window,
+            atoms,
+            data: Vec::new(),
+            temp_file: None,
+            transfers: Vec::new(),
+            chunk,
+            waker,
+        })
+    }
+
+    fn run(mut self, rx: Receiver<Request>) {
+        loop {
+            let event = match self.conn.wait_for_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("clipboard connection lost: {e}");
+                    return;
+                }
+            };
+            if !self.is_wake(&event) {
+                if let Err(e) = self.handle(event) {
+                    eprintln!("clipboard: {e}");
+                }
+                continue;
+            }
+
+            loop {
+                match rx.try_recv() {
+                    Ok(Request::Set(targets, temp_file, reply)) => {
+                        let _ = reply.send(self.set(targets, temp_file));
+                    }
+                    Ok(Request::Shutdown(done)) => {
+                        self.handoff();
+                        let _ = done.send(());
+                        return;
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        self.handoff();
+                        return;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                }
+            }
+        }
+    }
+
+    /// 是否是 `Waker` 发来的唤醒消息
+    fn is_wake(&self, event: &Event) -> bool {
+        matches!(event, Event::ClientMessage(ev) if ev.type_ == self.atoms.wake)
+    }
+
+    fn set(&mut self, targets: Vec<(String, Vec<u8>)>, temp_file: Option<PathBuf>) -> Result<()> {
+        self.clear();
+        self.temp_file = temp_file;
+        let mut data = Vec::with_capacity(targets.len());
+        for (name, bytes) in targets {
+            let atom = self.conn.intern_atom(false, name.as_bytes())?.reply()?.atom;
+            data.push((atom, Arc::new(bytes)));
+        }
+        self.data = data;
+
+        self.conn
+            .set_selection_owner(self.window, self.atoms.clipboard, CURRENT_TIME)?;
+        let owner = self
+            .conn
+            .get_selection_owner(self.atoms.clipboard)?
+            .reply()?
+            .owner;
+        if owner != self.window {
+            self.clear();
+            return Err(anyhow!("another application holds the clipboard"));
+        }
+        Ok(())
+    }
+
+    /// 丢弃当前内容，删除为它写入的临时文件
+    fn clear(&mut self) {
+        self.data.clear();
+        if let Some(path) = self.temp_file.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn handle(&mut self, event: Event) -> Result<()> {
+        match event {
+            Event::SelectionRequest(req) => self.handle_request(req)?,
+            Event::PropertyNotify(ev) if ev.state == Property::DELETE => {
+                self.continue_transfer(ev.window, ev.atom)?;
+            }
+            Event::SelectionClear(ev) if ev.selection == self.atoms.clipboard => {
+                // 其他程序接管了剪贴板
+                self.clear();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: SelectionRequestEvent) -> Result<()> {
+        let property = if req.property == u32::from(AtomEnum::NONE) {
+            req.target
+        } else {
+            req.property
+        };
+
+        let ok = if req.target == self.atoms.targets {
+            let mut targets = vec![self.atoms.targets];
+            targets.extend(self.data.iter().map(|(atom, _)| *atom));
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                req.requestor,
+                property,
+                AtomEnum::ATOM,
+                &targets,
+            )?;
+            true
+        } else if let Some((_, bytes)) = self.data.iter().find(|(atom, _)| *atom == req.target) {
+            if bytes.len() > self.chunk {
+                self.conn.change_window_attributes(
+                    req.requestor,
+                    &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+                )?;
+                self.conn.change_property32(
+                    PropMode::REPLACE,
+                    req.requestor,
+                    property,
+                    self.atoms.incr,
+                    &[bytes.len() as u32],
+                )?;
+                self.transfers.push(Transfer {
+                    requestor: req.requestor,
+                    property,
+                    target: req.target,
+                    data: bytes.clone(),
+                    offset: 0,
+                });
+            } else {
+                self.conn.change_property8(
+                    PropMode::REPLACE,
+                    req.requestor,
+                    property,
+                    req.target,
+                    bytes,
+                )?;
+            }
+            true
+        } else {
+            false
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: req.time,
+            requestor: req.requestor,
+            selection: req.selection,
+            target: req.target,
+            property: if ok { property } else { AtomEnum::NONE.into() },
+        };
+        self.conn
+            .send_event(false, req.requestor, EventMask::NO_EVENT, notify)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// 请求方删除属性后写入下一段，最后写入空数据表示结束
+    fn continue_transfer(&mut self, window: Window, property: Atom) -> Result<()> {
+        let Some(idx) = self
+            .transfers
+            .iter()
+            .position(|t| t.requestor == window && t.property == property)
+        else {
+            return Ok(());
+        };
+
+        let transfer = &mut self.transfers[idx];
+        let end = (transfer.offset + self.chunk).min(transfer.data.len());
+        self.conn.change_property8(
+            PropMode::REPLACE,
+            transfer.requestor,
+            transfer.property,
+            transfer.target,
+            &transfer.data[transfer.offset..end],
+        )?;
+        let finished = transfer.offset == end;
+        transfer.offset = end;
+        if finished {
+            self.transfers.remove(idx);
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// 退出前通过 SAVE_TARGETS 请剪贴板管理器保存内容
+    fn handoff(&mut self) {
+        if let Err(e) = self.try_handoff() {
+            eprintln!("clipboard handoff failed: {e}");
+        }
+    }
+
+    fn try_handoff(&mut self) -> Result<()> {
+        if self.data.is_empty() {
+            return Ok(());
+        }
+        let owner = self
+            .conn
+            .get_selection_owner(self.atoms.clipboard)?
+            .reply()?
+            .owner;
+        let manager = self
+            .conn
+            .get_selection_owner(self.atoms.clipboard_manager)?
+            .reply()?
+            .owner;
+        if owner != self.window || manager == u32::from(AtomEnum::NONE) {
+            return Ok(());
+        }
+
+        self.conn.convert_selection(
+            self.window,
+            self.atoms.clipboard_manager,
+            self.atoms.save_targets,
+            self.atoms.property,
+            CURRENT_TIME,
+        )?;
+        self.conn.flush()?;
+
+        // 管理器会回头向我们请求数据，继续处理直到它确认保存；超时后由计时线程唤醒
+        let deadline = Instant::now() + Self::HANDOFF_TIMEOUT;
+        let waker = self.waker.clone();
+        thread::spawn(move || {
+            thread::sleep(Self::HANDOFF_TIMEOUT);
+            let _ = waker.wake();
+        });
+        loop {
+            match self.conn.wait_for_event()? {
+                Event::SelectionNotify(ev) if ev.selection == self.atoms.clipboard_manager => {
+                    return Ok(());
+                }
+                event if self.is_wake(&event) => {
+                    if Instant::now() >= deadline {
+                        return Err(anyhow!("clipboard manager did not respond"));
+                    }
+                }
+                event => self.handle(event)?,
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::Action;
+use crate::capture::LastRegion;
+use crate::clipboard::ClipboardConfig;
+use crate::history::HistoryConfig;
+use crate::hotkey::HotkeyConfig;
+use crate::record::RecordConfig;
+use crate::tools::arrow::{ArrowSettings, Rasterizer};
+
+/// 持久化的用户配置，保存在 `<config_dir>/pxshot/config.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub history: HistoryConfig,
+    pub clipboard: ClipboardConfig,
+    pub last_region: Option<LastRegion>,
+    pub record: RecordConfig,
+    pub hotkeys: HotkeyConfig,
+    /// 截图完成后依次执行的动作
+    pub actions: Vec<Action>,
+    /// 导出时渲染标注的后端
+    pub rasterizer: Rasterizer,
+    /// 箭头工具的默认样式、预设和最近使用的颜色
+    pub arrow: ArrowSettings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            history: HistoryConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            last_region: None,
+            record: RecordConfig::default(),
+            hotkeys: HotkeyConfig::default(),
+            actions: Action::defaults(),
+            rasterizer: Rasterizer::default(),
+            arrow: ArrowSettings::default(),
+        }
+    }
+}
+
+/// 本次运行的临时目录，存放临时截图和剪贴板文件，退出时删除
+pub fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("pxshot-{}", std::process::id()))
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("pxshot").join("config.json"))
+    }
+
+    /// 读取配置，文件不存在或解析失败时使用默认值
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                eprintln!("invalid config {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| anyhow::anyhow!("no config directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use eframe::egui::Pos2;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+
+use crate::capture::RgbaImage;
+use crate::tools::arrow::Arrow;
+
+/// 可重新编辑的标注文档
+///
+/// 箭头坐标保存为相对截图左上角的逻辑坐标，配合 `ppp` 可换算成像素，
+/// 因此可以在不同缩放比例的屏幕上还原。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnotationDoc {
+    pub ppp: f32,
+    pub arrows: Vec<Arrow>,
+}
+
+impl AnnotationDoc {
+    /// 从屏幕上的箭头创建，`origin` 为选区左上角的逻辑坐标
+    pub fn from_arrows(arrows: &[Arrow], origin: Pos2, ppp: f32) -> Self {
+        Self {
+            ppp,
+            arrows: arrows.iter().map(|a| a.transformed(origin, 1.0)).collect(),
+        }
+    }
+
+    /// 还原到屏幕上：图像左上角位于 `origin`，当前缩放为 `ppp`
+    pub fn arrows_at(&self, origin: Pos2, ppp: f32) -> Vec<Arrow> {
+        let scale = if self.ppp > 0.0 { self.ppp / ppp } else { 1.0 };
+        self.arrows
+            .iter()
+            .map(|a| {
+                let mut arrow = a.transformed(Pos2::ZERO, scale);
+                arrow.translate(origin.to_vec2());
+                arrow
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty()
+    }
+}
+
+/// 项目文件格式版本，格式变化时递增
+///
+/// 2：箭头增加曲线和折线路径以及描边、阴影效果；版本 1 的文件没有 `path` 和 `effects`，
+/// 读作没有效果的直线。
+pub const PROJECT_VERSION: u32 = 2;
+/// 项目文件扩展名
+pub const PROJECT_EXTENSION: &str = "pxshot";
+const PROJECT_FORMAT: &str = "pxshot-project";
+
+/// 磁盘上的项目文件：JSON，原始像素以 base64 PNG 内嵌
+#[derive(Serialize, Deserialize)]
+struct ProjectFile {
+    format: String,
+    version: u32,
+    image_png: String,
+    annotations: AnnotationDoc,
+}
+
+/// 可重新打开编辑的截图项目：原始像素 + 标注
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub image: RgbaImage,
+    pub annotations: AnnotationDoc,
+}
+
+impl Project {
+    pub fn new(image: RgbaImage, annotations: AnnotationDoc) -> Self {
+        Self { image, annotations }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        self.image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        let file = ProjectFile {
+            format: PROJECT_FORMAT.to_string(),
+            version: PROJECT_VERSION,
+            image_png: BASE64.encode(png),
+            annotations: self.annotations.clone(),
+        };
+        Ok(serde_json::to_vec(&file)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let file: ProjectFile = serde_json::from_slice(bytes).context("not a pxshot project")?;
+        if file.format != PROJECT_FORMAT {
+            bail!("not a pxshot project");
+        }
+        if file.version > PROJECT_VERSION {
+            bail!(
+                "project version {} is newer than supported version {PROJECT_VERSION}",
+                file.version
+            );
+        }
+
+        let png = BASE64
+            .decode(file.image_png)
+            .context("bad embedded image")?;
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png)?.to_rgba8();
+        Ok(Self {
+            image,
+            annotations: file.annotations,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// 打开项目文件或普通图像（普通图像没有标注）
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(PROJECT_EXTENSION))
+        {
+            return Self::load(path);
+        }
+        let image = image::open(path)
+            .with_context(|| format!("open {}", path.display()))?
+            .to_rgba8();
+        Ok(Self::new(image, AnnotationDoc::default()))
+    }
+
+    /// 从内存中的文件内容打开（拖放时可能只有字节）
+    pub fn open_bytes(bytes: &[u8]) -> Result<Self> {
+        if let Ok(project) = Self::from_bytes(bytes) {
+            return Ok(project);
+        }
+        let image = image::load_from_memory(bytes)
+            .context("unsupported image")?
+            .to_rgba8();
+        Ok(Self::new(image, AnnotationDoc::default()))
+    }
+}
@@ -0,0 +1,34 @@
+mod svg;
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::capture::RgbaImage;
+use crate::document::{PROJECT_EXTENSION, Project};
+
+pub use svg::{project_to_svg, save_svg};
+
+/// 按扩展名保存结果：`.pxshot` 项目、`.svg` 矢量图，其余交给 image 编码
+pub fn save_output(path: &Path, image: &RgbaImage, project: &Project) -> Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match ext.as_str() {
+        PROJECT_EXTENSION => project.save(path),
+        "svg" => save_svg(project, path),
+        "jpg" | "jpeg" => {
+            // JPEG 不支持透明通道
+            image::DynamicImage::ImageRgba8(image.clone())
+                .to_rgb8()
+                .save(path)?;
+            Ok(())
+        }
+        _ => {
+            image.save(path)?;
+            Ok(())
+        }
+    }
+}
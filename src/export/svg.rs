@@ -0,0 +1,248 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use eframe::egui::{Color32, Pos2};
+use image::ImageFormat;
+
+use crate::document::Project;
+use crate::tools::arrow::{Arrow, ArrowType, Effects, LineStyle, Skeleton};
+
+/// 把项目导出为 SVG：截图作为内嵌 PNG，箭头作为原生矢量元素
+///
+/// 坐标单位为图像像素，每个箭头放在独立的 `<g>` 中，方便在 Inkscape 里编辑。
+pub fn project_to_svg(project: &Project) -> Result<String> {
+    let (w, h) = project.image.dimensions();
+
+    let mut png = Vec::new();
+    project
+        .image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    let data = BASE64.encode(png);
+
+    let mut svg = String::new();
+    writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#
+    )?;
+    writeln!(
+        svg,
+        r#"  <image id="screenshot" x="0" y="0" width="{w}" height="{h}" xlink:href="data:image/png;base64,{data}"/>"#
+    )?;
+
+    let ppp = if project.annotations.ppp > 0.0 {
+        project.annotations.ppp
+    } else {
+        1.0
+    };
+    writeln!(svg, r#"  <g id="annotations">"#)?;
+    for (i, arrow) in project.annotations.arrows.iter().enumerate() {
+        // 标注保存的是逻辑坐标，换算成图像像素
+        let arrow = arrow.transformed(Pos2::ZERO, ppp);
+        write_arrow(&mut svg, i, &arrow, ppp, (w, h))?;
+    }
+    writeln!(svg, "  </g>")?;
+    writeln!(svg, "</svg>")?;
+    Ok(svg)
+}
+
+pub fn save_svg(project: &Project, path: impl AsRef<Path>) -> Result<()> {
+    fs::write(path, project_to_svg(project)?)?;
+    Ok(())
+}
+
+/// `#rrggbb` 和不透明度
+fn svg_color(color: Color32) -> (String, f32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    (format!("#{r:02x}{g:02x}{b:02x}"), a as f32 / 255.0)
+}
+
+fn fmt_points(points: &[Pos2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 线段样式对应的 `stroke-dasharray`，与屏幕上的虚线模式相同（按 ppp 缩放）
+fn dash_array(style: LineStyle, width: f32, scale: f32) -> Option<String> {
+    let pattern = style.dash_pattern(width / scale)?;
+    Some(
+        pattern
+            .iter()
+            .map(|len| format!("{:.2}", len * scale))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// 描边和投影对应的 SVG 滤镜，没有启用效果时不输出
+///
+/// 滤镜区域覆盖整张图，避免默认的 10% 边距把模糊或描边裁掉。
+/// `opacity` 为箭头颜色的透明度，只作用在箭头本身上。
+fn write_effects_filter(
+    svg: &mut String,
+    id: &str,
+    effects: &Effects,
+    (w, h): (u32, u32),
+    opacity: f32,
+) -> Result<bool> {
+    let (outline, shadow) = (effects.outline, effects.shadow);
+    if !outline.enabled && !shadow.enabled {
+        return Ok(false);
+    }
+    writeln!(
+        svg,
+        r#"    <filter id="{id}" filterUnits="userSpaceOnUse" x="0" y="0" width="{w}" height="{h}">"#
+    )?;
+    let mut silhouette = "SourceAlpha";
+    if outline.enabled {
+        let (color, alpha) = svg_color(outline.color);
+        writeln!(
+            svg,
+            r#"      <feMorphology in="SourceAlpha" operator="dilate" radius="{:.2}" result="dilated"/>"#,
+            outline.width
+        )?;
+        writeln!(
+            svg,
+            r#"      <feFlood flood-color="{color}" flood-opacity="{alpha:.3}"/>"#
+        )?;
+        writeln!(
+            svg,
+            r#"      <feComposite in2="dilated" operator="in" result="outline"/>"#
+        )?;
+        silhouette = "dilated";
+    }
+    if shadow.enabled {
+        writeln!(
+            svg,
+            r#"      <feGaussianBlur in="{silhouette}" stdDeviation="{:.2}"/>"#,
+            shadow.blur.max(0.0) / 2.0
+        )?;
+        writeln!(
+            svg,
+            r#"      <feOffset dx="{:.2}" dy="{:.2}"/>"#,
+            shadow.offset.x, shadow.offset.y
+        )?;
+        writeln!(
+            svg,
+            r#"      <feComponentTransfer result="shadow"><feFuncA type="linear" slope="{:.3}"/></feComponentTransfer>"#,
+            shadow.opacity.clamp(0.0, 1.0)
+        )?;
+    }
+    writeln!(
+        svg,
+        r#"      <feComponentTransfer in="SourceGraphic" result="body"><feFuncA type="linear" slope="{opacity:.3}"/></feComponentTransfer>"#
+    )?;
+    writeln!(svg, "      <feMerge>")?;
+    for (enabled, layer) in [(shadow.enabled, "shadow"), (outline.enabled, "outline")] {
+        if enabled {
+            writeln!(svg, r#"        <feMergeNode in="{layer}"/>"#)?;
+        }
+    }
+    writeln!(svg, r#"        <feMergeNode in="body"/>"#)?;
+    writeln!(svg, "      </feMerge>")?;
+    writeln!(svg, "    </filter>")?;
+    Ok(true)
+}
+
+/// `scale` 为逻辑坐标到像素的比例，用于换算固定长度的虚线；`size` 为图像尺寸
+fn write_arrow(
+    svg: &mut String,
+    index: usize,
+    arrow: &Arrow,
+    scale: f32,
+    size: (u32, u32),
+) -> Result<()> {
+    let (color, opacity) = svg_color(arrow.color);
+    let width = arrow.stroke_width;
+    let Some(skeleton) = Skeleton::of(arrow) else {
+        return Ok(());
+    };
+
+    // 透明度只在整组上应用一次，子元素不透明，重叠处与预览一样只混合一次；
+    // 有效果时在滤镜里只淡化箭头本身，描边和投影不受影响
+    let filter_id = format!("arrow-{index}-effects");
+    let group = if write_effects_filter(svg, &filter_id, &arrow.effects, size, opacity)? {
+        format!(r#" filter="url(#{filter_id})""#)
+    } else if opacity < 1.0 {
+        format!(r#" opacity="{opacity:.3}""#)
+    } else {
+        String::new()
+    };
+    writeln!(
+        svg,
+        r#"    <g id="arrow-{index}" data-arrow-type="{}" data-line-style="{}" data-path="{}"{group}>"#,
+        arrow.arrow_type.name(),
+        arrow.line_style.name(),
+        arrow.path.kind().name()
+    )?;
+
+    let filled = |svg: &mut String, points: &[Pos2]| -> Result<()> {
+        writeln!(
+            svg,
+            r#"      <polygon points="{}" fill="{color}"/>"#,
+            fmt_points(points)
+        )?;
+        Ok(())
+    };
+    let (tip, dir) = skeleton.heads[0];
+
+    match arrow.arrow_type {
+        ArrowType::Single | ArrowType::Double => {
+            // 线条沿路径，虚线交给 stroke-dasharray
+            let dash = dash_array(arrow.line_style, width, scale)
+                .map(|d| format!(r#" stroke-dasharray="{d}""#))
+                .unwrap_or_default();
+            let stroke = format!(
+                r#"stroke="{color}" stroke-width="{width:.2}" stroke-linecap="round"{dash}"#
+            );
+            if let [from, to] = skeleton.body[..] {
+                writeln!(
+                    svg,
+                    r#"      <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {stroke}/>"#,
+                    from.x, from.y, to.x, to.y
+                )?;
+            } else {
+                writeln!(
+                    svg,
+                    r#"      <polyline points="{}" fill="none" {stroke} stroke-linejoin="round"/>"#,
+                    fmt_points(&skeleton.body)
+                )?;
+            }
+            for &(tip, dir) in &skeleton.heads {
+                filled(svg, &skeleton.head(tip, dir, 0.35))?;
+            }
+        }
+        ArrowType::Hollow => {
+            let (left, right) = skeleton.sides(width * 0.15, skeleton.head_size * 0.5);
+            let [_, head_left, head_right] = skeleton.head(tip, dir, 0.5);
+            let outline: Vec<Pos2> = left
+                .into_iter()
+                .chain([head_left, tip, head_right])
+                .chain(right.into_iter().rev())
+                .collect();
+            writeln!(
+                svg,
+                r#"      <polygon points="{}" fill="none" stroke="{color}" stroke-width="{:.2}" stroke-linejoin="round"/>"#,
+                fmt_points(&outline),
+                width * 0.4
+            )?;
+        }
+        ArrowType::Filled => {
+            let (left, right) = skeleton.sides(width * 0.15, skeleton.head_size * 0.5);
+            let body: Vec<Pos2> = left.into_iter().chain(right.into_iter().rev()).collect();
+            filled(svg, &body)?;
+            filled(svg, &skeleton.head(tip, dir, 0.5))?;
+        }
+    }
+
+    writeln!(svg, "    </g>")?;
+    Ok(())
+}
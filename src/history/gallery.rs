@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+
+use super::{HistoryEntry, format_timestamp};
+use crate::App;
+use crate::document::PROJECT_EXTENSION;
+use crate::export;
+
+/// 历史画廊的界面状态
+#[derive(Default)]
+pub struct GalleryState {
+    pub query: String,
+    thumbs: HashMap<String, egui::TextureHandle>,
+    pub status: Option<String>,
+}
+
+/// 画廊中点击的操作
+enum GalleryAction {
+    Copy(String),
+    Save(String),
+    Edit(String),
+    SaveProject(String),
+    SaveSvg(String),
+    Pin(String),
+    Delete(String),
+}
+
+impl GalleryState {
+    fn thumbnail(&mut self, ctx: &egui::Context, entry: &HistoryEntry) -> Option<egui::TextureId> {
+        if !self.thumbs.contains_key(&entry.id) {
+            let img = entry.load_thumbnail().ok()?;
+            let size = [img.width() as usize, img.height() as usize];
+            let color = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+            let tex = ctx.load_texture(format!("thumb-{}", entry.id), color, Default::default());
+            self.thumbs.insert(entry.id.clone(), tex);
+        }
+        self.thumbs.get(&entry.id).map(|t| t.id())
+    }
+}
+
+impl App {
+    /// 绘制历史画廊：搜索、保留策略和每条记录的操作
+    pub(crate) fn history_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some(history) = &self.history else {
+            ui.label("History is disabled.");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Search");
+            ui.text_edit_singleline(&mut self.gallery.query);
+            ui.label(format!("{} captures", history.entries().len()));
+        });
+
+        ui.collapsing("Retention", |ui| {
+            let cfg = &mut self.config.history;
+            // 拖动结束或输入完成时才保存，拖动过程中不逐帧写配置
+            let mut done = false;
+            ui.horizontal(|ui| {
+                for response in [
+                    ui.add(egui::DragValue::new(&mut cfg.max_entries).suffix(" captures")),
+                    ui.add(egui::DragValue::new(&mut cfg.max_size_mb).suffix(" MB")),
+                    ui.add(egui::DragValue::new(&mut cfg.max_age_days).suffix(" days")),
+                ] {
+                    done |= response.drag_stopped() || response.lost_focus();
+                }
+            });
+            ui.small("0 = unlimited");
+            if done && let Err(e) = self.config.save() {
+                self.gallery.status = Some(format!("save config failed: {e}"));
+            }
+            if ui.button("Apply now").clicked() {
+                self.apply_history_retention();
+            }
+        });
+
+        if let Some(status) = &self.gallery.status {
+            ui.label(status);
+        }
+
+        let Some(history) = &self.history else {
+            return;
+        };
+        let entries: Vec<HistoryEntry> = history.search(&self.gallery.query).cloned().collect();
+
+        let mut action = None;
+        egui::ScrollArea::vertical()
+            .max_height(320.0)
+            .show(ui, |ui| {
+                for entry in &entries {
+                    ui.horizontal(|ui| {
+                        if let Some(tex) = self.gallery.thumbnail(ctx, entry) {
+                            let size = self.gallery.thumbs[&entry.id].size_vec2();
+                            let scale = (64.0 / size.y).min(1.0);
+                            ui.image((tex, size * scale));
+                        }
+                        ui.vertical(|ui| {
+                            let meta = &entry.meta;
+                            ui.label(format!(
+                                "{}  {}×{}",
+                                format_timestamp(meta.timestamp),
+                                meta.width,
+                                meta.height
+                            ));
+                            if let Some(title) = &meta.window_title {
+                                ui.small(title);
+                            }
+                            if let Some(monitor) = &meta.monitor {
+                                ui.small(format!(
+                                    "{monitor} @ {},{}",
+                                    meta.region.x, meta.region.y
+                                ));
+                            }
+                            ui.horizontal(|ui| {
+                                let id = entry.id.clone();
+                                if ui.button("Copy").clicked() {
+                                    action = Some(GalleryAction::Copy(id.clone()));
+                                }
+                                if ui.button("Save").clicked() {
+                                    action = Some(GalleryAction::Save(id.clone()));
+                                }
+                                if ui.button("Edit").clicked() {
+                                    action = Some(GalleryAction::Edit(id.clone()));
+                                }
+                                if ui
+                                    .button("Project")
+                                    .on_hover_text("Save as re-editable project")
+                                    .clicked()
+                                {
+                                    action = Some(GalleryAction::SaveProject(id.clone()));
+                                }
+                                if ui.button("SVG").on_hover_text("Export as SVG").clicked() {
+                                    action = Some(GalleryAction::SaveSvg(id.clone()));
+                                }
+                                if ui.button("Pin").clicked() {
+                                    action = Some(GalleryAction::Pin(id.clone()));
+                                }
+                                if ui.button("Delete").clicked() {
+                                    action = Some(GalleryAction::Delete(id));
+                                }
+                            });
+                        });
+                    });
+                    ui.separator();
+                }
+            });
+
+        if let Some(action) = action {
+            self.gallery.status = self
+                .run_gallery_action(ctx, action)
+                .err()
+                .map(|e| e.to_string());
+        }
+    }
+
+    fn run_gallery_action(
+        &mut self,
+        ctx: &egui::Context,
+        action: GalleryAction,
+    ) -> anyhow::Result<()> {
+        let Some(history) = &mut self.history else {
+            return Ok(());
+        };
+        match action {
+            GalleryAction::Copy(id) => {
+                if let Some(entry) = history.get(&id) {
+                    let image = entry.load_image()?;
+                    self.clipboard.copy(
+                        &self.config.clipboard,
+                        &image,
+                        Some(&entry.image_path()),
+                    )?;
+                }
+            }
+            GalleryAction::Save(id) => {
+                if let Some(entry) = history.get(&id) {
+                    entry.load_image()?.save(format!("pxshot-{id}.png"))?;
+                }
+            }
+            GalleryAction::Edit(id) => {
+                if let Some(entry) = history.get(&id) {
+                    let project = entry.load_project()?;
+                    self.edit_image(ctx, project.image, &project.annotations);
+                }
+            }
+            GalleryAction::SaveSvg(id) => {
+                if let Some(entry) = history.get(&id) {
+                    export::save_svg(&entry.load_project()?, format!("pxshot-{id}.svg"))?;
+                }
+            }
+            GalleryAction::SaveProject(id) => {
+                if let Some(entry) = history.get(&id) {
+                    entry
+                        .load_project()?
+                        .save(format!("pxshot-{id}.{PROJECT_EXTENSION}"))?;
+                }
+            }
+            GalleryAction::Pin(id) => {
+                if let Some(entry) = history.get(&id) {
+                    self.pins.pin(entry.load_image()?, None);
+                }
+            }
+            GalleryAction::Delete(id) => {
+                history.delete(&id)?;
+                self.gallery.thumbs.remove(&id);
+            }
+        }
+        Ok(())
+    }
+
+    /// 按配置的保留策略清理历史
+    pub(crate) fn apply_history_retention(&mut self) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        match history.apply_retention(&self.config.history) {
+            Ok(0) => {}
+            Ok(n) => self.gallery.status = Some(format!("removed {n} old captures")),
+            Err(e) => self.gallery.status = Some(format!("retention failed: {e}")),
+        }
+        let ids: Vec<String> = history.entries().iter().map(|e| e.id.clone()).collect();
+        self.gallery.thumbs.retain(|id, _| ids.contains(id));
+    }
+}
@@ -0,0 +1,367 @@
+mod gallery;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::{RectPx, RgbaImage};
+use crate::document::{AnnotationDoc, Project};
+
+pub use gallery::GalleryState;
+
+/// 历史记录的保留策略，0 表示不限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub max_size_mb: u64,
+    pub max_age_days: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 200,
+            max_size_mb: 512,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// 截图元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureMeta {
+    pub timestamp: u64, // Unix 秒
+    pub region: RectPx,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub monitor: Option<String>,
+    #[serde(default)]
+    pub window_title: Option<String>,
+}
+
+impl CaptureMeta {
+    pub fn new(region: RectPx, image: &RgbaImage) -> Self {
+        Self {
+            timestamp: now_secs(),
+            region,
+            width: image.width(),
+            height: image.height(),
+            monitor: None,
+            window_title: None,
+        }
+    }
+
+    /// 是否匹配搜索关键字（标题、显示器、日期、尺寸）
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let haystack = format!(
+            "{} {} {} {}x{}",
+            self.window_title.as_deref().unwrap_or_default(),
+            self.monitor.as_deref().unwrap_or_default(),
+            format_timestamp(self.timestamp),
+            self.width,
+            self.height,
+        )
+        .to_lowercase();
+        query.split_whitespace().all(|word| haystack.contains(word))
+    }
+}
+
+/// 交给后台线程写入的一条历史记录
+pub struct PendingHistory {
+    pub dir: PathBuf,
+    pub original: RgbaImage,
+    pub annotations: AnnotationDoc,
+    pub meta: CaptureMeta,
+}
+
+/// 单条历史记录，每条对应历史目录下的一个子目录
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub meta: CaptureMeta,
+    dir: PathBuf,
+}
+
+impl HistoryEntry {
+    const IMAGE: &str = "capture.png";
+    const ORIGINAL: &str = "original.png";
+    const THUMB: &str = "thumb.png";
+    const ANNOTATIONS: &str = "annotations.json";
+    const META: &str = "meta.json";
+    const THUMB_SIZE: u32 = 160;
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 带标注的最终图像
+    pub fn image_path(&self) -> PathBuf {
+        self.dir.join(Self::IMAGE)
+    }
+
+    pub fn load_image(&self) -> Result<RgbaImage> {
+        Ok(image::open(self.image_path())?.to_rgba8())
+    }
+
+    /// 未标注的原始像素
+    pub fn load_original(&self) -> Result<RgbaImage> {
+        Ok(image::open(self.dir.join(Self::ORIGINAL))?.to_rgba8())
+    }
+
+    pub fn load_thumbnail(&self) -> Result<RgbaImage> {
+        Ok(image::open(self.dir.join(Self::THUMB))?.to_rgba8())
+    }
+
+    pub fn load_annotations(&self) -> Result<AnnotationDoc> {
+        let path = self.dir.join(Self::ANNOTATIONS);
+        if !path.exists() {
+            return Ok(AnnotationDoc::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// 原始像素和标注组成的可编辑项目
+    pub fn load_project(&self) -> Result<Project> {
+        Ok(Project::new(
+            self.load_original()?,
+            self.load_annotations()?,
+        ))
+    }
+
+    /// 磁盘占用（字节）
+    pub fn size_bytes(&self) -> u64 {
+        fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok()?.metadata().ok())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// 把截图写入 `History::reserve` 分配的目录，失败时删除目录
+    pub fn write(
+        dir: PathBuf,
+        image: &RgbaImage,
+        original: &RgbaImage,
+        annotations: &AnnotationDoc,
+        meta: CaptureMeta,
+    ) -> Result<Self> {
+        let result = Self::write_files(&dir, image, original, annotations, &meta);
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&dir);
+        }
+        result?;
+        let id = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("bad history entry name")?
+            .to_string();
+        Ok(Self { id, meta, dir })
+    }
+
+    fn write_files(
+        dir: &Path,
+        image: &RgbaImage,
+        original: &RgbaImage,
+        annotations: &AnnotationDoc,
+        meta: &CaptureMeta,
+    ) -> Result<()> {
+        image.save(dir.join(Self::IMAGE))?;
+        original.save(dir.join(Self::ORIGINAL))?;
+        let thumb_w = Self::THUMB_SIZE.min(image.width()).max(1);
+        let thumb_h = (thumb_w * image.height() / image.width().max(1)).max(1);
+        image::imageops::thumbnail(image, thumb_w, thumb_h).save(dir.join(Self::THUMB))?;
+        fs::write(
+            dir.join(Self::ANNOTATIONS),
+            serde_json::to_string(annotations)?,
+        )?;
+        fs::write(dir.join(Self::META), serde_json::to_string_pretty(meta)?)?;
+        Ok(())
+    }
+
+    fn load(dir: PathBuf) -> Result<Self> {
+        let meta: CaptureMeta = serde_json::from_str(&fs::read_to_string(dir.join(Self::META))?)?;
+        let id = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("bad history entry name")?
+            .to_string();
+        Ok(Self { id, meta, dir })
+    }
+}
+
+/// 截图历史，按时间倒序保存在磁盘上
+pub struct History {
+    dir: PathBuf,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// 默认目录 `<data_dir>/pxshot/history`
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("pxshot").join("history"))
+    }
+
+    /// 打开历史目录并读取所有记录，损坏的记录会被跳过
+    ///
+    /// 分配了目录但没写完的记录（没有 `meta.json`）会被删除；刚创建的可能正由
+    /// 另一个实例写入，先保留。
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut entries = Vec::new();
+        for path in fs::read_dir(&dir)?.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if !path.is_dir() {
+                continue;
+            }
+            if path.join(HistoryEntry::META).exists() {
+                if let Ok(entry) = HistoryEntry::load(path) {
+                    entries.push(entry);
+                }
+            } else if is_stale(&path) {
+                let _ = fs::remove_dir_all(&path);
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.meta
+                .timestamp
+                .cmp(&a.meta.timestamp)
+                .then(b.id.cmp(&a.id))
+        });
+        Ok(Self { dir, entries })
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    pub fn search<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a HistoryEntry> {
+        self.entries.iter().filter(move |e| e.meta.matches(query))
+    }
+
+    /// 保存一次截图：最终图像、原始像素、标注文档、缩略图和元数据
+    pub fn add(
+        &mut self,
+        image: &RgbaImage,
+        original: &RgbaImage,
+        annotations: &AnnotationDoc,
+        meta: CaptureMeta,
+    ) -> Result<&HistoryEntry> {
+        let dir = self.reserve()?;
+        let entry = HistoryEntry::write(dir, image, original, annotations, meta)?;
+        self.insert(entry);
+        Ok(&self.entries[0])
+    }
+
+    /// 为一次截图分配记录目录，文件可以之后在后台用 `HistoryEntry::write` 写入
+    pub fn reserve(&self) -> Result<PathBuf> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut id = millis.to_string();
+        let mut n = 1;
+        while self.dir.join(&id).exists() {
+            id = format!("{millis}-{n}");
+            n += 1;
+        }
+        let dir = self.dir.join(&id);
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// 把写好的记录加到最前面
+    pub fn insert(&mut self, entry: HistoryEntry) {
+        self.entries.insert(0, entry);
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<()> {
+        if let Some(idx) = self.entries.iter().position(|e| e.id == id) {
+            let entry = self.entries.remove(idx);
+            fs::remove_dir_all(entry.dir)?;
+        }
+        Ok(())
+    }
+
+    /// 按保留策略删除最旧的记录，返回删除的数量
+    pub fn apply_retention(&mut self, config: &HistoryConfig) -> Result<usize> {
+        let now = now_secs();
+        let max_age = config.max_age_days * 24 * 60 * 60;
+        let max_bytes = config.max_size_mb * 1024 * 1024;
+
+        let mut keep = 0;
+        let mut total = 0;
+        for entry in &self.entries {
+            total += entry.size_bytes();
+            let too_many = config.max_entries > 0 && keep >= config.max_entries;
+            let too_old = max_age > 0 && now.saturating_sub(entry.meta.timestamp) > max_age;
+            let too_big = max_bytes > 0 && total > max_bytes && keep > 0;
+            if too_many || too_old || too_big {
+                break;
+            }
+            keep += 1;
+        }
+
+        let removed: Vec<String> = self.entries[keep..].iter().map(|e| e.id.clone()).collect();
+        for id in &removed {
+            self.delete(id)?;
+        }
+        Ok(removed.len())
+    }
+}
+
+/// 写入一条记录用不了这么久，超过的视为中途放弃
+const STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > STALE_AFTER))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 把 Unix 秒格式化为 `YYYY-MM-DD HH:MM:SS UTC`
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Howard Hinnant 的 civil_from_days 算法
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,200 @@
+#[cfg(all(unix, not(target_os = "macos")))]
+mod x11;
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, bail};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// 全局快捷键触发的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    Region,
+    FullScreen,
+    LastRegion,
+}
+
+/// 快捷键绑定，空字符串表示不绑定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub region: String,
+    pub full_screen: String,
+    pub last_region: String,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            region: "Print".to_string(),
+            full_screen: "Shift+Print".to_string(),
+            last_region: "Ctrl+Print".to_string(),
+        }
+    }
+}
+
+impl HotkeyConfig {
+    /// 解析所有绑定，重复的组合键视为错误
+    pub fn bindings(&self) -> Result<Vec<(Hotkey, HotkeyAction)>> {
+        let mut bindings: Vec<(Hotkey, HotkeyAction)> = Vec::new();
+        for (text, action) in [
+            (&self.region, HotkeyAction::Region),
+            (&self.full_screen, HotkeyAction::FullScreen),
+            (&self.last_region, HotkeyAction::LastRegion),
+        ] {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let hotkey = Hotkey::parse(text)?;
+            if let Some((_, other)) = bindings.iter().find(|(h, _)| *h == hotkey) {
+                bail!("{text} is bound to both {other:?} and {action:?}");
+            }
+            bindings.push((hotkey, action));
+        }
+        Ok(bindings)
+    }
+}
+
+/// 修饰键位，与 X11 的修饰键掩码一致
+pub mod modifiers {
+    pub const SHIFT: u16 = 1;
+    pub const CTRL: u16 = 1 << 2;
+    pub const ALT: u16 = 1 << 3;
+    pub const SUPER: u16 = 1 << 6;
+}
+
+/// 一个组合键：修饰键 + X11 keysym
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub modifiers: u16,
+    pub keysym: u32,
+    name: &'static str,
+}
+
+impl Hotkey {
+    /// 解析 `Ctrl+Shift+Print` 形式的组合键
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let Some(key) = parts.pop().filter(|k| !k.is_empty()) else {
+            bail!("empty hotkey {text:?}");
+        };
+
+        let mut mods = 0;
+        for part in parts {
+            mods |= match part.to_ascii_lowercase().as_str() {
+                "shift" => modifiers::SHIFT,
+                "ctrl" | "control" => modifiers::CTRL,
+                "alt" | "mod1" => modifiers::ALT,
+                "super" | "win" | "mod4" => modifiers::SUPER,
+                _ => bail!("unknown modifier {part:?} in {text:?}"),
+            };
+        }
+
+        let Some((name, keysym)) = keysym_from_name(key) else {
+            bail!("unknown key {key:?} in {text:?}");
+        };
+        Ok(Self {
+            modifiers: mods,
+            keysym,
+            name,
+        })
+    }
+}
+
+impl std::fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (bit, name) in [
+            (modifiers::CTRL, "Ctrl"),
+            (modifiers::ALT, "Alt"),
+            (modifiers::SUPER, "Super"),
+            (modifiers::SHIFT, "Shift"),
+        ] {
+            if self.modifiers & bit != 0 {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(self.name)
+    }
+}
+
+/// 按名称查找 keysym（不区分大小写），返回规范名称
+fn keysym_from_name(name: &str) -> Option<(&'static str, u32)> {
+    const NAMED: &[(&str, u32)] = &[
+        ("Print", 0xff61),
+        ("Pause", 0xff13),
+        ("Scroll_Lock", 0xff14),
+        ("Insert", 0xff63),
+        ("Home", 0xff50),
+        ("End", 0xff57),
+        ("Page_Up", 0xff55),
+        ("Page_Down", 0xff56),
+        ("Space", 0x20),
+    ];
+    const FUNCTION: [&str; 12] = [
+        "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+    ];
+    const LETTERS: [&str; 26] = [
+        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R",
+        "S", "T", "U", "V", "W", "X", "Y", "Z",
+    ];
+    const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+    if let Some(&(canonical, sym)) = NAMED.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+        return Some((canonical, sym));
+    }
+    if let Some(i) = FUNCTION.iter().position(|n| n.eq_ignore_ascii_case(name)) {
+        return Some((FUNCTION[i], 0xffbe + i as u32));
+    }
+    if let Some(i) = LETTERS.iter().position(|n| n.eq_ignore_ascii_case(name)) {
+        // 小写字母的 keysym
+        return Some((LETTERS[i], 0x61 + i as u32));
+    }
+    if let Some(i) = DIGITS.iter().position(|n| *n == name) {
+        return Some((DIGITS[i], 0x30 + i as u32));
+    }
+    None
+}
+
+/// 全局快捷键监听器：后台线程抓取按键，把动作发给界面线程
+pub struct HotkeyListener {
+    rx: Receiver<HotkeyAction>,
+    waker: Arc<Mutex<Option<egui::Context>>>,
+}
+
+impl HotkeyListener {
+    /// 注册所有绑定；任一组合键已被其他程序占用时返回错误
+    pub fn start(bindings: Vec<(Hotkey, HotkeyAction)>) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let waker: Arc<Mutex<Option<egui::Context>>> = Arc::default();
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        {
+            let waker = waker.clone();
+            x11::listen(bindings, move |action| {
+                let _ = tx.send(action);
+                if let Some(ctx) = &*waker.lock().unwrap() {
+                    ctx.request_repaint();
+                }
+            })?;
+        }
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        {
+            let _ = (bindings, tx);
+            bail!("global hotkeys are only supported on X11");
+        }
+
+        #[allow(unreachable_code)]
+        Ok(Self { rx, waker })
+    }
+
+    /// 收到按键时唤醒界面
+    pub fn set_waker(&self, ctx: &egui::Context) {
+        *self.waker.lock().unwrap() = Some(ctx.clone());
+    }
+
+    pub fn try_recv(&self) -> Option<HotkeyAction> {
+        self.rx.try_recv().ok()
+    }
+}
@@ -0,0 +1,80 @@
+use std::thread;
+
+use anyhow::{Result, anyhow};
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{ConnectionExt, GrabMode, Keycode, ModMask};
+
+use super::{Hotkey, HotkeyAction};
+
+/// 抓取时要忽略的锁定键组合（CapsLock、NumLock）
+const LOCK_MASKS: [u16; 4] = [0, 1 << 1, 1 << 4, (1 << 1) | (1 << 4)];
+
+/// 在根窗口上抓取所有组合键，并在后台线程中分发按键
+pub fn listen(
+    bindings: Vec<(Hotkey, HotkeyAction)>,
+    on_key: impl Fn(HotkeyAction) + Send + 'static,
+) -> Result<()> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+
+    let mut grabbed: Vec<(Keycode, u16, HotkeyAction)> = Vec::new();
+    for (hotkey, action) in bindings {
+        let keycode = keycode_for(&conn, hotkey.keysym)?
+            .ok_or_else(|| anyhow!("{hotkey} has no key on this keyboard"))?;
+        for lock in LOCK_MASKS {
+            let modifiers = ModMask::from(hotkey.modifiers | lock);
+            let result = conn
+                .grab_key(
+                    true,
+                    root,
+                    modifiers,
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )?
+                .check();
+            match result {
+                Ok(()) => {}
+                Err(ReplyError::X11Error(_)) => {
+                    return Err(anyhow!(
+                        "{hotkey} is already grabbed by another application; \
+                         change it in the config or free it first"
+                    ));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        grabbed.push((keycode, hotkey.modifiers, action));
+    }
+
+    thread::Builder::new()
+        .name("pxshot-hotkeys".into())
+        .spawn(move || {
+            let ignored = LOCK_MASKS[3];
+            while let Ok(event) = conn.wait_for_event() {
+                let Event::KeyPress(ev) = event else {
+                    continue;
+                };
+                let state = u16::from(ev.state) & !ignored;
+                if let Some((_, _, action)) = grabbed
+                    .iter()
+                    .find(|(keycode, mods, _)| *keycode == ev.detail && *mods == state)
+                {
+                    on_key(*action);
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// 查找产生该 keysym 的键码
+fn keycode_for(conn: &impl Connection, keysym: u32) -> Result<Option<Keycode>> {
+    let setup = conn.setup();
+    let (min, max) = (setup.min_keycode, setup.max_keycode);
+    let reply = conn.get_keyboard_mapping(min, max - min + 1)?.reply()?;
+    let per = reply.keysyms_per_keycode.max(1) as usize;
+    let index = reply.keysyms.iter().position(|&sym| sym == keysym);
+    Ok(index.map(|i| min + (i / per) as u8))
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use eframe::egui::{self, ViewportCommand};
+
+use super::{IpcCall, IpcServer, Request, Response};
+use crate::App;
+use crate::document::Project;
+use crate::mode::{CaptureTarget, Mode};
+
+impl App {
+    /// 在 `path` 上监听控制命令
+    pub fn start_ipc(&mut self, path: PathBuf) -> Result<()> {
+        self.ipc = Some(IpcServer::bind(path)?);
+        Ok(())
+    }
+
+    /// 处理排队的控制命令
+    pub(crate) fn poll_ipc(&mut self, ctx: &egui::Context) {
+        while let Some(call) = self.ipc.as_ref().and_then(IpcServer::try_recv) {
+            self.handle_ipc(ctx, call);
+        }
+    }
+
+    fn handle_ipc(&mut self, ctx: &egui::Context, call: IpcCall) {
+        let busy = !matches!(self.mode, Mode::Idle) || self.ipc_reply.is_some();
+        match &call.request {
+            Request::CaptureRegion { .. } | Request::CaptureFull | Request::OpenFile { .. }
+                if busy =>
+            {
+                call.respond(Response::error("busy with another capture"));
+            }
+            Request::CaptureRegion { region } => {
+                let region = *region;
+                let (_, reply) = call.into_reply();
+                self.ipc_reply = Some(reply);
+                match region {
+                    Some(rect) => {
+                        self.start_delayed_capture(ctx, CaptureTarget::Region(rect), Duration::ZERO)
+                    }
+                    None => self.start_region_capture(ctx),
+                }
+            }
+            Request::CaptureFull => {
+                let (_, reply) = call.into_reply();
+                self.ipc_reply = Some(reply);
+                self.start_delayed_capture(ctx, CaptureTarget::FullScreen, Duration::ZERO);
+            }
+            Request::OpenFile { path } => match Project::open(path) {
+                Ok(project) => {
+                    let (_, reply) = call.into_reply();
+                    self.ipc_reply = Some(reply);
+                    self.edit_image(ctx, project.image, &project.annotations);
+                }
+                Err(e) => call.respond(Response::error(format!("{e:#}"))),
+            },
+            Request::Pin { path } => {
+                let image = match path {
+                    Some(path) => image::open(path)
+                        .map(|img| img.to_rgba8())
+                        .map_err(|e| format!("open {}: {e}", path.display())),
+                    None => self
+                        .screenshot
+                        .clone()
+                        .ok_or("nothing captured yet".to_string()),
+                };
+                match image {
+                    Ok(image) => {
+                        self.pins.pin(image, None);
+                        call.respond(Response::ok());
+                    }
+                    Err(e) => call.respond(Response::error(e)),
+                }
+            }
+            Request::GetLast => {
+                let last = self.last_capture_path.clone().or_else(|| {
+                    let history = self.history.as_ref()?;
+                    history.entries().first().map(|e| e.image_path())
+                });
+                call.respond(match last {
+                    Some(path) => Response::path(path),
+                    None => Response::error("nothing captured yet"),
+                });
+            }
+            Request::Quit => {
+                call.respond(Response::ok());
+                ctx.send_viewport_cmd(ViewportCommand::Close);
+            }
+        }
+    }
+}
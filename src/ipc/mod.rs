@@ -0,0 +1,83 @@
+#[cfg(unix)]
+mod handler;
+#[cfg(unix)]
+mod server;
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::capture::RectPx;
+
+#[cfg(unix)]
+pub use server::{IpcCall, IpcServer, send};
+
+/// 控制命令，每条为一行 JSON，如 `{"cmd":"capture-full"}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case")]
+pub enum Request {
+    /// 交互式框选；给出 region 时直接截取该区域
+    CaptureRegion {
+        #[serde(default)]
+        region: Option<RectPx>,
+    },
+    CaptureFull,
+    /// 在编辑器中打开图像或项目，完成后返回结果
+    OpenFile {
+        path: PathBuf,
+    },
+    /// 钉图：指定文件，或最近一次截图
+    Pin {
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+    /// 最近一次截图的文件路径
+    GetLast,
+    Quit,
+}
+
+/// 命令的结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn path(path: PathBuf) -> Self {
+        Self {
+            ok: true,
+            path: Some(path),
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            path: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+/// 默认的控制 socket：`$XDG_RUNTIME_DIR/pxshot.sock`，没有时放在临时目录
+pub fn default_socket_path() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("pxshot.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_default();
+            std::env::temp_dir().join(format!("pxshot-{user}.sock"))
+        }
+    }
+}
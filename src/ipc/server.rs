@@ -0,0 +1,147 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use eframe::egui;
+
+use super::{Request, Response};
+
+/// 收到的一条命令，处理完后用 `respond` 回复客户端
+pub struct IpcCall {
+    pub request: Request,
+    reply: Sender<Response>,
+}
+
+impl IpcCall {
+    pub fn respond(self, response: Response) {
+        let _ = self.reply.send(response);
+    }
+
+    /// 拆出回复通道，用于稍后（如截图完成后）回复
+    pub fn into_reply(self) -> (Request, Sender<Response>) {
+        (self.request, self.reply)
+    }
+}
+
+/// Unix socket 控制服务：后台线程接受连接，命令交给界面线程处理
+pub struct IpcServer {
+    path: PathBuf,
+    rx: Receiver<IpcCall>,
+    waker: Arc<Mutex<Option<egui::Context>>>,
+}
+
+impl IpcServer {
+    /// 绑定 socket；已有实例在监听时返回错误，残留的 socket 文件会被删除
+    pub fn bind(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                bail!("another instance is listening on {}", path.display());
+            }
+            std::fs::remove_file(&path)
+                .with_context(|| format!("remove stale socket {}", path.display()))?;
+        }
+        let listener =
+            UnixListener::bind(&path).with_context(|| format!("bind {}", path.display()))?;
+
+        let (tx, rx) = mpsc::channel();
+        let waker: Arc<Mutex<Option<egui::Context>>> = Arc::default();
+        {
+            let waker = waker.clone();
+            thread::Builder::new()
+                .name("pxshot-ipc".into())
+                .spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let tx = tx.clone();
+                        let waker = waker.clone();
+                        thread::spawn(move || serve(stream, &tx, &waker));
+                    }
+                })?;
+        }
+
+        Ok(Self { path, rx, waker })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 收到命令时唤醒界面
+    pub fn set_waker(&self, ctx: &egui::Context) {
+        *self.waker.lock().unwrap() = Some(ctx.clone());
+    }
+
+    pub fn try_recv(&self) -> Option<IpcCall> {
+        self.rx.try_recv().ok()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<IpcCall> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// 处理一个连接：逐行读取命令，逐行回复
+fn serve(stream: UnixStream, tx: &Sender<IpcCall>, waker: &Mutex<Option<egui::Context>>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let (reply, reply_rx) = mpsc::channel();
+                if tx.send(IpcCall { request, reply }).is_err() {
+                    return;
+                }
+                if let Some(ctx) = &*waker.lock().unwrap() {
+                    ctx.request_repaint();
+                }
+                reply_rx
+                    .recv()
+                    .unwrap_or_else(|_| Response::error("request dropped"))
+            }
+            Err(e) => Response::error(format!("bad request: {e}")),
+        };
+
+        let Ok(mut text) = serde_json::to_string(&response) else {
+            return;
+        };
+        text.push('\n');
+        if writer.write_all(text.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/// 客户端：发送一条命令并等待回复
+pub fn send(path: &Path, request: &Request) -> Result<Response> {
+    let mut stream = UnixStream::connect(path)
+        .with_context(|| format!("no pxshot instance listening on {}", path.display()))?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        bail!("connection closed without a reply");
+    }
+    Ok(serde_json::from_str(&reply)?)
+}
@@ -1,7 +1,19 @@
+pub mod actions;
 pub mod app;
 pub mod capture;
+pub mod cli;
 pub mod clipboard;
+pub mod config;
+pub mod document;
+pub mod export;
+pub mod history;
+pub mod hotkey;
+pub mod ipc;
 pub mod mode;
 pub mod overlay;
+pub mod pin;
+pub mod record;
+pub mod scroll;
 pub mod tools;
+pub mod upload;
 pub use app::App;
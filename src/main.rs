@@ -1,19 +1,122 @@
 use eframe::egui;
 use pxshot::App;
+use pxshot::cli::{self, Command};
+use pxshot::document::Project;
+use pxshot::mode::CaptureTarget;
 
 fn main() -> eframe::Result {
+    let command = match cli::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Command::Ctl(request) = &command {
+        std::process::exit(ctl(request));
+    }
+
+    let mut app = App::new();
+    let mut visible = true;
+    match command {
+        Command::Gui => start_ipc(&mut app),
+        Command::Daemon => {
+            if let Err(e) = app.start_daemon() {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+            start_ipc(&mut app);
+            visible = false;
+        }
+        Command::Ctl(_) => unreachable!("handled above"),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Command::Capture {
+            target,
+            delay,
+            output,
+            last_region,
+        } => {
+            let target = match (last_region, app.last_region()) {
+                (false, _) => target,
+                (true, Some(rect)) => CaptureTarget::Region(rect),
+                (true, None) => {
+                    eprintln!("no previous region to capture");
+                    std::process::exit(1);
+                }
+            };
+            app.capture_on_start(target, delay, output);
+        }
+        Command::Edit { input, output } => match Project::open(&input) {
+            Ok(project) => app.open_on_start(project, output),
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        },
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_title("pxshot")
-            .with_inner_size([520.0, 320.0])
+            .with_inner_size(App::WINDOW_SIZE)
             // overlay 要看到桌面，需要透明窗口
-            .with_transparent(true),
+            .with_transparent(true)
+            // 接收拖放的图像文件
+            .with_drag_and_drop(true)
+            // 常驻模式下保持隐藏，直到快捷键触发
+            .with_visible(visible),
         ..Default::default()
     };
 
     eframe::run_native(
         "pxshot",
         options,
-        Box::new(|_cc| Ok(Box::new(App::default()))),
+        Box::new(|cc| {
+            app.attach(&cc.egui_ctx);
+            Ok(Box::new(app))
+        }),
     )
 }
+
+/// 长期运行的实例监听控制 socket，失败时只打印警告
+fn start_ipc(app: &mut App) {
+    #[cfg(unix)]
+    if let Err(e) = app.start_ipc(pxshot::ipc::default_socket_path()) {
+        eprintln!("control socket disabled: {e:#}");
+    }
+    #[cfg(not(unix))]
+    let _ = app;
+}
+
+/// `pxshot ctl`：把命令发给运行中的实例并打印结果，返回退出码
+fn ctl(request: &pxshot::ipc::Request) -> i32 {
+    #[cfg(unix)]
+    {
+        match pxshot::ipc::send(&pxshot::ipc::default_socket_path(), request) {
+            Ok(response) if response.ok => {
+                if let Some(path) = response.path {
+                    println!("{}", path.display());
+                }
+                0
+            }
+            Ok(response) => {
+                eprintln!("{}", response.error.unwrap_or_default());
+                1
+            }
+            Err(e) => {
+                eprintln!("{e:#}");
+                1
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = request;
+        eprintln!("ctl is only supported on Unix");
+        1
+    }
+}
@@ -4,6 +4,15 @@ use eframe::egui::{Pos2, Rect};
 
 use crate::{capture::RectPx, overlay::HitZone};
 
+/// 定时截图的目标
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CaptureTarget {
+    #[default]
+    Interactive, // 先截取整屏，再在冻结帧上框选
+    FullScreen,
+    Region(RectPx),
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub enum Mode {
     #[default]
@@ -17,8 +26,15 @@ pub enum Mode {
         dragging: Option<HitZone>,
     },
     PendingCapture {
-        rect_px: RectPx,
-        rect_points: Rect, // 选区的逻辑坐标（用于箭头渲染）
-        hidden_at: Instant,
+        target: CaptureTarget,
+        rect_points: Option<Rect>, // 遮罩中选区的逻辑坐标（用于箭头渲染）
+        due: Instant,              // 到点后截图
+        pin: bool,                 // 截图后钉到屏幕上
+    },
+    Scrolling {
+        rect_points: Rect, // 长截图选区的逻辑坐标
+    },
+    Recording {
+        rect_points: Rect, // 录屏选区的逻辑坐标
     },
 }
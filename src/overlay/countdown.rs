@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use eframe::egui::{self, Color32, RichText, ViewportCommand, WindowLevel};
+
+use crate::App;
+use crate::mode::{CaptureTarget, Mode};
+
+impl App {
+    /// 倒计时窗口的大小
+    const COUNTDOWN_SIZE: [f32; 2] = [140.0, 90.0];
+
+    /// 延迟 `delay` 后截图；倒计时期间主窗口缩成置顶的小窗
+    pub fn start_delayed_capture(
+        &mut self,
+        ctx: &egui::Context,
+        target: CaptureTarget,
+        delay: Duration,
+    ) {
+        let min_delay = Duration::from_millis(Self::CAPTURE_DELAY_MS);
+        self.screenshot = None;
+        self.texture = None;
+
+        if delay > min_delay {
+            self.countdown_shown = true;
+            ctx.send_viewport_cmd(ViewportCommand::Decorations(false));
+            ctx.send_viewport_cmd(ViewportCommand::InnerSize(Self::COUNTDOWN_SIZE.into()));
+            ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::AlwaysOnTop));
+            ctx.send_viewport_cmd(ViewportCommand::Visible(true));
+        } else {
+            ctx.send_viewport_cmd(ViewportCommand::Visible(false));
+        }
+
+        self.mode = Mode::PendingCapture {
+            target,
+            rect_points: None,
+            due: Instant::now() + delay.max(min_delay),
+            pin: false,
+        };
+        ctx.request_repaint();
+    }
+
+    /// 倒计时小窗：剩余秒数和取消按钮
+    pub(crate) fn countdown_ui(&mut self, ctx: &egui::Context, remaining: Duration) {
+        let mut cancel = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                let secs = remaining.as_secs_f32().ceil();
+                ui.label(
+                    RichText::new(format!("{secs:.0}"))
+                        .size(36.0)
+                        .strong()
+                        .color(Color32::from_rgb(0, 122, 255)),
+                );
+                if ui.small_button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+        if cancel {
+            self.hide_countdown(ctx);
+            self.cancel_overlay(ctx);
+            if !self.exit_when_done {
+                ctx.send_viewport_cmd(ViewportCommand::Visible(true));
+            }
+        }
+    }
+
+    /// 截图前收起倒计时窗口，并恢复主窗口外观
+    pub(crate) fn hide_countdown(&mut self, ctx: &egui::Context) {
+        if !self.countdown_shown {
+            return;
+        }
+        self.countdown_shown = false;
+        ctx.send_viewport_cmd(ViewportCommand::Visible(false));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(Self::WINDOW_SIZE.into()));
+        ctx.send_viewport_cmd(ViewportCommand::Decorations(true));
+        ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::Normal));
+    }
+}
@@ -0,0 +1,62 @@
+use eframe::egui::{self, Color32, Pos2, Rect, Vec2};
+
+use crate::capture::{RectPx, RgbaImage};
+
+/// 冻结的背景帧：编辑已有图像时代替实时桌面显示在遮罩下面
+pub struct FrozenFrame {
+    pub image: RgbaImage,
+    pub is_screen: bool, // 是刚截取的整屏画面，坐标与屏幕一致
+    texture: Option<egui::TextureHandle>,
+}
+
+impl FrozenFrame {
+    pub fn new(image: RgbaImage) -> Self {
+        Self {
+            image,
+            is_screen: false,
+            texture: None,
+        }
+    }
+
+    pub fn from_screen(image: RgbaImage) -> Self {
+        Self {
+            is_screen: true,
+            ..Self::new(image)
+        }
+    }
+
+    /// 图像在屏幕上的逻辑区域（左上角对齐屏幕原点，1:1 像素显示）
+    pub fn rect(&self, ppp: f32) -> Rect {
+        Rect::from_min_size(
+            Pos2::ZERO,
+            Vec2::new(self.image.width() as f32, self.image.height() as f32) / ppp,
+        )
+    }
+
+    /// 绘制背景：图像外的区域填充为黑色
+    pub fn paint(&mut self, painter: &egui::Painter, ctx: &egui::Context, full: Rect) {
+        let image = &self.image;
+        let texture = self.texture.get_or_insert_with(|| {
+            let size = [image.width() as usize, image.height() as usize];
+            let color = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+            ctx.load_texture("frozen", color, Default::default())
+        });
+
+        painter.rect_filled(full, 0.0, Color32::BLACK);
+        painter.image(
+            texture.id(),
+            self.rect(ctx.pixels_per_point()),
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::WHITE,
+        );
+    }
+
+    /// 从冻结帧中裁剪选区，超出图像的部分会被截掉
+    pub fn crop(&self, r: RectPx) -> RgbaImage {
+        let x = r.x.min(self.image.width().saturating_sub(1));
+        let y = r.y.min(self.image.height().saturating_sub(1));
+        let w = r.w.min(self.image.width() - x).max(1);
+        let h = r.h.min(self.image.height() - y).max(1);
+        image::imageops::crop_imm(&self.image, x, y, w, h).to_image()
+    }
+}
@@ -1,11 +1,19 @@
+mod countdown;
+mod frozen;
 mod resize;
 mod selected;
 mod selecting;
+mod size;
 mod toolbar;
 
 use crate::capture::RectPx;
 use eframe::egui::{self, Color32, Pos2, Rect};
+pub use frozen::FrozenFrame;
 pub use resize::HitZone;
+pub use size::{
+    AspectRatio, SIZE_PRESETS, SizeEditor, SizeInput, lock_aspect, parse_aspect_input,
+    parse_size_input, rect_from_size_input,
+};
 pub const DIM_ALPHA: u8 = 120;
 
 /// 使用单一 Mesh 绘制带洞的暗幕，避免多块分离导致的不同步消失
@@ -94,10 +102,53 @@ pub fn points_rect_to_px(ctx: &egui::Context, r: Rect) -> RectPx {
     RectPx { x, y, w, h }
 }
 
-/// 绘制选区尺寸标签
-pub fn draw_size_label(painter: &egui::Painter, ctx: &egui::Context, rect: Rect) {
+/// 物理像素矩形转换为逻辑坐标
+pub fn px_rect_to_points(r: RectPx, ppp: f32) -> Rect {
+    Rect::from_min_size(
+        Pos2::new(r.x as f32, r.y as f32) / ppp,
+        egui::Vec2::new(r.w as f32, r.h as f32) / ppp,
+    )
+}
+
+/// 放在选区旁边的辅助窗口位置，选区外放不下时返回 None（窗口应隐藏，以免被截进画面）
+pub fn side_window_pos(ctx: &egui::Context, rect: Rect, size: egui::Vec2) -> Option<Pos2> {
+    let monitor = ctx
+        .input(|i| i.viewport().monitor_size)
+        .unwrap_or(egui::Vec2::INFINITY);
+    side_window_placement(rect, size, monitor)
+}
+
+/// 依次尝试选区的右、左、下、上，返回第一个完全在屏幕内且不与选区重叠的位置
+pub fn side_window_placement(rect: Rect, size: egui::Vec2, monitor: egui::Vec2) -> Option<Pos2> {
+    const GAP: f32 = 16.0;
+    let clamp = |v: f32, len: f32, max: f32| v.min(max - len).max(0.0);
+    let x = clamp(rect.min.x, size.x, monitor.x);
+    let y = clamp(rect.min.y, size.y, monitor.y);
+    let candidates = [
+        Pos2::new(rect.max.x + GAP, y),
+        Pos2::new(rect.min.x - GAP - size.x, y),
+        Pos2::new(x, rect.max.y + GAP),
+        Pos2::new(x, rect.min.y - GAP - size.y),
+    ];
+    let screen = Rect::from_min_size(Pos2::ZERO, monitor);
+    candidates.into_iter().find(|&pos| {
+        let window = Rect::from_min_size(pos, size);
+        screen.contains_rect(window) && !window.intersects(rect)
+    })
+}
+
+/// 绘制选区尺寸标签，返回标签所占区域（点击可编辑尺寸）
+pub fn draw_size_label(
+    painter: &egui::Painter,
+    ctx: &egui::Context,
+    rect: Rect,
+    aspect: AspectRatio,
+) -> Rect {
     let rect_px = points_rect_to_px(ctx, rect);
-    let size_text = format!("{}×{}", rect_px.w, rect_px.h);
+    let size_text = match aspect {
+        AspectRatio::Free => format!("{}×{}", rect_px.w, rect_px.h),
+        _ => format!("{}×{}  {}", rect_px.w, rect_px.h, aspect.name()),
+    };
 
     let text_pos = if rect.min.y < 30.0 {
         Pos2::new(rect.min.x + 4.0, rect.min.y + 18.0)
@@ -111,5 +162,5 @@ pub fn draw_size_label(painter: &egui::Painter, ctx: &egui::Context, rect: Rect)
         size_text,
         font,
         Color32::WHITE,
-    );
+    )
 }
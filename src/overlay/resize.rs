@@ -1,5 +1,7 @@
 use eframe::egui::{self, CursorIcon, Pos2, Rect};
 
+use super::size::lock_aspect;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HitZone {
     None,
@@ -55,8 +57,20 @@ impl HitZone {
         }
     }
 
-    /// 根据拖拽增量调整选区
-    pub fn apply_drag(&self, rect: Rect, delta: egui::Vec2, screen: Rect) -> Rect {
+    /// 根据拖拽增量调整选区，`aspect` 为锁定的宽高比（宽 / 高）
+    pub fn apply_drag(
+        &self,
+        rect: Rect,
+        delta: egui::Vec2,
+        screen: Rect,
+        aspect: Option<f32>,
+    ) -> Rect {
+        if let Some(ratio) = aspect
+            && *self != HitZone::Inside
+        {
+            return self.apply_drag_locked(rect, delta, screen, ratio);
+        }
+
         let mut min = rect.min;
         let mut max = rect.max;
 
@@ -128,4 +142,67 @@ impl HitZone {
         // 限制在屏幕内（调整大小时可以裁剪）
         Rect::from_min_max(min, max).intersect(screen)
     }
+
+    /// 锁定宽高比的调整：角拖拽以对角为锚点，边拖拽以对边中点为锚点
+    fn apply_drag_locked(&self, rect: Rect, delta: egui::Vec2, screen: Rect, ratio: f32) -> Rect {
+        let center = rect.center();
+        let new_rect = match self {
+            HitZone::TopLeft => {
+                let corner = lock_aspect(rect.max, rect.min + delta, ratio);
+                Rect::from_two_pos(rect.max, corner)
+            }
+            HitZone::TopRight => {
+                let anchor = rect.left_bottom();
+                let corner = lock_aspect(anchor, rect.right_top() + delta, ratio);
+                Rect::from_two_pos(anchor, corner)
+            }
+            HitZone::BottomLeft => {
+                let anchor = rect.right_top();
+                let corner = lock_aspect(anchor, rect.left_bottom() + delta, ratio);
+                Rect::from_two_pos(anchor, corner)
+            }
+            HitZone::BottomRight => {
+                let corner = lock_aspect(rect.min, rect.max + delta, ratio);
+                Rect::from_two_pos(rect.min, corner)
+            }
+            HitZone::Top | HitZone::Bottom => {
+                let h = if *self == HitZone::Top {
+                    rect.height() - delta.y
+                } else {
+                    rect.height() + delta.y
+                };
+                let h = h.abs();
+                let w = h * ratio;
+                let y = if *self == HitZone::Top {
+                    rect.max.y - h
+                } else {
+                    rect.min.y
+                };
+                Rect::from_min_size(Pos2::new(center.x - w / 2.0, y), egui::vec2(w, h))
+            }
+            HitZone::Left | HitZone::Right => {
+                let w = if *self == HitZone::Left {
+                    rect.width() - delta.x
+                } else {
+                    rect.width() + delta.x
+                };
+                let w = w.abs();
+                let h = w / ratio;
+                let x = if *self == HitZone::Left {
+                    rect.max.x - w
+                } else {
+                    rect.min.x
+                };
+                Rect::from_min_size(Pos2::new(x, center.y - h / 2.0), egui::vec2(w, h))
+            }
+            HitZone::Inside | HitZone::None => rect,
+        };
+
+        // 超出屏幕时保持原选区，避免裁剪破坏比例
+        if screen.contains_rect(new_rect) {
+            new_rect
+        } else {
+            rect
+        }
+    }
 }
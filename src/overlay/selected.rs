@@ -1,13 +1,13 @@
 use eframe::egui::{self, CursorIcon, Rect, StrokeKind, ViewportCommand};
 use egui::{Color32, Stroke};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::paint_dim_with_hole;
 use crate::App;
-use crate::mode::Mode;
+use crate::mode::{CaptureTarget, Mode};
 use crate::overlay::toolbar::{Toolbar, ToolbarAction};
 use crate::overlay::{DIM_ALPHA, HitZone, draw_size_label, points_rect_to_px};
-use crate::tools::arrow::{DrawState, PopupState};
+use crate::tools::arrow::{DrawState, PopupState, draw_snap_hints};
 
 impl App {
     pub fn overlay_selected_ui(&mut self, ctx: &egui::Context) {
@@ -21,15 +21,21 @@ impl App {
                     _ => return,
                 };
 
-                // Esc = 取消绘制或退出
+                // Esc = 关闭尺寸输入、取消绘制或退出
                 if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-                    if self.arrow_mode_active {
-                        // 如果正在绘制，取消绘制
-                        if self.arrow_drawer.state == DrawState::Drawing {
+                    if self.size_editor.open {
+                        self.close_size_editor();
+                    } else if self.arrow_mode_active {
+                        // 如果正在绘制或框选，先取消
+                        if matches!(
+                            self.arrow_drawer.state,
+                            DrawState::Drawing | DrawState::Selecting
+                        ) {
                             self.arrow_drawer.cancel();
                         } else {
                             // 退出箭头模式
                             self.arrow_mode_active = false;
+                            self.arrow_drawer.deselect();
                             self.arrow_drawer.state = DrawState::Idle;
                         }
                     } else {
@@ -39,25 +45,34 @@ impl App {
                 }
 
                 // Delete 键删除选中的箭头
-                if ctx.input(|i| {
-                    i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)
-                }) {
+                if !ctx.wants_keyboard_input()
+                    && ctx.input(|i| {
+                        i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)
+                    })
+                {
                     self.arrow_drawer.delete_selected();
                 }
+                if self.arrow_mode_active && !ctx.wants_keyboard_input() {
+                    self.handle_arrow_shortcuts(ctx);
+                }
 
                 // 获取鼠标状态
-                let (pos, pressed, down, released, delta) = ctx.input(|i| {
+                let (pos, pressed, down, released, delta, shift) = ctx.input(|i| {
                     (
                         i.pointer.interact_pos(),
                         i.pointer.primary_pressed(),
                         i.pointer.primary_down(),
                         i.pointer.primary_released(),
                         i.pointer.delta(),
+                        i.modifiers.shift,
                     )
                 });
 
                 // 绘制暗幕和边框
                 let painter = ui.painter_at(full);
+                if let Some(frozen) = &mut self.frozen {
+                    frozen.paint(&painter, ctx, full);
+                }
                 paint_dim_with_hole(&painter, full, rect, DIM_ALPHA);
                 painter.rect_stroke(
                     rect,
@@ -70,8 +85,17 @@ impl App {
                 self.arrow_drawer.draw_all(&painter);
                 self.arrow_drawer.draw_selection_handles(&painter);
 
+                // 端点吸附：Shift 锁定角度，否则吸附到选区边缘和其他端点
+                let snapped = pos.map(|p| self.arrow_drawer.snap(p, rect, shift));
+                if let Some((_, hints)) = &snapped
+                    && self.arrow_mode_active
+                    && self.arrow_drawer.is_dragging_point()
+                {
+                    draw_snap_hints(&painter, hints);
+                }
+
                 // 绘制正在绘制的箭头预览
-                if let Some(current_pos) = pos {
+                if let Some((current_pos, _)) = snapped {
                     self.arrow_drawer
                         .draw_preview(&painter, current_pos, &self.arrow_panel.config);
                 }
@@ -81,18 +105,62 @@ impl App {
                 let toolbar_rect = toolbar.calc_rect(rect, full);
                 let arrow_panel_rect = self.arrow_panel.calc_panel_rect(toolbar_rect, full);
 
+                // 尺寸输入弹窗
+                let mut size_editor_rect = None;
+                if self.size_editor.open {
+                    let (new_rect, editor_rect) = self.size_editor_ui(ctx, rect, full);
+                    if let Some(new_rect) = new_rect {
+                        rect = new_rect;
+                        self.mode = Mode::Selected {
+                            rect,
+                            dragging: None,
+                        };
+                    }
+                    size_editor_rect = Some(editor_rect);
+
+                    // 点击弹窗外部时关闭
+                    if let Some(click_pos) = pos
+                        && pressed
+                        && !editor_rect.contains(click_pos)
+                    {
+                        self.close_size_editor();
+                    }
+                }
+
+                // 多选时的排列工具条
+                let arrange_rect = if self.arrow_mode_active && dragging.is_none() {
+                    self.arrow_drawer.arrange_bar_rect(full)
+                } else {
+                    None
+                };
+                if let Some(bar_rect) = arrange_rect
+                    && let Some(action) = self.arrow_drawer.show_arrange_bar(ui, bar_rect)
+                {
+                    self.arrow_drawer.arrange(action, rect);
+                }
+
                 // 检查鼠标是否在 UI 区域内
                 let in_ui_area = pos.is_some_and(|p| {
                     toolbar_rect.contains(p)
-                        || (self.show_arrow_panel && arrow_panel_rect.contains(p))
+                        || arrange_rect.is_some_and(|r| r.contains(p))
+                        || (self.show_arrow_panel
+                            && (arrow_panel_rect.contains(p) || self.arrow_panel.contains(p)))
+                        || size_editor_rect.is_some_and(|r| r.contains(p))
                 });
 
                 // 箭头模式的鼠标处理
                 if self.arrow_mode_active && !in_ui_area {
-                    if let Some(mouse_pos) = pos {
+                    if let (Some(mouse_pos), Some((snapped_pos, _))) = (pos, snapped) {
                         // 只在选区内响应
                         if rect.contains(mouse_pos) {
-                            self.handle_arrow_input(ctx, mouse_pos, pressed, down, released);
+                            self.handle_arrow_input(
+                                ctx,
+                                mouse_pos,
+                                snapped_pos,
+                                pressed,
+                                down,
+                                released,
+                            );
                         }
                     }
                 } else if !self.arrow_mode_active && !in_ui_area {
@@ -111,7 +179,13 @@ impl App {
                     }
 
                     if down && let Some(zone) = new_dragging {
-                        rect = zone.apply_drag(rect, delta, full);
+                        // 按住 Shift 锁定宽高比（自由比例时保持当前比例）
+                        let aspect = shift.then(|| {
+                            self.aspect_ratio
+                                .ratio()
+                                .unwrap_or(rect.width() / rect.height().max(1.0))
+                        });
+                        rect = zone.apply_drag(rect, delta, full, aspect);
                         ctx.request_repaint();
                     }
 
@@ -119,34 +193,53 @@ impl App {
                         new_dragging = None;
                     }
 
+                    // 显示尺寸，点击标签可输入精确尺寸
+                    let label_rect = draw_size_label(&painter, ctx, rect, self.aspect_ratio);
+                    if let Some(p) = pos
+                        && label_rect.contains(p)
+                        && dragging.is_none()
+                    {
+                        ctx.set_cursor_icon(CursorIcon::Text);
+                        if pressed {
+                            self.open_size_editor(ctx, rect);
+                            new_dragging = None;
+                        }
+                    }
+
                     self.mode = Mode::Selected {
                         rect,
                         dragging: new_dragging,
                     };
 
-                    // 显示尺寸
-                    draw_size_label(&painter, ctx, rect);
-
                     // 绘制调整手柄
                     draw_resize_handles(&painter, rect);
                 }
 
                 // 工具栏（只在不拖拽时响应）
                 if dragging.is_none() {
-                    match toolbar.show(ui, toolbar_rect) {
+                    let action = toolbar.show(ui, toolbar_rect);
+                    match action {
                         ToolbarAction::Cancel => {
                             self.cancel_overlay(ctx);
                         }
-                        ToolbarAction::Confirm => {
+                        ToolbarAction::Confirm | ToolbarAction::Pin => {
                             let rect_px = points_rect_to_px(ctx, rect);
+                            self.remember_region(rect_px);
                             ctx.send_viewport_cmd(ViewportCommand::Visible(false));
                             self.mode = Mode::PendingCapture {
-                                rect_px,
-                                rect_points: rect,
-                                hidden_at: Instant::now(),
+                                target: CaptureTarget::Region(rect_px),
+                                rect_points: Some(rect),
+                                due: Instant::now() + Duration::from_millis(App::CAPTURE_DELAY_MS),
+                                pin: action == ToolbarAction::Pin,
                             };
                             ctx.request_repaint();
                         }
+                        ToolbarAction::Scroll => {
+                            self.start_scrolling(ctx, rect);
+                        }
+                        ToolbarAction::Record => {
+                            self.start_recording(ctx, rect);
+                        }
                         ToolbarAction::Arrow => {
                             // 切换箭头模式
                             self.arrow_mode_active = !self.arrow_mode_active;
@@ -154,6 +247,7 @@ impl App {
                             self.show_arrow_panel = self.arrow_mode_active;
                             if !self.show_arrow_panel {
                                 self.arrow_panel.popup_state = PopupState::None;
+                                self.arrow_drawer.deselect();
                             }
                         }
                         ToolbarAction::None => {}
@@ -161,7 +255,28 @@ impl App {
 
                     // 显示箭头工具面板
                     if self.show_arrow_panel {
+                        // 有选中的箭头时面板显示并修改它的样式
+                        self.arrow_panel.editing = self.arrow_drawer.selected_style();
+                        let before = self.arrow_panel.style().clone();
                         self.arrow_panel.show(ui, arrow_panel_rect, full);
+                        if let Some(after) = &self.arrow_panel.editing
+                            && *after != before
+                        {
+                            self.arrow_drawer.restyle_selected(&before, after);
+                        }
+                        // 拖动滑块等连续修改在松开时结束，记为一步
+                        if released {
+                            self.arrow_drawer.seal_history();
+                        }
+
+                        // 默认样式、预设和最近颜色有变化时保存，拖动中不写盘
+                        let settings = self.arrow_panel.settings();
+                        if !down && settings != self.config.arrow {
+                            self.config.arrow = settings;
+                            if let Err(e) = self.config.save() {
+                                eprintln!("save config failed: {e}");
+                            }
+                        }
 
                         // 点击面板和工具栏外部时关闭面板（但不退出箭头模式）
                         if let Some(click_pos) = pos
@@ -184,13 +299,13 @@ impl App {
                 {
                     let cursor = match self.arrow_drawer.state {
                         DrawState::Drawing => CursorIcon::Crosshair,
-                        DrawState::Selected(_) => {
+                        DrawState::Selected => {
                             if let Some(hit) = self.arrow_drawer.hit_endpoint(mouse_pos) {
                                 match hit {
-                                    DrawState::MovingStart(_) | DrawState::MovingEnd(_) => {
-                                        CursorIcon::Grab
-                                    }
-                                    DrawState::MovingWhole(_) => CursorIcon::Move,
+                                    DrawState::MovingStart(_)
+                                    | DrawState::MovingEnd(_)
+                                    | DrawState::MovingHandle(..) => CursorIcon::Grab,
+                                    DrawState::MovingSelection => CursorIcon::Move,
                                     _ => CursorIcon::Crosshair,
                                 }
                             } else {
@@ -199,8 +314,9 @@ impl App {
                         }
                         DrawState::MovingStart(_)
                         | DrawState::MovingEnd(_)
-                        | DrawState::MovingWhole(_) => CursorIcon::Grabbing,
-                        DrawState::Idle => CursorIcon::Crosshair,
+                        | DrawState::MovingSelection
+                        | DrawState::MovingHandle(..) => CursorIcon::Grabbing,
+                        DrawState::Idle | DrawState::Selecting => CursorIcon::Crosshair,
                     };
                     ctx.set_cursor_icon(cursor);
                 }
@@ -208,53 +324,109 @@ impl App {
     }
 
     /// 处理箭头模式下的鼠标输入
+    ///
+    /// `pos` 用于选中箭头，`snapped` 为吸附后的位置，用于绘制和移动端点。
+    /// 按住 Shift 点击可增减选中的箭头，按住 Ctrl 在空白处拖动则框选。
     fn handle_arrow_input(
         &mut self,
         ctx: &egui::Context,
         pos: egui::Pos2,
+        snapped: egui::Pos2,
         pressed: bool,
         down: bool,
         released: bool,
     ) {
         match self.arrow_drawer.state {
-            DrawState::Idle => {
+            DrawState::Idle | DrawState::Selected => {
                 if pressed {
-                    // 先尝试选中已有箭头
-                    if !self.arrow_drawer.try_select(pos) {
-                        // 没选中则开始绘制新箭头
-                        self.arrow_drawer.start_drawing(pos);
-                    }
+                    let modifiers = ctx.input(|i| i.modifiers);
+                    self.arrow_drawer.press(pos, snapped, modifiers);
                 }
             }
             DrawState::Drawing => {
                 if released {
                     self.arrow_drawer
-                        .finish_drawing(pos, &self.arrow_panel.config);
+                        .finish_drawing(snapped, &self.arrow_panel.config);
                 }
                 ctx.request_repaint();
             }
-            DrawState::Selected(_) => {
-                if pressed {
-                    // 检查是否点击了端点
-                    if let Some(hit) = self.arrow_drawer.hit_endpoint(pos) {
-                        self.arrow_drawer.start_move(pos, hit);
-                    } else if !self.arrow_drawer.try_select(pos) {
-                        // 点击空白处，开始绘制新箭头
-                        self.arrow_drawer.start_drawing(pos);
-                    }
+            DrawState::Selecting => {
+                if down {
+                    self.arrow_drawer.update_band(pos);
+                    ctx.request_repaint();
+                }
+                if released {
+                    self.arrow_drawer.finish_band();
                 }
             }
-            DrawState::MovingStart(_) | DrawState::MovingEnd(_) | DrawState::MovingWhole(_) => {
+            DrawState::MovingStart(_)
+            | DrawState::MovingEnd(_)
+            | DrawState::MovingSelection
+            | DrawState::MovingHandle(..) => {
                 if down {
-                    self.arrow_drawer.update_move(pos);
+                    // 整体移动不吸附，避免箭头跳到别处
+                    let target = if self.arrow_drawer.is_dragging_point() {
+                        snapped
+                    } else {
+                        pos
+                    };
+                    self.arrow_drawer.update_move(target);
                     ctx.request_repaint();
                 }
                 if released {
                     self.arrow_drawer.finish_move();
+                    // 双击折线箭头增删拐点
+                    if ctx.input(|i| {
+                        i.pointer
+                            .button_double_clicked(egui::PointerButton::Primary)
+                    }) {
+                        self.arrow_drawer.toggle_waypoint(pos);
+                    }
                 }
             }
         }
     }
+
+    /// 箭头模式的快捷键：撤销、重做、全选、复制、粘贴、原地复制
+    fn handle_arrow_shortcuts(&mut self, ctx: &egui::Context) {
+        let (undo, redo, select_all, duplicate, copy, paste) = ctx.input(|i| {
+            let command = i.modifiers.command;
+            let z = command && i.key_pressed(egui::Key::Z);
+            (
+                z && !i.modifiers.shift,
+                (z && i.modifiers.shift) || (command && i.key_pressed(egui::Key::Y)),
+                command && i.key_pressed(egui::Key::A),
+                command && i.key_pressed(egui::Key::D),
+                i.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                i.events.iter().find_map(|e| match e {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                }),
+            )
+        });
+
+        if undo {
+            self.arrow_drawer.undo();
+        }
+        if redo {
+            self.arrow_drawer.redo();
+        }
+        if select_all {
+            self.arrow_drawer.select_all();
+        }
+        if duplicate {
+            self.arrow_drawer.duplicate_selected();
+        }
+        if copy && let Some(text) = self.arrow_drawer.copy_selected() {
+            // 写入系统剪贴板，便于粘贴到另一张截图
+            if let Err(e) = self.clipboard.copy_text(&text) {
+                eprintln!("copy annotations failed: {e:#}");
+            }
+        }
+        if let Some(text) = paste {
+            self.arrow_drawer.paste(&text);
+        }
+    }
 }
 
 /// 绘制四角的调整手柄
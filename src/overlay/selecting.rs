@@ -4,7 +4,7 @@ use egui::{Color32, Pos2, Rect, Sense, Stroke, Vec2};
 use super::{DIM_ALPHA, paint_dim_with_hole};
 use crate::App;
 use crate::mode::Mode;
-use crate::overlay::draw_size_label;
+use crate::overlay::{draw_size_label, lock_aspect, px_rect_to_points};
 
 impl App {
     pub fn overlay_selecting_ui(&mut self, ctx: &egui::Context) {
@@ -14,13 +14,14 @@ impl App {
                 let full = ui.max_rect();
                 let resp = ui.allocate_rect(full, Sense::click_and_drag());
 
-                let (pos, pressed, down, released, esc) = ctx.input(|i| {
+                let (pos, pressed, down, released, esc, shift) = ctx.input(|i| {
                     (
                         i.pointer.interact_pos(),
                         i.pointer.primary_pressed(),
                         i.pointer.primary_down(),
                         i.pointer.primary_released(),
                         i.key_pressed(egui::Key::Escape),
+                        i.modifiers.shift,
                     )
                 });
 
@@ -29,6 +30,18 @@ impl App {
                     return;
                 }
 
+                // R：直接选中上次的选区
+                if ctx.input(|i| i.key_pressed(egui::Key::R))
+                    && let Some(last) = self.last_region()
+                {
+                    self.mode = Mode::Selected {
+                        rect: px_rect_to_points(last, ctx.pixels_per_point()),
+                        dragging: None,
+                    };
+                    ctx.request_repaint();
+                    return;
+                }
+
                 if resp.hovered() {
                     ctx.set_cursor_icon(egui::CursorIcon::Crosshair);
                 }
@@ -51,11 +64,20 @@ impl App {
                     end = p;
                 }
 
+                // 按住 Shift 锁定宽高比（自由比例时为正方形）
+                if shift {
+                    let ratio = self.aspect_ratio.ratio().unwrap_or(1.0);
+                    end = lock_aspect(start, end, ratio);
+                }
+
                 let min = Pos2::new(start.x.min(end.x), start.y.min(end.y));
                 let max = Pos2::new(start.x.max(end.x), start.y.max(end.y));
                 let sel = Rect::from_min_max(min, max).intersect(full);
 
                 let painter = ui.painter_at(full);
+                if let Some(frozen) = &mut self.frozen {
+                    frozen.paint(&painter, ctx, full);
+                }
                 if sel.width() > 1.0 && sel.height() > 1.0 {
                     paint_dim_with_hole(&painter, full, sel, DIM_ALPHA);
                     painter.rect_stroke(
@@ -65,7 +87,7 @@ impl App {
                         StrokeKind::Inside,
                     );
 
-                    draw_size_label(&painter, ctx, sel);
+                    draw_size_label(&painter, ctx, sel, self.aspect_ratio);
                 } else {
                     painter.rect_filled(full, 0.0, Color32::from_black_alpha(DIM_ALPHA));
                 }
@@ -73,7 +95,7 @@ impl App {
                 painter.text(
                     full.center_top() + Vec2::new(0.0, 12.0),
                     egui::Align2::CENTER_TOP,
-                    "Drag to select. Release to continue. Esc to cancel.",
+                    "Drag to select. Release to continue. Hold Shift to lock aspect ratio. Esc to cancel.",
                     egui::FontId::proportional(16.0),
                     Color32::WHITE,
                 );
@@ -0,0 +1,258 @@
+use eframe::egui::{self, Pos2, Rect, Vec2};
+
+use super::points_rect_to_px;
+use crate::App;
+
+/// 选区宽高比约束
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AspectRatio {
+    #[default]
+    Free, // 自由比例
+    Square,      // 1:1
+    FourThree,   // 4:3
+    SixteenNine, // 16:9
+    Custom(f32, f32),
+}
+
+impl AspectRatio {
+    pub fn all() -> &'static [AspectRatio] {
+        &[
+            AspectRatio::Free,
+            AspectRatio::Square,
+            AspectRatio::FourThree,
+            AspectRatio::SixteenNine,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            AspectRatio::Free => "Free".to_string(),
+            AspectRatio::Square => "1:1".to_string(),
+            AspectRatio::FourThree => "4:3".to_string(),
+            AspectRatio::SixteenNine => "16:9".to_string(),
+            AspectRatio::Custom(w, h) => format!("{w}:{h}"),
+        }
+    }
+
+    /// 宽 / 高，自由比例返回 None
+    pub fn ratio(&self) -> Option<f32> {
+        match self {
+            AspectRatio::Free => None,
+            AspectRatio::Square => Some(1.0),
+            AspectRatio::FourThree => Some(4.0 / 3.0),
+            AspectRatio::SixteenNine => Some(16.0 / 9.0),
+            AspectRatio::Custom(w, h) => Some(w / h),
+        }
+    }
+}
+
+/// 预设尺寸（物理像素）
+pub const SIZE_PRESETS: &[(u32, u32, &str)] = &[
+    (640, 480, "VGA"),
+    (800, 600, "SVGA"),
+    (1024, 768, "XGA"),
+    (1280, 720, "720p"),
+    (1920, 1080, "1080p"),
+    (512, 512, "Icon"),
+    (1200, 630, "Social card"),
+    (1242, 2688, "App Store 6.5\""),
+    (2048, 2732, "iPad Pro"),
+];
+
+/// 用户输入的尺寸（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeInput {
+    pub w: u32,
+    pub h: u32,
+    pub pos: Option<(u32, u32)>,
+}
+
+/// 解析 `W×H`、`WxH+X+Y` 或 `WxH@X,Y`
+pub fn parse_size_input(text: &str) -> Option<SizeInput> {
+    let text: String = text
+        .trim()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '×' | 'X' | '*' => 'x',
+            c => c,
+        })
+        .collect();
+
+    let (size, pos) = if let Some((size, pos)) = text.split_once('+') {
+        (size, Some(pos.split('+').collect::<Vec<_>>()))
+    } else if let Some((size, pos)) = text.split_once('@') {
+        (size, Some(pos.split(',').collect::<Vec<_>>()))
+    } else {
+        (text.as_str(), None)
+    };
+
+    let (w, h) = size.split_once('x')?;
+    let w: u32 = w.parse().ok()?;
+    let h: u32 = h.parse().ok()?;
+    if w == 0 || h == 0 {
+        return None;
+    }
+
+    let pos = match pos.as_deref() {
+        Some([x, y]) => Some((x.parse().ok()?, y.parse().ok()?)),
+        Some(_) => return None,
+        None => None,
+    };
+
+    Some(SizeInput { w, h, pos })
+}
+
+/// 解析 `W:H` 形式的自定义比例
+pub fn parse_aspect_input(text: &str) -> Option<AspectRatio> {
+    let (w, h) = text.trim().split_once(':')?;
+    let w: f32 = w.trim().parse().ok()?;
+    let h: f32 = h.trim().parse().ok()?;
+    if w <= 0.0 || h <= 0.0 {
+        return None;
+    }
+    Some(AspectRatio::Custom(w, h))
+}
+
+/// 以 anchor 为固定点调整 moving，使两点构成的矩形满足宽高比
+pub fn lock_aspect(anchor: Pos2, moving: Pos2, ratio: f32) -> Pos2 {
+    let d = moving - anchor;
+    let (w, h) = (d.x.abs(), d.y.abs());
+    let (w, h) = if w > h * ratio {
+        (w, w / ratio)
+    } else {
+        (h * ratio, h)
+    };
+    anchor + Vec2::new(w.copysign(d.x), h.copysign(d.y))
+}
+
+/// 把像素尺寸转换为逻辑坐标的选区，并保持在屏幕内
+pub fn rect_from_size_input(input: SizeInput, current: Rect, screen: Rect, ppp: f32) -> Rect {
+    let size = Vec2::new(input.w as f32, input.h as f32) / ppp;
+    let size = size.min(screen.size());
+    let mut min = match input.pos {
+        Some((x, y)) => Pos2::new(x as f32, y as f32) / ppp,
+        None => current.min,
+    };
+    min.x = min.x.clamp(screen.min.x, screen.max.x - size.x);
+    min.y = min.y.clamp(screen.min.y, screen.max.y - size.y);
+    Rect::from_min_size(min, size)
+}
+
+/// 尺寸输入框的状态
+#[derive(Debug, Default)]
+pub struct SizeEditor {
+    pub open: bool,
+    pub text: String,
+    pub error: bool,
+}
+
+impl App {
+    /// 绘制尺寸编辑弹窗，返回新的选区和弹窗占用的区域
+    pub(crate) fn size_editor_ui(
+        &mut self,
+        ctx: &egui::Context,
+        rect: Rect,
+        screen: Rect,
+    ) -> (Option<Rect>, Rect) {
+        let ppp = ctx.pixels_per_point();
+        let mut new_rect = None;
+        let mut close = false;
+
+        let pos = if rect.min.y < 30.0 {
+            Pos2::new(rect.min.x + 4.0, rect.min.y + 24.0)
+        } else {
+            Pos2::new(rect.min.x, (rect.min.y - 200.0).max(screen.min.y))
+        };
+
+        let area = egui::Area::new(egui::Id::new("size_editor"))
+            .fixed_pos(pos)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(260.0);
+
+                    let edit = ui.add(
+                        egui::TextEdit::singleline(&mut self.size_editor.text)
+                            .hint_text("W×H, W×H+X+Y or W:H")
+                            .desired_width(200.0),
+                    );
+                    if !edit.has_focus() && !edit.lost_focus() {
+                        edit.request_focus();
+                    }
+                    if edit.changed() {
+                        self.size_editor.error = false;
+                    }
+
+                    if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let text = self.size_editor.text.clone();
+                        if let Some(input) = parse_size_input(&text) {
+                            new_rect = Some(rect_from_size_input(input, rect, screen, ppp));
+                            close = true;
+                        } else if let Some(aspect) = parse_aspect_input(&text) {
+                            self.aspect_ratio = aspect;
+                            close = true;
+                        } else {
+                            self.size_editor.error = true;
+                        }
+                    }
+                    if self.size_editor.error {
+                        ui.colored_label(egui::Color32::LIGHT_RED, "Invalid size");
+                    }
+
+                    ui.separator();
+                    ui.label("Aspect ratio (hold Shift to lock)");
+                    ui.horizontal_wrapped(|ui| {
+                        for aspect in AspectRatio::all() {
+                            if ui
+                                .selectable_label(self.aspect_ratio == *aspect, aspect.name())
+                                .clicked()
+                            {
+                                self.aspect_ratio = *aspect;
+                            }
+                        }
+                        if let AspectRatio::Custom(..) = self.aspect_ratio {
+                            let _ = ui.selectable_label(true, self.aspect_ratio.name());
+                        }
+                    });
+
+                    ui.separator();
+                    ui.label("Preset sizes");
+                    ui.horizontal_wrapped(|ui| {
+                        for (w, h, name) in SIZE_PRESETS {
+                            if ui.button(format!("{w}×{h}")).on_hover_text(*name).clicked() {
+                                let input = SizeInput {
+                                    w: *w,
+                                    h: *h,
+                                    pos: None,
+                                };
+                                new_rect = Some(rect_from_size_input(input, rect, screen, ppp));
+                                close = true;
+                            }
+                        }
+                    });
+                });
+            });
+
+        if close {
+            self.close_size_editor();
+        }
+
+        (new_rect, area.response.rect)
+    }
+
+    /// 打开尺寸编辑弹窗，用当前选区填充输入框
+    pub(crate) fn open_size_editor(&mut self, ctx: &egui::Context, rect: Rect) {
+        let px = points_rect_to_px(ctx, rect);
+        self.size_editor = SizeEditor {
+            open: true,
+            text: format!("{}x{}+{}+{}", px.w, px.h, px.x, px.y),
+            error: false,
+        };
+    }
+
+    pub(crate) fn close_size_editor(&mut self) {
+        self.size_editor.open = false;
+        self.size_editor.error = false;
+    }
+}
@@ -5,6 +5,9 @@ pub enum ToolbarAction {
     None,
     Cancel,
     Confirm,
+    Pin,
+    Scroll,
+    Record,
     Arrow,
 }
 
@@ -24,7 +27,7 @@ impl Default for Toolbar {
 
 impl Toolbar {
     pub fn calc_rect(&self, selection: Rect, screen: Rect) -> Rect {
-        let btn_count = 3.0;
+        let btn_count = Self::buttons().len() as f32;
         let width = self.btn_size.x * btn_count + self.spacing * (btn_count - 1.0) + 16.0;
         let height = self.btn_size.y + 12.0;
 
@@ -49,6 +52,36 @@ impl Toolbar {
         Rect::from_center_size(center, Vec2::new(width, height))
     }
 
+    /// 工具栏按钮，按从左到右的顺序排列
+    fn buttons() -> [(egui::ImageSource<'static>, ToolbarAction); 6] {
+        [
+            (
+                egui::include_image!("../../assets/icons/close.png"),
+                ToolbarAction::Cancel,
+            ),
+            (
+                egui::include_image!("../../assets/icons/check.png"),
+                ToolbarAction::Confirm,
+            ),
+            (
+                egui::include_image!("../../assets/icons/pin.png"),
+                ToolbarAction::Pin,
+            ),
+            (
+                egui::include_image!("../../assets/icons/scroll.png"),
+                ToolbarAction::Scroll,
+            ),
+            (
+                egui::include_image!("../../assets/icons/record.png"),
+                ToolbarAction::Record,
+            ),
+            (
+                egui::include_image!("../../assets/icons/arrow.png"),
+                ToolbarAction::Arrow,
+            ),
+        ]
+    }
+
     /// 绘制工具栏并返回点击的动作
     pub fn show(&self, ui: &mut egui::Ui, toolbar_rect: Rect) -> ToolbarAction {
         let painter = ui.painter();
@@ -56,48 +89,17 @@ impl Toolbar {
         // 背景
         painter.rect_filled(toolbar_rect, 6.0, Color32::from_rgb(240, 240, 240));
 
-        let cx = toolbar_rect.center().x;
-        let cy = toolbar_rect.center().y;
-
         let mut action = ToolbarAction::None;
+        let mut x = toolbar_rect.min.x + 8.0 + self.btn_size.x / 2.0;
+        let cy = toolbar_rect.center().y;
 
-        // Cancel 按钮
-        let cancel_rect = Rect::from_center_size(
-            Pos2::new(cx - self.btn_size.x - self.spacing, cy),
-            self.btn_size,
-        );
-        let cancel_img = egui::Image::new(egui::include_image!("../../assets/icons/close.png"))
-            .fit_to_exact_size(self.btn_size);
-        if ui
-            .put(cancel_rect, cancel_img.sense(Sense::click()))
-            .clicked()
-        {
-            action = ToolbarAction::Cancel;
-        }
-
-        // Confirm 按钮
-        let confirm_rect = Rect::from_center_size(Pos2::new(cx, cy), self.btn_size);
-        let check_img = egui::Image::new(egui::include_image!("../../assets/icons/check.png"))
-            .fit_to_exact_size(self.btn_size);
-        if ui
-            .put(confirm_rect, check_img.sense(Sense::click()))
-            .clicked()
-        {
-            action = ToolbarAction::Confirm;
-        }
-
-        // Arrow 按钮
-        let arrow_rect = Rect::from_center_size(
-            Pos2::new(cx + self.btn_size.x + self.spacing, cy),
-            self.btn_size,
-        );
-        let arrow_img = egui::Image::new(egui::include_image!("../../assets/icons/arrow.png"))
-            .fit_to_exact_size(self.btn_size);
-        if ui
-            .put(arrow_rect, arrow_img.sense(Sense::click()))
-            .clicked()
-        {
-            action = ToolbarAction::Arrow;
+        for (source, btn_action) in Self::buttons() {
+            let btn_rect = Rect::from_center_size(Pos2::new(x, cy), self.btn_size);
+            let img = egui::Image::new(source).fit_to_exact_size(self.btn_size);
+            if ui.put(btn_rect, img.sense(Sense::click())).clicked() {
+                action = btn_action;
+            }
+            x += self.btn_size.x + self.spacing;
         }
 
         action
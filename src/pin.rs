@@ -0,0 +1,213 @@
+use eframe::egui::{
+    self, Color32, Pos2, Sense, Vec2, ViewportBuilder, ViewportCommand, ViewportId,
+};
+
+use crate::capture::RgbaImage;
+use crate::clipboard::{ClipboardConfig, ClipboardService};
+
+/// 钉在屏幕上的单张截图
+pub struct Pin {
+    id: ViewportId,
+    image: RgbaImage,
+    texture: Option<egui::TextureHandle>,
+    position: Option<Pos2>,
+    pub zoom: f32,
+    pub opacity: f32,
+    pub click_through: bool,
+    pub open: bool,
+}
+
+impl Pin {
+    const MIN_ZOOM: f32 = 0.1;
+    const MAX_ZOOM: f32 = 8.0;
+    const MIN_OPACITY: f32 = 0.1;
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn title(&self) -> String {
+        format!("Pin {}×{}", self.image.width(), self.image.height())
+    }
+
+    /// 窗口的逻辑尺寸
+    fn window_size(&self, ppp: f32) -> Vec2 {
+        Vec2::new(self.image.width() as f32, self.image.height() as f32) / ppp * self.zoom
+    }
+
+    fn viewport_builder(&self, ppp: f32) -> ViewportBuilder {
+        let mut builder = ViewportBuilder::default()
+            .with_title(self.title())
+            .with_decorations(false)
+            .with_always_on_top()
+            .with_transparent(true)
+            .with_resizable(false)
+            .with_taskbar(false)
+            .with_inner_size(self.window_size(ppp))
+            .with_mouse_passthrough(self.click_through);
+        if let Some(pos) = self.position {
+            builder = builder.with_position(pos);
+        }
+        builder
+    }
+
+    /// 绘制钉图窗口内容
+    fn ui(
+        &mut self,
+        ctx: &egui::Context,
+        clipboard: &mut ClipboardService,
+        config: &ClipboardConfig,
+    ) {
+        if ctx.input(|i| i.viewport().close_requested() || i.key_pressed(egui::Key::Escape)) {
+            self.open = false;
+            return;
+        }
+
+        let texture = self.texture.get_or_insert_with(|| {
+            let size = [self.image.width() as usize, self.image.height() as usize];
+            let color = egui::ColorImage::from_rgba_unmultiplied(size, self.image.as_raw());
+            ctx.load_texture("pin", color, Default::default())
+        });
+        let texture_id = texture.id();
+
+        egui::CentralPanel::default()
+            .frame(egui::Frame::NONE)
+            .show(ctx, |ui| {
+                let full = ui.max_rect();
+                let resp = ui.allocate_rect(full, Sense::click_and_drag());
+
+                let tint = Color32::WHITE.gamma_multiply(self.opacity);
+                ui.painter().image(
+                    texture_id,
+                    full,
+                    egui::Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    tint,
+                );
+                ui.painter().rect_stroke(
+                    full,
+                    0.0,
+                    egui::Stroke::new(1.0, Color32::from_rgb(0, 122, 255)),
+                    egui::StrokeKind::Inside,
+                );
+
+                // 拖拽移动窗口
+                if resp.drag_started_by(egui::PointerButton::Primary) {
+                    ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                }
+
+                // 滚轮缩放，Ctrl + 滚轮调整透明度
+                if resp.hovered() {
+                    let (scroll, ctrl) = ctx.input(|i| (i.smooth_scroll_delta.y, i.modifiers.ctrl));
+                    if scroll != 0.0 {
+                        if ctrl {
+                            self.opacity =
+                                (self.opacity + scroll * 0.002).clamp(Self::MIN_OPACITY, 1.0);
+                        } else {
+                            self.zoom = (self.zoom * (scroll * 0.002).exp())
+                                .clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+                        }
+                    }
+                }
+
+                // 双击关闭
+                if resp.double_clicked() {
+                    self.open = false;
+                }
+
+                resp.context_menu(|ui| self.context_menu_ui(ui, clipboard, config));
+            });
+    }
+
+    /// 右键菜单
+    fn context_menu_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        clipboard: &mut ClipboardService,
+        config: &ClipboardConfig,
+    ) {
+        if ui.button("Copy").clicked() {
+            if let Err(e) = clipboard.copy(config, &self.image, None) {
+                eprintln!("copy to clipboard failed: {e}");
+            }
+            ui.close();
+        }
+        if ui.button("Save pin.png").clicked() {
+            if let Err(e) = self.image.save("pin.png") {
+                eprintln!("save pin failed: {e}");
+            }
+            ui.close();
+        }
+        ui.separator();
+        ui.add(egui::Slider::new(&mut self.opacity, Self::MIN_OPACITY..=1.0).text("Opacity"));
+        ui.add(
+            egui::Slider::new(&mut self.zoom, Self::MIN_ZOOM..=Self::MAX_ZOOM)
+                .logarithmic(true)
+                .text("Zoom"),
+        );
+        if ui.button("Reset zoom").clicked() {
+            self.zoom = 1.0;
+        }
+        if ui
+            .checkbox(&mut self.click_through, "Click-through")
+            .on_hover_text("Toggle back from the main window")
+            .changed()
+        {
+            ui.close();
+        }
+        ui.separator();
+        if ui.button("Close").clicked() {
+            self.open = false;
+            ui.close();
+        }
+    }
+}
+
+/// 管理所有钉图窗口
+#[derive(Default)]
+pub struct PinManager {
+    pins: Vec<Pin>,
+    next_id: u64,
+}
+
+impl PinManager {
+    /// 新建钉图，`position` 为窗口左上角的逻辑坐标
+    pub fn pin(&mut self, image: RgbaImage, position: Option<Pos2>) {
+        let id = ViewportId::from_hash_of(("pxshot_pin", self.next_id));
+        self.next_id += 1;
+        self.pins.push(Pin {
+            id,
+            image,
+            texture: None,
+            position,
+            zoom: 1.0,
+            opacity: 1.0,
+            click_through: false,
+            open: true,
+        });
+    }
+
+    pub fn pins_mut(&mut self) -> impl Iterator<Item = &mut Pin> {
+        self.pins.iter_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// 显示所有钉图窗口，并移除已关闭的
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        clipboard: &mut ClipboardService,
+        config: &ClipboardConfig,
+    ) {
+        let ppp = ctx.pixels_per_point();
+        for pin in &mut self.pins {
+            let builder = pin.viewport_builder(ppp);
+            ctx.show_viewport_immediate(pin.id, builder, |ctx, _class| {
+                pin.ui(ctx, clipboard, config)
+            });
+        }
+        self.pins.retain(|pin| pin.open);
+    }
+}
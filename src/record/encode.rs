@@ -0,0 +1,202 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+use super::{Frame, RecordConfig, RecordFormat};
+
+/// 边录边编码的编码器，每次只接收一帧
+pub enum FrameEncoder {
+    Gif {
+        encoder: gif::Encoder<BufWriter<File>>,
+        size: (u16, u16),
+        speed: i32,
+    },
+    /// APNG 要在文件头写总帧数，先把帧写进临时文件，结束时再编码
+    Apng {
+        spill: BufWriter<File>,
+        spill_path: PathBuf,
+        path: PathBuf,
+        size: (u32, u32),
+        delays: Vec<u16>,
+    },
+    #[cfg(feature = "webm")]
+    WebM {
+        child: std::process::Child,
+        stdin: std::process::ChildStdin,
+        fps: u32,
+    },
+}
+
+impl FrameEncoder {
+    /// 创建输出文件，`size` 为每帧的像素大小
+    pub fn new(config: &RecordConfig, path: &Path, size: (u32, u32)) -> Result<Self> {
+        match config.format {
+            RecordFormat::Gif => Self::gif(path, size, config.gif_speed),
+            RecordFormat::Apng => Self::apng(path, size),
+            #[cfg(feature = "webm")]
+            RecordFormat::WebM => Self::webm(path, size, config.fps),
+        }
+    }
+
+    /// 无限循环的 GIF，`speed` 为 NeuQuant 调色板量化速度（1 最慢最准，30 最快）
+    fn gif(path: &Path, (w, h): (u32, u32), speed: i32) -> Result<Self> {
+        let (Ok(w), Ok(h)) = (u16::try_from(w), u16::try_from(h)) else {
+            bail!("frame too large for GIF: {w}x{h}");
+        };
+        let mut encoder = gif::Encoder::new(BufWriter::new(File::create(path)?), w, h, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        Ok(Self::Gif {
+            encoder,
+            size: (w, h),
+            speed: speed.clamp(1, 30),
+        })
+    }
+
+    /// 无限循环的 APNG（无损）
+    fn apng(path: &Path, size: (u32, u32)) -> Result<Self> {
+        let spill_path =
+            std::env::temp_dir().join(format!("pxshot-recording-{}.rgba", std::process::id()));
+        Ok(Self::Apng {
+            spill: BufWriter::new(File::create(&spill_path)?),
+            spill_path,
+            path: path.to_path_buf(),
+            size,
+            delays: Vec::new(),
+        })
+    }
+
+    /// 通过 ffmpeg 编码为 VP9 WebM
+    #[cfg(feature = "webm")]
+    fn webm(path: &Path, (w, h): (u32, u32), fps: u32) -> Result<Self> {
+        use std::process::{Command, Stdio};
+
+        let size = format!("{w}x{h}");
+        let mut child = Command::new("ffmpeg")
+            .args([
+                "-y",
+                "-loglevel",
+                "error",
+                "-f",
+                "rawvideo",
+                "-pix_fmt",
+                "rgba",
+            ])
+            .args(["-s", &size, "-r", &fps.to_string(), "-i", "-"])
+            .args(["-c:v", "libvpx-vp9", "-pix_fmt", "yuva420p"])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("cannot run ffmpeg: {e}"))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        Ok(Self::WebM { child, stdin, fps })
+    }
+
+    /// 编码一帧
+    pub fn push(&mut self, frame: &Frame) -> Result<()> {
+        match self {
+            Self::Gif {
+                encoder,
+                size: (w, h),
+                speed,
+            } => {
+                let mut pixels = frame.image.as_raw().clone();
+                let mut gif_frame = gif::Frame::from_rgba_speed(*w, *h, &mut pixels, *speed);
+                // GIF 的延迟单位是 10 毫秒
+                gif_frame.delay = (frame.delay.as_millis() / 10).clamp(2, u16::MAX as u128) as u16;
+                encoder.write_frame(&gif_frame)?;
+            }
+            Self::Apng { spill, delays, .. } => {
+                spill.write_all(frame.image.as_raw())?;
+                delays.push(frame.delay.as_millis().clamp(1, u16::MAX as u128) as u16);
+            }
+            #[cfg(feature = "webm")]
+            Self::WebM { stdin, fps, .. } => {
+                // 按帧率重复帧以保持时间轴
+                let repeat = (frame.delay.as_secs_f32() * *fps as f32).round().max(1.0) as usize;
+                for _ in 0..repeat {
+                    stdin.write_all(frame.image.as_raw())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 写完所有帧后收尾
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Self::Gif { encoder, .. } => {
+                encoder.into_inner()?.flush()?;
+            }
+            Self::Apng {
+                spill,
+                spill_path,
+                path,
+                size: (w, h),
+                delays,
+            } => {
+                spill.into_inner().map_err(|e| e.into_error())?;
+                let result = write_apng(&spill_path, &path, (w, h), &delays);
+                let _ = fs::remove_file(&spill_path);
+                result?;
+            }
+            #[cfg(feature = "webm")]
+            Self::WebM {
+                mut child, stdin, ..
+            } => {
+                drop(stdin);
+                if !child.wait()?.success() {
+                    bail!("ffmpeg failed");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 放弃编码，删除已写出的文件
+    pub fn abort(self, path: &Path) {
+        match self {
+            Self::Gif { encoder, .. } => drop(encoder),
+            Self::Apng {
+                spill, spill_path, ..
+            } => {
+                drop(spill);
+                let _ = fs::remove_file(spill_path);
+            }
+            #[cfg(feature = "webm")]
+            Self::WebM {
+                mut child, stdin, ..
+            } => {
+                drop(stdin);
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 从临时文件逐帧读出，写成 APNG
+fn write_apng(spill_path: &Path, path: &Path, (w, h): (u32, u32), delays: &[u16]) -> Result<()> {
+    if delays.is_empty() {
+        bail!("no frames recorded");
+    }
+    let mut spill = BufReader::new(File::open(spill_path)?);
+    let mut pixels = vec![0; w as usize * h as usize * 4];
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, w, h);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(delays.len() as u32, 0)?;
+
+    let mut writer = encoder.write_header()?;
+    for &millis in delays {
+        spill.read_exact(&mut pixels)?;
+        writer.set_frame_delay(millis, 1000)?;
+        writer.write_image_data(&pixels)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
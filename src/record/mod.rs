@@ -0,0 +1,333 @@
+mod encode;
+mod panel;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::{self, RectPx, RgbaImage};
+
+pub use encode::FrameEncoder;
+
+/// 录屏输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RecordFormat {
+    #[default]
+    Gif,
+    Apng,
+    #[cfg(feature = "webm")]
+    WebM,
+}
+
+impl RecordFormat {
+    pub fn all() -> &'static [RecordFormat] {
+        &[
+            RecordFormat::Gif,
+            RecordFormat::Apng,
+            #[cfg(feature = "webm")]
+            RecordFormat::WebM,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordFormat::Gif => "GIF",
+            RecordFormat::Apng => "APNG",
+            #[cfg(feature = "webm")]
+            RecordFormat::WebM => "WebM",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Gif => "gif",
+            RecordFormat::Apng => "png",
+            #[cfg(feature = "webm")]
+            RecordFormat::WebM => "webm",
+        }
+    }
+}
+
+/// 录屏设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordConfig {
+    pub fps: u32,
+    pub format: RecordFormat,
+    pub dedup: bool,      // 合并相同的连续帧
+    pub cursor: bool,     // 把鼠标指针画进帧里
+    pub gif_speed: i32,   // GIF 调色板量化速度，1 最准 30 最快
+    pub max_seconds: u32, // 录制时长上限
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        Self {
+            fps: 10,
+            format: RecordFormat::Gif,
+            dedup: true,
+            cursor: true,
+            gif_speed: 10,
+            max_seconds: 60,
+        }
+    }
+}
+
+/// 录制的一帧及其显示时长
+pub struct Frame {
+    pub image: RgbaImage,
+    pub delay: Duration,
+}
+
+/// 后台线程与界面共享的状态
+#[derive(Default)]
+struct Shared {
+    frames: usize,
+    elapsed: Duration,
+    status: Option<String>,
+    output: Option<Result<PathBuf, String>>,
+}
+
+/// 截帧线程和编码线程之间最多排队的帧数
+const FRAME_QUEUE: usize = 4;
+
+/// 一次录屏：后台线程按帧率截取选区，边录边交给编码线程
+pub struct RecordSession {
+    pub rect_px: RectPx,
+    config: RecordConfig,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RecordSession {
+    /// 开始录制，`delay` 留给遮罩窗口隐藏
+    pub fn start(rect_px: RectPx, config: RecordConfig, output: PathBuf, delay: Duration) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let config = config.clone();
+            let shared = shared.clone();
+            let stop = stop.clone();
+            let cancel = cancel.clone();
+            let paused = paused.clone();
+            thread::spawn(move || {
+                thread::sleep(delay);
+                let (sender, receiver) = mpsc::sync_channel(FRAME_QUEUE);
+                let encoder = {
+                    let config = config.clone();
+                    let output = output.clone();
+                    let cancel = cancel.clone();
+                    thread::spawn(move || Self::encode(receiver, &config, &output, &cancel))
+                };
+                Self::record(rect_px, &config, &shared, &stop, &paused, &sender);
+                drop(sender);
+                if cancel.load(Ordering::Relaxed) {
+                    let _ = encoder.join();
+                    return;
+                }
+                shared.lock().unwrap().status = Some("Encoding…".to_string());
+                let result = match encoder.join() {
+                    Ok(result) => result.map(|()| output).map_err(|e| format!("{e:#}")),
+                    Err(_) => Err("encoder panicked".to_string()),
+                };
+                shared.lock().unwrap().output = Some(result);
+            })
+        };
+
+        Self {
+            rect_px,
+            config,
+            shared,
+            stop,
+            cancel,
+            paused,
+            thread: Some(thread),
+        }
+    }
+
+    fn record(
+        rect: RectPx,
+        config: &RecordConfig,
+        shared: &Mutex<Shared>,
+        stop: &AtomicBool,
+        paused: &AtomicBool,
+        sender: &SyncSender<Frame>,
+    ) {
+        let interval = Duration::from_secs_f32(1.0 / config.fps.clamp(1, 60) as f32);
+        let max_duration = Duration::from_secs(config.max_seconds.max(1).into());
+        // 只保留上一帧，它的时长要等到下一个不同的帧才确定
+        let mut last: Option<Frame> = None;
+        let mut frames = 0;
+        let mut elapsed = Duration::ZERO;
+        let mut last_tick = Instant::now();
+        let mut cursor_ok = config.cursor;
+        // 整段录制共用一个连接，每帧不必重新连接
+        let grabber = match capture::Grabber::new() {
+            Ok(grabber) => grabber,
+            Err(e) => {
+                shared.lock().unwrap().status = Some(format!("capture failed: {e}"));
+                return;
+            }
+        };
+
+        while !stop.load(Ordering::Relaxed) && elapsed < max_duration {
+            let tick = Instant::now();
+            if paused.load(Ordering::Relaxed) {
+                // 暂停的时间不计入上一帧
+                last_tick = tick;
+                thread::sleep(interval);
+                continue;
+            }
+
+            let mut image = match grabber.capture_region(rect) {
+                Ok(image) => image,
+                Err(e) => {
+                    shared.lock().unwrap().status = Some(format!("capture failed: {e}"));
+                    break;
+                }
+            };
+            if cursor_ok {
+                match grabber.cursor_image() {
+                    Ok(cursor) => overlay_cursor(&mut image, rect, &cursor),
+                    Err(e) => {
+                        shared.lock().unwrap().status = Some(format!("no cursor: {e}"));
+                        cursor_ok = false;
+                    }
+                }
+            }
+
+            // 上一帧一直显示到这一帧
+            let since = tick - last_tick;
+            last_tick = tick;
+            elapsed += since;
+            shared.lock().unwrap().elapsed = elapsed;
+            if let Some(last) = &mut last {
+                last.delay += since;
+                if config.dedup && last.image == image {
+                    continue_after(interval, tick);
+                    continue;
+                }
+            }
+            let frame = Frame {
+                image,
+                delay: Duration::ZERO,
+            };
+            // 编码线程出错退出后不再录制
+            if let Some(done) = last.replace(frame)
+                && sender.send(done).is_err()
+            {
+                break;
+            }
+            frames += 1;
+            shared.lock().unwrap().frames = frames;
+
+            continue_after(interval, tick);
+        }
+
+        if let Some(mut last) = last {
+            last.delay += last_tick.elapsed().max(interval);
+            let _ = sender.send(last);
+        }
+    }
+
+    /// 编码线程：按顺序编码收到的帧，取消时删除输出
+    fn encode(
+        receiver: Receiver<Frame>,
+        config: &RecordConfig,
+        output: &Path,
+        cancel: &AtomicBool,
+    ) -> Result<()> {
+        let mut encoder: Option<FrameEncoder> = None;
+        for frame in receiver {
+            let encoder = match &mut encoder {
+                Some(encoder) => encoder,
+                None => {
+                    encoder.insert(FrameEncoder::new(config, output, frame.image.dimensions())?)
+                }
+            };
+            encoder.push(&frame)?;
+        }
+        let Some(encoder) = encoder else {
+            bail!("no frames recorded");
+        };
+        if cancel.load(Ordering::Relaxed) {
+            encoder.abort(output);
+            return Ok(());
+        }
+        encoder.finish()
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// 停止录制，后台线程随后编码
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// 已录制的不同帧数和时长
+    pub fn progress(&self) -> (usize, Duration) {
+        let state = self.shared.lock().unwrap();
+        (state.frames, state.elapsed)
+    }
+
+    pub fn status(&self) -> Option<String> {
+        self.shared.lock().unwrap().status.clone()
+    }
+
+    pub fn config(&self) -> &RecordConfig {
+        &self.config
+    }
+
+    /// 编码完成后返回输出路径或错误
+    pub fn take_output(&mut self) -> Option<Result<PathBuf, String>> {
+        let output = self.shared.lock().unwrap().output.take()?;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        Some(output)
+    }
+}
+
+impl Drop for RecordSession {
+    fn drop(&mut self) {
+        // 未完成就丢弃时不再编码
+        if self.thread.is_some() {
+            self.cancel.store(true, Ordering::Relaxed);
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 睡到下一帧的时间点
+fn continue_after(interval: Duration, tick: Instant) {
+    thread::sleep(interval.saturating_sub(tick.elapsed()));
+}
+
+/// 把鼠标指针按 alpha 混合画到帧上
+fn overlay_cursor(frame: &mut RgbaImage, rect: RectPx, cursor: &capture::CursorImage) {
+    let x = i64::from(cursor.x) - i64::from(rect.x);
+    let y = i64::from(cursor.y) - i64::from(rect.y);
+    image::imageops::overlay(frame, &cursor.image, x, y);
+}
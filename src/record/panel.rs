@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use eframe::egui::{self, Color32, Rect, ViewportBuilder, ViewportCommand, ViewportId};
+
+use super::{RecordFormat, RecordSession};
+use crate::App;
+use crate::mode::Mode;
+use crate::overlay::{points_rect_to_px, side_window_pos};
+
+impl App {
+    /// 录屏控制窗口的大小
+    const RECORD_PANEL_SIZE: [f32; 2] = [220.0, 110.0];
+
+    /// 从选区开始录屏：隐藏遮罩，后台线程开始截帧
+    pub(crate) fn start_recording(&mut self, ctx: &egui::Context, rect: Rect) {
+        if self.frozen.as_ref().is_some_and(|f| !f.is_screen) {
+            self.status = Some("recording needs a live screen".to_string());
+            return;
+        }
+        let rect_px = points_rect_to_px(ctx, rect);
+        self.remember_region(rect_px);
+        self.arrow_drawer.clear();
+        ctx.send_viewport_cmd(ViewportCommand::Visible(false));
+
+        let config = self.config.record.clone();
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let output = format!("pxshot-recording-{secs}.{}", config.format.extension());
+        self.recording = Some(RecordSession::start(
+            rect_px,
+            config,
+            output.into(),
+            Duration::from_millis(Self::CAPTURE_DELAY_MS),
+        ));
+        self.mode = Mode::Recording { rect_points: rect };
+    }
+
+    /// 录屏期间的控制窗口：暂停、停止、取消
+    pub(crate) fn recording_ui(&mut self, ctx: &egui::Context, rect_points: Rect) {
+        ctx.request_repaint_after(Duration::from_millis(100));
+        let Some(session) = &mut self.recording else {
+            self.mode = Mode::Idle;
+            return;
+        };
+
+        if let Some(output) = session.take_output() {
+            self.status = Some(match output {
+                Ok(path) => format!("saved {}", path.display()),
+                Err(e) => format!("recording failed: {e}"),
+            });
+            self.recording = None;
+            self.reset_after_capture(ctx);
+            return;
+        }
+
+        let size = Self::RECORD_PANEL_SIZE;
+        let builder = ViewportBuilder::default()
+            .with_title("Recording")
+            .with_always_on_top()
+            .with_resizable(false)
+            .with_inner_size(size);
+        // 选区外放不下时隐藏，用热键结束
+        let builder = match side_window_pos(ctx, rect_points, size.into()) {
+            Some(pos) => builder.with_position(pos),
+            None => builder.with_visible(false),
+        };
+
+        let mut cancel = false;
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of("pxshot_record"),
+            builder,
+            |ctx, _class| {
+                if ctx.input(|i| i.viewport().close_requested() || i.key_pressed(egui::Key::Escape))
+                {
+                    cancel = true;
+                }
+
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let (frames, elapsed) = session.progress();
+                    let secs = elapsed.as_secs();
+                    ui.horizontal(|ui| {
+                        if session.is_stopping() {
+                            ui.label("Finishing…");
+                        } else if session.is_paused() {
+                            ui.label("Paused");
+                        } else {
+                            ui.colored_label(Color32::RED, "● REC");
+                        }
+                        ui.label(format!(
+                            "{:02}:{:02}, {frames} frames",
+                            secs / 60,
+                            secs % 60
+                        ));
+                    });
+                    if let Some(status) = session.status() {
+                        ui.label(status);
+                    }
+
+                    ui.add_enabled_ui(!session.is_stopping(), |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button("Stop").clicked() {
+                                session.stop();
+                            }
+                            let paused = session.is_paused();
+                            if ui.button(if paused { "Resume" } else { "Pause" }).clicked() {
+                                session.set_paused(!paused);
+                            }
+                            if ui.button("Cancel").clicked() {
+                                cancel = true;
+                            }
+                        });
+                    });
+                    ui.weak(format!(
+                        "{} fps → {}",
+                        session.config().fps,
+                        session.config().format.name()
+                    ));
+                });
+            },
+        );
+
+        if cancel {
+            self.recording = None;
+            self.reset_after_capture(ctx);
+        }
+    }
+
+    /// 空闲界面中的录屏设置
+    pub(crate) fn record_settings_ui(&mut self, ui: &mut egui::Ui) {
+        let config = &mut self.config.record;
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Format");
+            for format in RecordFormat::all() {
+                changed |= ui
+                    .selectable_value(&mut config.format, *format, format.name())
+                    .changed();
+            }
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(egui::Slider::new(&mut config.fps, 1..=30).text("fps"))
+                .changed();
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut config.max_seconds)
+                        .range(1..=600)
+                        .prefix("max ")
+                        .suffix(" s"),
+                )
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut config.dedup, "Merge identical frames")
+                .changed();
+            changed |= ui.checkbox(&mut config.cursor, "Show cursor").changed();
+        });
+        if config.format == RecordFormat::Gif {
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut config.gif_speed, 1..=30)
+                        .text("Palette speed")
+                        .clamping(egui::SliderClamping::Always),
+                )
+                .on_hover_text("Lower is slower but gives better colors")
+                .changed();
+        }
+
+        if changed && let Err(e) = self.config.save() {
+            self.status = Some(format!("save config failed: {e}"));
+        }
+    }
+}
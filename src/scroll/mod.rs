@@ -0,0 +1,263 @@
+mod preview;
+mod stitch;
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use eframe::egui;
+use image::GenericImageView;
+
+use crate::capture::{self, RectPx, RgbaImage};
+
+pub use stitch::{StitchResult, Stitcher};
+
+/// 后台线程与界面共享的状态
+#[derive(Default)]
+struct Shared {
+    stitcher: Option<Stitcher>,
+    preview: Option<RgbaImage>,
+    version: u64,
+    frames: usize,
+    status: Option<String>,
+}
+
+/// 一次长截图：后台线程反复截取选区并拼接
+pub struct ScrollSession {
+    pub rect_px: RectPx,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    auto_scroll: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    texture: Option<egui::TextureHandle>,
+    texture_version: u64,
+}
+
+impl ScrollSession {
+    /// 两次截图的间隔
+    const INTERVAL: Duration = Duration::from_millis(250);
+    /// 长图的最大高度（像素）
+    const MAX_HEIGHT: u32 = 30_000;
+    /// 预览图宽度
+    const PREVIEW_WIDTH: u32 = 240;
+    /// 预览图的最大高度，超出后不再更新
+    const PREVIEW_MAX_HEIGHT: u32 = 8192;
+    /// 自动滚动时每次滚动的滚轮格数
+    const SCROLL_CLICKS: u32 = 3;
+    /// 自动滚动时连续多少帧没有变化视为到底
+    const END_FRAMES: usize = 4;
+
+    /// 开始截图，`delay` 留给遮罩窗口隐藏
+    pub fn start(rect_px: RectPx, delay: Duration) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+        let auto_scroll = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            let paused = paused.clone();
+            let auto_scroll = auto_scroll.clone();
+            thread::spawn(move || {
+                thread::sleep(delay);
+                Self::run(rect_px, &shared, &stop, &paused, &auto_scroll);
+            })
+        };
+
+        Self {
+            rect_px,
+            shared,
+            stop,
+            paused,
+            auto_scroll,
+            thread: Some(thread),
+            texture: None,
+            texture_version: 0,
+        }
+    }
+
+    fn run(
+        rect: RectPx,
+        shared: &Mutex<Shared>,
+        stop: &AtomicBool,
+        paused: &AtomicBool,
+        auto_scroll: &AtomicBool,
+    ) {
+        let center = ((rect.x + rect.w / 2) as i32, (rect.y + rect.h / 2) as i32);
+        let mut unchanged = 0;
+
+        while !stop.load(Ordering::Relaxed) {
+            if paused.load(Ordering::Relaxed) {
+                thread::sleep(Self::INTERVAL);
+                continue;
+            }
+
+            let frame = match capture::capture_region(rect) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    shared.lock().unwrap().status = Some(format!("capture failed: {e}"));
+                    return;
+                }
+            };
+
+            let mut guard = shared.lock().unwrap();
+            let state = &mut *guard;
+            state.frames += 1;
+            let old_height = state.stitcher.as_ref().map_or(0, |s| s.image().height());
+            let result = match &mut state.stitcher {
+                Some(stitcher) => stitcher.push(&frame),
+                None => {
+                    state.stitcher = Some(Stitcher::new(frame));
+                    StitchResult::Appended(rect.h)
+                }
+            };
+            let Some(stitcher) = state.stitcher.as_ref() else {
+                return;
+            };
+            let stitched = stitcher.image();
+
+            match result {
+                StitchResult::Unchanged => unchanged += 1,
+                StitchResult::Appended(_) | StitchResult::NoOverlap => {
+                    unchanged = 0;
+                    // 只缩小新拼上的部分
+                    let end = stitched.height() - stitcher.footer();
+                    let rows = end - (stitched.height() - old_height)..end;
+                    let preview = state.preview.get_or_insert_with(|| {
+                        let width = Self::PREVIEW_WIDTH.min(stitched.width());
+                        RgbaImage::new(width, 0)
+                    });
+                    if extend_preview(preview, stitched, rows, stitcher.footer()) {
+                        state.version += 1;
+                    }
+                }
+            }
+            if result == StitchResult::NoOverlap {
+                state.status = Some("no overlap found, scroll slower".to_string());
+            }
+
+            let height = stitched.height();
+            if height >= Self::MAX_HEIGHT {
+                state.status = Some(format!("reached the {} px limit", Self::MAX_HEIGHT));
+                return;
+            }
+            drop(guard);
+
+            if auto_scroll.load(Ordering::Relaxed) {
+                if unchanged >= Self::END_FRAMES {
+                    shared.lock().unwrap().status = Some("reached the end".to_string());
+                    return;
+                }
+                if let Err(e) = capture::scroll_at(center.0, center.1, Self::SCROLL_CLICKS) {
+                    shared.lock().unwrap().status = Some(format!("auto-scroll failed: {e}"));
+                    auto_scroll.store(false, Ordering::Relaxed);
+                }
+            }
+
+            thread::sleep(Self::INTERVAL);
+        }
+    }
+
+    /// 后台线程是否已自行结束（到底、达到上限或出错）
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_auto_scroll(&self, auto: bool) {
+        self.auto_scroll.store(auto, Ordering::Relaxed);
+    }
+
+    pub fn is_auto_scroll(&self) -> bool {
+        self.auto_scroll.load(Ordering::Relaxed)
+    }
+
+    /// 已截取的帧数和长图尺寸
+    pub fn progress(&self) -> (usize, Option<(u32, u32)>) {
+        let state = self.shared.lock().unwrap();
+        let size = state.stitcher.as_ref().map(|s| s.image().dimensions());
+        (state.frames, size)
+    }
+
+    pub fn status(&self) -> Option<String> {
+        self.shared.lock().unwrap().status.clone()
+    }
+
+    /// 预览纹理，拼接结果变化时更新
+    pub fn preview_texture(&mut self, ctx: &egui::Context) -> Option<&egui::TextureHandle> {
+        let state = self.shared.lock().unwrap();
+        if state.version != self.texture_version
+            && let Some(preview) = &state.preview
+        {
+            let size = [preview.width() as usize, preview.height() as usize];
+            let color = egui::ColorImage::from_rgba_unmultiplied(size, preview.as_raw());
+            self.texture = Some(ctx.load_texture("scroll_preview", color, Default::default()));
+            self.texture_version = state.version;
+        }
+        self.texture.as_ref()
+    }
+
+    /// 请求结束，界面随后取出长图
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// 停止截图并返回拼接好的长图
+    pub fn finish(mut self) -> Option<RgbaImage> {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let stitcher = self.shared.lock().unwrap().stitcher.take();
+        stitcher.map(Stitcher::into_image)
+    }
+}
+
+impl Drop for ScrollSession {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 把长图中 `rows` 范围的新内容缩小后插到预览图末尾的页脚之前，预览图太高时返回 false
+fn extend_preview(
+    preview: &mut RgbaImage,
+    stitched: &RgbaImage,
+    rows: Range<u32>,
+    footer: u32,
+) -> bool {
+    let scale = preview.width() as f32 / stitched.width() as f32;
+    let strip_h = ((rows.len() as f32 * scale).round() as u32).max(1);
+    if preview.height() + strip_h > ScrollSession::PREVIEW_MAX_HEIGHT {
+        return false;
+    }
+    let strip = image::imageops::thumbnail(
+        &*stitched.view(0, rows.start, stitched.width(), rows.len() as u32),
+        preview.width(),
+        strip_h,
+    );
+
+    let row_bytes = preview.width() as usize * 4;
+    let footer_h = ((footer as f32 * scale).round() as u32).min(preview.height());
+    let at = (preview.height() - footer_h) as usize * row_bytes;
+    let (width, height) = (preview.width(), preview.height() + strip_h);
+    let mut data = std::mem::take(preview).into_raw();
+    data.splice(at..at, strip.into_raw());
+    *preview = RgbaImage::from_raw(width, height, data).expect("preview buffer matches its size");
+    true
+}
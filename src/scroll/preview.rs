@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use eframe::egui::{self, Rect, ViewportBuilder, ViewportCommand, ViewportId};
+
+use super::ScrollSession;
+use crate::App;
+use crate::actions::CaptureSource;
+use crate::mode::Mode;
+use crate::overlay::{points_rect_to_px, side_window_pos};
+
+impl App {
+    /// 预览窗口的大小
+    const SCROLL_PREVIEW_SIZE: [f32; 2] = [280.0, 480.0];
+
+    /// 从选区开始长截图：隐藏遮罩，后台线程开始截图
+    pub(crate) fn start_scrolling(&mut self, ctx: &egui::Context, rect: Rect) {
+        if self.frozen.as_ref().is_some_and(|f| !f.is_screen) {
+            self.status = Some("scrolling capture needs a live screen".to_string());
+            return;
+        }
+        let rect_px = points_rect_to_px(ctx, rect);
+        self.remember_region(rect_px);
+        // 标注无法对应到长图上
+        self.arrow_drawer.clear();
+        ctx.send_viewport_cmd(ViewportCommand::Visible(false));
+        self.scroll = Some(ScrollSession::start(
+            rect_px,
+            Duration::from_millis(Self::CAPTURE_DELAY_MS),
+        ));
+        self.mode = Mode::Scrolling { rect_points: rect };
+    }
+
+    /// 长截图期间的预览窗口，放在选区旁边以免被截进去
+    pub(crate) fn scrolling_ui(&mut self, ctx: &egui::Context, rect_points: Rect) {
+        ctx.request_repaint_after(Duration::from_millis(100));
+        let Some(session) = &mut self.scroll else {
+            self.mode = Mode::Idle;
+            return;
+        };
+
+        let size = Self::SCROLL_PREVIEW_SIZE;
+        let builder = ViewportBuilder::default()
+            .with_title("Scrolling capture")
+            .with_always_on_top()
+            .with_inner_size(size);
+        // 选区外放不下时隐藏，用热键结束
+        let builder = match side_window_pos(ctx, rect_points, size.into()) {
+            Some(pos) => builder.with_position(pos),
+            None => builder.with_visible(false),
+        };
+
+        let mut stop = false;
+        let mut cancel = false;
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of("pxshot_scroll"),
+            builder,
+            |ctx, _class| {
+                if ctx.input(|i| i.viewport().close_requested() || i.key_pressed(egui::Key::Escape))
+                {
+                    cancel = true;
+                }
+
+                egui::TopBottomPanel::top("scroll_controls").show(ctx, |ui| {
+                    let (frames, size) = session.progress();
+                    match size {
+                        Some((w, h)) => ui.label(format!("{frames} frames, {w}×{h}")),
+                        None => ui.label("Waiting for the first frame…"),
+                    };
+                    if let Some(status) = session.status() {
+                        ui.label(status);
+                    }
+                    ui.label("Scroll the page under the selection");
+
+                    let mut auto = session.is_auto_scroll();
+                    if ui
+                        .checkbox(&mut auto, "Auto-scroll")
+                        .on_hover_text("Send wheel events to the selected area")
+                        .changed()
+                    {
+                        session.set_auto_scroll(auto);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Stop").clicked() {
+                            stop = true;
+                        }
+                        let paused = session.is_paused();
+                        if ui.button(if paused { "Resume" } else { "Pause" }).clicked() {
+                            session.set_paused(!paused);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    egui::ScrollArea::vertical()
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            if let Some(texture) = session.preview_texture(ctx) {
+                                ui.image((texture.id(), texture.size_vec2()));
+                            }
+                        });
+                });
+            },
+        );
+
+        // 线程自己结束（到底或达到上限）或按了热键时也直接出图
+        if stop || session.is_stopping() || (session.is_finished() && session.status().is_some()) {
+            let session = self.scroll.take().expect("session checked above");
+            let rect_px = session.rect_px;
+            // 第一帧就截图失败时显示真正的原因
+            let status = session.status();
+            match session.finish() {
+                Some(image) => {
+                    self.deliver_capture(ctx, image, rect_px, None, CaptureSource::Scrolling, false)
+                }
+                None => self.status = status.or_else(|| Some("nothing was captured".to_string())),
+            }
+            self.reset_after_capture(ctx);
+        } else if cancel {
+            self.scroll = None;
+            self.reset_after_capture(ctx);
+        }
+    }
+}
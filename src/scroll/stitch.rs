@@ -0,0 +1,156 @@
+use std::ops::Range;
+
+use crate::capture::RgbaImage;
+
+/// 拼接一帧的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StitchResult {
+    Unchanged,     // 画面没有滚动
+    Appended(u32), // 追加的新行数
+    NoOverlap,     // 找不到重叠，整帧追加
+}
+
+/// 把连续滚动的帧拼接成一张长图
+pub struct Stitcher {
+    image: RgbaImage,
+    last_rows: Vec<u64>,
+    /// 长图末尾固定页脚的行数
+    footer: u32,
+}
+
+impl Stitcher {
+    /// 重叠部分中至少要匹配的有效行数
+    const MIN_MATCHED_ROWS: usize = 8;
+    /// 有效行中匹配的比例
+    const MIN_MATCH_RATIO: f32 = 0.9;
+    /// 一行不匹配抵消的匹配行数，避免重复的行在错误的位置凑够比例
+    const MISMATCH_PENALTY: usize = 10;
+
+    pub fn new(first: RgbaImage) -> Self {
+        let last_rows = row_hashes(&first);
+        Self {
+            image: first,
+            last_rows,
+            footer: 0,
+        }
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+
+    /// 长图末尾固定页脚的行数，新内容插在页脚之前
+    pub fn footer(&self) -> u32 {
+        self.footer
+    }
+
+    /// 拼接下一帧，帧的宽度和高度必须与第一帧相同
+    ///
+    /// 两帧顶部和底部位置不变的行视为固定的页眉页脚，不参与匹配，新内容插在页脚之前。
+    pub fn push(&mut self, frame: &RgbaImage) -> StitchResult {
+        let rows = row_hashes(frame);
+        if rows == self.last_rows
+            || frame.width() != self.image.width()
+            || rows.len() != self.last_rows.len()
+        {
+            return StitchResult::Unchanged;
+        }
+
+        let height = rows.len();
+        let top = static_rows(rows.iter(), self.last_rows.iter());
+        let bottom = static_rows(rows.iter().rev(), self.last_rows.iter().rev()).min(height - top);
+        let middle = top..height - bottom;
+
+        let (new_rows, result) = match find_scroll(&self.last_rows[middle.clone()], &rows[middle]) {
+            Some(0) => return StitchResult::Unchanged,
+            Some(shift) => (shift, StitchResult::Appended(shift as u32)),
+            None => (height - top - bottom, StitchResult::NoOverlap),
+        };
+        let end = (height - bottom) as u32;
+        self.footer = bottom as u32;
+        self.insert(frame, end - new_rows as u32..end, self.footer);
+        self.last_rows = rows;
+        result
+    }
+
+    /// 把 frame 中 `rows` 范围的行插到长图最后 `footer` 行之前
+    fn insert(&mut self, frame: &RgbaImage, rows: Range<u32>, footer: u32) {
+        let width = self.image.width();
+        let height = self.image.height() + rows.len() as u32;
+        let row_bytes = width as usize * 4;
+        let at = (self.image.height() - footer) as usize * row_bytes;
+        let new = &frame.as_raw()[rows.start as usize * row_bytes..rows.end as usize * row_bytes];
+        let mut data = std::mem::take(&mut self.image).into_raw();
+        data.splice(at..at, new.iter().copied());
+        self.image =
+            RgbaImage::from_raw(width, height, data).expect("stitched buffer matches its size");
+    }
+}
+
+/// 两帧从一端开始逐行相同的行数
+fn static_rows<'a>(a: impl Iterator<Item = &'a u64>, b: impl Iterator<Item = &'a u64>) -> usize {
+    a.zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// 找出内容向上滚动的行数：prev 的第 `shift + i` 行对应 next 的第 `i` 行
+///
+/// 纯色行（空白背景）只在颜色不同时计为不匹配，相同时不计入匹配分数。
+fn find_scroll(prev: &[u64], next: &[u64]) -> Option<usize> {
+    let height = prev.len().min(next.len());
+    let mut best: Option<(usize, usize)> = None; // (得分, shift)
+
+    for shift in 0..height {
+        let overlap = height - shift;
+        let mut informative = 0;
+        let mut matched = 0;
+        for i in 0..overlap {
+            let row = next[i];
+            if row & UNIFORM_ROW != 0 {
+                if prev[i + shift] != row {
+                    informative += 1;
+                }
+                continue;
+            }
+            informative += 1;
+            if prev[i + shift] == row {
+                matched += 1;
+            }
+        }
+        if matched < Stitcher::MIN_MATCHED_ROWS
+            || (matched as f32) < informative as f32 * Stitcher::MIN_MATCH_RATIO
+        {
+            continue;
+        }
+        // 得分相同时取滚动距离小的
+        let score = matched.saturating_sub((informative - matched) * Stitcher::MISMATCH_PENALTY);
+        if best.is_none_or(|(s, _)| score > s) {
+            best = Some((score, shift));
+        }
+    }
+    best.map(|(_, shift)| shift)
+}
+
+/// 纯色行哈希的标记位，低 32 位是该行的颜色
+const UNIFORM_ROW: u64 = 1 << 63;
+
+/// 每行像素的 FNV-1a 哈希，纯色行记为 `UNIFORM_ROW` 加上颜色
+fn row_hashes(image: &RgbaImage) -> Vec<u64> {
+    let row_bytes = image.width() as usize * 4;
+    image
+        .as_raw()
+        .chunks_exact(row_bytes.max(1))
+        .map(|row| {
+            if row.chunks_exact(4).all(|px| px == &row[..4]) {
+                return UNIFORM_ROW | u32::from_le_bytes([row[0], row[1], row[2], row[3]]) as u64;
+            }
+            let hash = row.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, &b| {
+                (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+            });
+            hash & !UNIFORM_ROW
+        })
+        .collect()
+}
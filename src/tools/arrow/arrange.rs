@@ -0,0 +1,351 @@
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, StrokeKind, Vec2};
+
+use super::drawable::{ArrowDrawer, DrawState};
+
+/// 对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    CenterH, // 水平居中
+    Right,
+    Top,
+    Middle, // 垂直居中
+    Bottom,
+}
+
+/// 分布方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribute {
+    Horizontal,
+    Vertical,
+}
+
+/// 排列工具条上的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrangeAction {
+    Align(Align),
+    Distribute(Distribute),
+    BringToFront,
+    SendToBack,
+}
+
+impl ArrangeAction {
+    fn all() -> &'static [ArrangeAction] {
+        &[
+            ArrangeAction::Align(Align::Left),
+            ArrangeAction::Align(Align::CenterH),
+            ArrangeAction::Align(Align::Right),
+            ArrangeAction::Align(Align::Top),
+            ArrangeAction::Align(Align::Middle),
+            ArrangeAction::Align(Align::Bottom),
+            ArrangeAction::Distribute(Distribute::Horizontal),
+            ArrangeAction::Distribute(Distribute::Vertical),
+            ArrangeAction::BringToFront,
+            ArrangeAction::SendToBack,
+        ]
+    }
+
+    fn tooltip(&self) -> &'static str {
+        match self {
+            ArrangeAction::Align(Align::Left) => "Align left",
+            ArrangeAction::Align(Align::CenterH) => "Align center",
+            ArrangeAction::Align(Align::Right) => "Align right",
+            ArrangeAction::Align(Align::Top) => "Align top",
+            ArrangeAction::Align(Align::Middle) => "Align middle",
+            ArrangeAction::Align(Align::Bottom) => "Align bottom",
+            ArrangeAction::Distribute(Distribute::Horizontal) => "Distribute horizontally",
+            ArrangeAction::Distribute(Distribute::Vertical) => "Distribute vertically",
+            ArrangeAction::BringToFront => "Bring to front",
+            ArrangeAction::SendToBack => "Send to back",
+        }
+    }
+}
+
+impl ArrowDrawer {
+    /// 第 `idx` 个箭头中心线的外接矩形
+    fn path_rect(&self, idx: usize) -> Rect {
+        Rect::from_points(&self.arrows[idx].centerline())
+    }
+
+    /// 所有选中箭头的外接矩形
+    pub fn selection_rect(&self) -> Option<Rect> {
+        self.selection
+            .iter()
+            .filter(|&&idx| idx < self.arrows.len())
+            .map(|&idx| self.path_rect(idx))
+            .reduce(|a, b| a.union(b))
+    }
+
+    /// 对齐选中的箭头：多选时对齐到整体外接矩形，单选时对齐到 `bounds`（选区）
+    pub fn align_selected(&mut self, align: Align, bounds: Rect) {
+        let target = if self.selection.len() > 1 {
+            match self.selection_rect() {
+                Some(rect) => rect,
+                None => return,
+            }
+        } else {
+            bounds
+        };
+        let before = self.arrows.clone();
+        for idx in self.selection.clone() {
+            if idx >= self.arrows.len() {
+                continue;
+            }
+            let rect = self.path_rect(idx);
+            let delta = match align {
+                Align::Left => Vec2::new(target.left() - rect.left(), 0.0),
+                Align::CenterH => Vec2::new(target.center().x - rect.center().x, 0.0),
+                Align::Right => Vec2::new(target.right() - rect.right(), 0.0),
+                Align::Top => Vec2::new(0.0, target.top() - rect.top()),
+                Align::Middle => Vec2::new(0.0, target.center().y - rect.center().y),
+                Align::Bottom => Vec2::new(0.0, target.bottom() - rect.bottom()),
+            };
+            self.arrows[idx].translate(delta);
+        }
+        self.record_history(before);
+    }
+
+    /// 按中心等间距分布，首尾两个箭头不动，至少需要三个
+    pub fn distribute_selected(&mut self, direction: Distribute) {
+        let axis = |p: Pos2| match direction {
+            Distribute::Horizontal => p.x,
+            Distribute::Vertical => p.y,
+        };
+        let mut items: Vec<(usize, f32)> = self
+            .selection
+            .iter()
+            .filter(|&&idx| idx < self.arrows.len())
+            .map(|&idx| (idx, axis(self.path_rect(idx).center())))
+            .collect();
+        if items.len() < 3 {
+            return;
+        }
+        items.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let before = self.arrows.clone();
+        let first = items[0].1;
+        let step = (items[items.len() - 1].1 - first) / (items.len() - 1) as f32;
+        for (i, &(idx, center)) in items.iter().enumerate() {
+            let offset = first + step * i as f32 - center;
+            let delta = match direction {
+                Distribute::Horizontal => Vec2::new(offset, 0.0),
+                Distribute::Vertical => Vec2::new(0.0, offset),
+            };
+            self.arrows[idx].translate(delta);
+        }
+        self.record_history(before);
+    }
+
+    /// 选中的箭头移到最上层，保持它们之间的先后顺序
+    pub fn bring_to_front(&mut self) {
+        self.reorder(false);
+    }
+
+    /// 选中的箭头移到最下层，保持它们之间的先后顺序
+    pub fn send_to_back(&mut self) {
+        self.reorder(true);
+    }
+
+    fn reorder(&mut self, to_back: bool) {
+        let before = self.arrows.clone();
+        let (mut selected, mut others) = (Vec::new(), Vec::new());
+        for (i, arrow) in std::mem::take(&mut self.arrows).into_iter().enumerate() {
+            if self.is_selected(i) {
+                selected.push(arrow);
+            } else {
+                others.push(arrow);
+            }
+        }
+        let count = selected.len();
+        let first = if to_back {
+            selected.append(&mut others);
+            self.arrows = selected;
+            0
+        } else {
+            others.append(&mut selected);
+            self.arrows = others;
+            self.arrows.len() - count
+        };
+        self.selection = (first..first + count).collect();
+        self.record_history(before);
+    }
+
+    /// 执行排列操作
+    pub fn arrange(&mut self, action: ArrangeAction, bounds: Rect) {
+        match action {
+            ArrangeAction::Align(align) => self.align_selected(align, bounds),
+            ArrangeAction::Distribute(direction) => self.distribute_selected(direction),
+            ArrangeAction::BringToFront => self.bring_to_front(),
+            ArrangeAction::SendToBack => self.send_to_back(),
+        }
+    }
+
+    /// 排列工具条的位置：选中箭头上方，放不下时放到下方
+    pub fn arrange_bar_rect(&self, screen: Rect) -> Option<Rect> {
+        if self.state != DrawState::Selected {
+            return None;
+        }
+        let anchor = self.selection_rect()?;
+        let size = Vec2::new(
+            ArrangeAction::all().len() as f32 * BUTTON_SIZE + 2.0 * BAR_PADDING,
+            BUTTON_SIZE + 2.0 * BAR_PADDING,
+        );
+        let mut pos = Pos2::new(
+            anchor.center().x - size.x / 2.0,
+            anchor.top() - size.y - 12.0,
+        );
+        if pos.y < screen.top() {
+            pos.y = anchor.bottom() + 12.0;
+        }
+        pos.x = pos
+            .x
+            .clamp(screen.left(), (screen.right() - size.x).max(screen.left()));
+        pos.y = pos
+            .y
+            .clamp(screen.top(), (screen.bottom() - size.y).max(screen.top()));
+        Some(Rect::from_min_size(pos, size))
+    }
+
+    /// 绘制排列工具条，返回点击的操作
+    pub fn show_arrange_bar(&self, ui: &mut egui::Ui, rect: Rect) -> Option<ArrangeAction> {
+        let painter = ui.painter();
+        painter.rect_filled(rect, 6.0, Color32::from_rgba_unmultiplied(40, 40, 40, 235));
+
+        let multiple = self.selection.len() > 2;
+        let mut clicked = None;
+        for (i, &action) in ArrangeAction::all().iter().enumerate() {
+            let button = Rect::from_min_size(
+                rect.min + Vec2::new(BAR_PADDING + i as f32 * BUTTON_SIZE, BAR_PADDING),
+                Vec2::splat(BUTTON_SIZE),
+            );
+            // 分布至少需要三个箭头
+            let enabled = multiple || !matches!(action, ArrangeAction::Distribute(_));
+            let response = ui
+                .interact(button, ui.id().with(("arrange", i)), Sense::click())
+                .on_hover_text(action.tooltip());
+            if enabled && response.hovered() {
+                ui.painter()
+                    .rect_filled(button.shrink(2.0), 4.0, Color32::from_gray(70));
+            }
+            let color = if enabled {
+                Color32::WHITE
+            } else {
+                Color32::from_gray(110)
+            };
+            draw_action_icon(ui.painter(), button.shrink(7.0), action, color);
+            if enabled && response.clicked() {
+                clicked = Some(action);
+            }
+        }
+        clicked
+    }
+}
+
+const BUTTON_SIZE: f32 = 28.0;
+const BAR_PADDING: f32 = 4.0;
+
+/// 排列操作的图标：一条基准线加两个方块
+fn draw_action_icon(painter: &egui::Painter, rect: Rect, action: ArrangeAction, color: Color32) {
+    let stroke = Stroke::new(1.5, color);
+    let block = |min: Pos2, size: Vec2| Rect::from_min_size(min, size);
+    let (w, h) = (rect.width(), rect.height());
+    match action {
+        ArrangeAction::Align(align) => {
+            let (line, blocks) = match align {
+                Align::Left => (
+                    [rect.left_top(), rect.left_bottom()],
+                    [
+                        block(rect.min + Vec2::new(2.0, 1.0), Vec2::new(w * 0.7, 4.0)),
+                        block(rect.min + Vec2::new(2.0, h - 5.0), Vec2::new(w * 0.45, 4.0)),
+                    ],
+                ),
+                Align::CenterH => (
+                    [rect.center_top(), rect.center_bottom()],
+                    [
+                        block(rect.min + Vec2::new(w * 0.15, 1.0), Vec2::new(w * 0.7, 4.0)),
+                        block(
+                            rect.min + Vec2::new(w * 0.3, h - 5.0),
+                            Vec2::new(w * 0.4, 4.0),
+                        ),
+                    ],
+                ),
+                Align::Right => (
+                    [rect.right_top(), rect.right_bottom()],
+                    [
+                        block(
+                            rect.min + Vec2::new(w * 0.3 - 2.0, 1.0),
+                            Vec2::new(w * 0.7, 4.0),
+                        ),
+                        block(
+                            rect.min + Vec2::new(w * 0.55 - 2.0, h - 5.0),
+                            Vec2::new(w * 0.45, 4.0),
+                        ),
+                    ],
+                ),
+                Align::Top => (
+                    [rect.left_top(), rect.right_top()],
+                    [
+                        block(rect.min + Vec2::new(1.0, 2.0), Vec2::new(4.0, h * 0.7)),
+                        block(rect.min + Vec2::new(w - 5.0, 2.0), Vec2::new(4.0, h * 0.45)),
+                    ],
+                ),
+                Align::Middle => (
+                    [rect.left_center(), rect.right_center()],
+                    [
+                        block(rect.min + Vec2::new(1.0, h * 0.15), Vec2::new(4.0, h * 0.7)),
+                        block(
+                            rect.min + Vec2::new(w - 5.0, h * 0.3),
+                            Vec2::new(4.0, h * 0.4),
+                        ),
+                    ],
+                ),
+                Align::Bottom => (
+                    [rect.left_bottom(), rect.right_bottom()],
+                    [
+                        block(
+                            rect.min + Vec2::new(1.0, h * 0.3 - 2.0),
+                            Vec2::new(4.0, h * 0.7),
+                        ),
+                        block(
+                            rect.min + Vec2::new(w - 5.0, h * 0.55 - 2.0),
+                            Vec2::new(4.0, h * 0.45),
+                        ),
+                    ],
+                ),
+            };
+            painter.line_segment(line, stroke);
+            for b in blocks {
+                painter.rect_filled(b, 1.0, color);
+            }
+        }
+        ArrangeAction::Distribute(direction) => {
+            // 三个等距的方块
+            for i in 0..3 {
+                let t = i as f32 / 2.0;
+                let b = match direction {
+                    Distribute::Horizontal => block(
+                        rect.min + Vec2::new(t * (w - 4.0), h * 0.2),
+                        Vec2::new(4.0, h * 0.6),
+                    ),
+                    Distribute::Vertical => block(
+                        rect.min + Vec2::new(w * 0.2, t * (h - 4.0)),
+                        Vec2::new(w * 0.6, 4.0),
+                    ),
+                };
+                painter.rect_filled(b, 1.0, color);
+            }
+        }
+        ArrangeAction::BringToFront | ArrangeAction::SendToBack => {
+            // 两个叠放的方块，实心的那个在上层或下层
+            let back = block(rect.min, rect.size() * 0.65);
+            let front = block(rect.min + rect.size() * 0.35, rect.size() * 0.65);
+            let (filled, outlined) = if action == ArrangeAction::BringToFront {
+                (front, back)
+            } else {
+                (back, front)
+            };
+            painter.rect_stroke(outlined, 1.0, stroke, StrokeKind::Inside);
+            painter.rect_filled(filled, 1.0, color);
+        }
+    }
+}
@@ -1,14 +1,20 @@
 use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
 
+use super::effects::Effects;
+use super::path::PathKind;
 use super::types::{ArrowType, LineStyle};
 
 /// 箭头工具配置
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ArrowConfig {
     pub arrow_type: ArrowType,
     pub line_style: LineStyle,
+    pub path_kind: PathKind,
     pub color: Color32,
     pub stroke_width: f32,
+    pub effects: Effects,
 }
 
 impl Default for ArrowConfig {
@@ -16,8 +22,10 @@ impl Default for ArrowConfig {
         Self {
             arrow_type: ArrowType::Single,
             line_style: LineStyle::Solid,
+            path_kind: PathKind::Straight,
             color: Color32::RED,
             stroke_width: 4.0,
+            effects: Effects::default(),
         }
     }
 }
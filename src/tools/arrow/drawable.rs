@@ -1,17 +1,21 @@
-use eframe::egui::{self, Color32, Pos2, Rect, Stroke, Vec2};
+use eframe::egui::{self, Color32, Pos2, Rect, Stroke, StrokeKind, Vec2};
 use image::{ImageBuffer, Rgba};
-use imageproc::drawing::draw_antialiased_polygon_mut;
-use imageproc::pixelops::interpolate;
-use imageproc::point::Point;
+use serde::{Deserialize, Serialize};
 
 use super::config::ArrowConfig;
+use super::effects::Effects;
+use super::geometry::Geometry;
+use super::history::History;
+use super::path::{self, ArrowPath};
+use super::raster::Rasterizer;
+use super::snap::{SnapHint, SnapTargets};
 use super::types::{ArrowType, LineStyle};
 
 /// RgbaImage 类型别名
 pub type RgbaImage = ImageBuffer<Rgba<u8>, Vec<u8>>;
 
 /// 单个箭头实例
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Arrow {
     pub start: Pos2,
     pub end: Pos2,
@@ -19,6 +23,10 @@ pub struct Arrow {
     pub line_style: LineStyle,
     pub color: Color32,
     pub stroke_width: f32,
+    #[serde(default)]
+    pub path: ArrowPath,
+    #[serde(default)]
+    pub effects: Effects,
 }
 
 impl Arrow {
@@ -31,699 +39,87 @@ impl Arrow {
             line_style: config.line_style,
             color: config.color,
             stroke_width: config.stroke_width,
+            path: ArrowPath::new(config.path_kind, start, end),
+            effects: config.effects,
         }
     }
 
-    /// 获取箭头的包围盒
-    pub fn bounding_rect(&self) -> Rect {
-        let min_x = self.start.x.min(self.end.x);
-        let min_y = self.start.y.min(self.end.y);
-        let max_x = self.start.x.max(self.end.x);
-        let max_y = self.start.y.max(self.end.y);
-        Rect::from_min_max(
-            Pos2::new(min_x - self.stroke_width, min_y - self.stroke_width),
-            Pos2::new(max_x + self.stroke_width, max_y + self.stroke_width),
-        )
-    }
-
-    /// 检测点是否在箭头附近（用于选中）
-    pub fn hit_test(&self, pos: Pos2, tolerance: f32) -> bool {
-        let dist = Self::point_to_line_distance(pos, self.start, self.end);
-        dist <= self.stroke_width / 2.0 + tolerance
-    }
-
-    /// 点到线段的距离
-    fn point_to_line_distance(point: Pos2, line_start: Pos2, line_end: Pos2) -> f32 {
-        let line = line_end - line_start;
-        let len_sq = line.length_sq();
-
-        if len_sq == 0.0 {
-            return (point - line_start).length();
-        }
-
-        let t = ((point - line_start).dot(line) / len_sq).clamp(0.0, 1.0);
-        let projection = line_start + line * t;
-        (point - projection).length()
-    }
-
-    /// 绘制箭头
-    pub fn draw(&self, painter: &egui::Painter) {
-        let stroke = Stroke::new(self.stroke_width, self.color);
-        let dir = (self.end - self.start).normalized();
-        let head_size = self.stroke_width * 3.0;
-
-        match self.arrow_type {
-            ArrowType::Single => {
-                // 直线箭头：线条到三角形底部 + 填充三角形
-                let line_end = self.end - dir * head_size;
-                self.draw_line(painter, self.start, line_end, stroke);
-                self.draw_v_arrow_head(painter, self.end, dir, head_size);
-            }
-            ArrowType::Double => {
-                // 双向箭头：两端都有填充三角形
-                let line_start = self.start + dir * head_size;
-                let line_end = self.end - dir * head_size;
-                self.draw_line(painter, line_start, line_end, stroke);
-                self.draw_v_arrow_head(painter, self.end, dir, head_size);
-                self.draw_v_arrow_head(painter, self.start, -dir, head_size);
-            }
-            ArrowType::Hollow => {
-                // 空心箭头：整个形状只有轮廓
-                self.draw_hollow_arrow(painter, dir, head_size);
-            }
-            ArrowType::Filled => {
-                // 实心箭头：渐变粗线条 + 实心三角形
-                let back = self.end - dir * head_size;
-                self.draw_tapered_line(painter, self.start, back, dir, head_size);
-                self.draw_filled_triangle(painter, self.end, dir, head_size);
-            }
-        }
-    }
-
-    /// 绘制 V 形箭头尖端（用于直线和双向箭头）- 使用填充三角形
-    fn draw_v_arrow_head(&self, painter: &egui::Painter, tip: Pos2, dir: Vec2, size: f32) {
-        let perpendicular = Vec2::new(-dir.y, dir.x);
-        // 箭头尖端的小填充三角形
-        let back = tip - dir * size;
-        let left = back + perpendicular * size * 0.35;
-        let right = back - perpendicular * size * 0.35;
-
-        let points = vec![tip, left, right];
-        painter.add(egui::Shape::convex_polygon(
-            points,
-            self.color,
-            Stroke::NONE,
-        ));
-    }
-
-    /// 绘制整个空心箭头（从细到粗的轮廓 + 空心三角形）
-    fn draw_hollow_arrow(&self, painter: &egui::Painter, dir: Vec2, head_size: f32) {
-        let perpendicular = Vec2::new(-dir.y, dir.x);
-        let stroke = Stroke::new(self.stroke_width * 0.4, self.color);
-
-        // 计算关键点
-        let start_width = self.stroke_width * 0.15;
-        let end_width = head_size * 0.5;
-        let back = self.end - dir * head_size;
-
-        // 线条部分的四个角点
-        let p1 = self.start + perpendicular * start_width; // 起点上
-        let p2 = self.start - perpendicular * start_width; // 起点下
-        let p3 = back - perpendicular * end_width; // 底部下
-        let p4 = back + perpendicular * end_width; // 底部上
-
-        // 三角形的三个顶点
-        let tip = self.end;
-        let tri_left = back + perpendicular * head_size * 0.5;
-        let tri_right = back - perpendicular * head_size * 0.5;
-
-        // 绘制外轮廓（从起点上方 -> 底部上方 -> 三角形左 -> 尖端 -> 三角形右 -> 底部下方 -> 起点下方 -> 起点上方）
-        painter.line_segment([p1, p4], stroke); // 上边线
-        painter.line_segment([p4, tri_left], stroke); // 到三角形左
-        painter.line_segment([tri_left, tip], stroke); // 三角形左边
-        painter.line_segment([tip, tri_right], stroke); // 三角形右边
-        painter.line_segment([tri_right, p3], stroke); // 从三角形右
-        painter.line_segment([p3, p2], stroke); // 下边线
-        painter.line_segment([p2, p1], stroke); // 起点封口
-    }
-
-    /// 绘制实心三角形
-    fn draw_filled_triangle(&self, painter: &egui::Painter, tip: Pos2, dir: Vec2, size: f32) {
-        let perpendicular = Vec2::new(-dir.y, dir.x);
-        let back = tip - dir * size;
-        let left = back + perpendicular * size * 0.5;
-        let right = back - perpendicular * size * 0.5;
-
-        let points = vec![tip, left, right];
-        painter.add(egui::Shape::convex_polygon(
-            points,
-            self.color,
-            Stroke::NONE,
-        ));
-    }
-
-    /// 绘制渐变粗细的线条（从细到粗）
-    fn draw_tapered_line(
-        &self,
-        painter: &egui::Painter,
-        start: Pos2,
-        end: Pos2,
-        dir: Vec2,
-        head_size: f32,
-    ) {
-        let perpendicular = Vec2::new(-dir.y, dir.x);
-        let start_width = self.stroke_width * 0.3;
-        let end_width = head_size * 0.5;
-
-        // 创建梯形形状
-        let p1 = start + perpendicular * start_width * 0.5;
-        let p2 = start - perpendicular * start_width * 0.5;
-        let p3 = end - perpendicular * end_width;
-        let p4 = end + perpendicular * end_width;
-
-        let points = vec![p1, p4, p3, p2];
-        painter.add(egui::Shape::convex_polygon(
-            points,
-            self.color,
-            Stroke::NONE,
-        ));
-    }
-
-    /// 根据线段类型绘制线条
-    fn draw_line(&self, painter: &egui::Painter, start: Pos2, end: Pos2, stroke: Stroke) {
-        match self.line_style {
-            LineStyle::Solid => {
-                painter.line_segment([start, end], stroke);
-            }
-            LineStyle::Dashed => {
-                self.draw_dashed_line(painter, start, end, stroke, 12.0, 6.0);
-            }
-            LineStyle::Dotted => {
-                self.draw_dotted_line(painter, start, end, stroke);
-            }
-            LineStyle::DashDot => {
-                self.draw_dash_dot_line(painter, start, end, stroke);
-            }
-            LineStyle::DashDotDot => {
-                self.draw_dash_dot_dot_line(painter, start, end, stroke);
-            }
+    /// 箭头当前的样式，形式与面板配置相同
+    pub fn style(&self) -> ArrowConfig {
+        ArrowConfig {
+            arrow_type: self.arrow_type,
+            line_style: self.line_style,
+            path_kind: self.path.kind(),
+            color: self.color,
+            stroke_width: self.stroke_width,
+            effects: self.effects,
         }
     }
 
-    /// 绘制虚线
-    fn draw_dashed_line(
-        &self,
-        painter: &egui::Painter,
-        start: Pos2,
-        end: Pos2,
-        stroke: Stroke,
-        dash_len: f32,
-        gap_len: f32,
-    ) {
-        let dir = (end - start).normalized();
-        let total_len = (end - start).length();
-        let mut pos = 0.0;
-        let mut is_dash = true;
-
-        while pos < total_len {
-            if is_dash {
-                let seg_end = (pos + dash_len).min(total_len);
-                painter.line_segment([start + dir * pos, start + dir * seg_end], stroke);
-                pos = seg_end;
-            } else {
-                pos += gap_len;
-            }
-            is_dash = !is_dash;
+    /// 坐标变换：`p' = (p - origin) * scale`，线宽同比缩放
+    pub fn transformed(&self, origin: Pos2, scale: f32) -> Self {
+        let map = |p: Pos2| Pos2::ZERO + (p - origin) * scale;
+        let mut path = self.path.clone();
+        path.map(map);
+        Self {
+            start: map(self.start),
+            end: map(self.end),
+            stroke_width: self.stroke_width * scale,
+            path,
+            effects: self.effects.scaled(scale),
+            ..self.clone()
         }
     }
 
-    /// 绘制点线
-    fn draw_dotted_line(&self, painter: &egui::Painter, start: Pos2, end: Pos2, stroke: Stroke) {
-        let dir = (end - start).normalized();
-        let total_len = (end - start).length();
-        let dot_gap = stroke.width * 2.0;
-        let mut pos = 0.0;
-
-        while pos < total_len {
-            painter.circle_filled(start + dir * pos, stroke.width / 2.0, stroke.color);
-            pos += dot_gap;
-        }
+    /// 整体平移，包括路径上的控制点
+    pub fn translate(&mut self, delta: Vec2) {
+        self.start += delta;
+        self.end += delta;
+        self.path.map(|p| p + delta);
     }
 
-    /// 绘制点划线
-    fn draw_dash_dot_line(&self, painter: &egui::Painter, start: Pos2, end: Pos2, stroke: Stroke) {
-        let dir = (end - start).normalized();
-        let total_len = (end - start).length();
-        let dash_len = 12.0;
-        let gap_len = 4.0;
-        let mut pos = 0.0;
-        let mut state = 0; // 0=dash, 1=gap, 2=dot, 3=gap
-
-        while pos < total_len {
-            match state {
-                0 => {
-                    let seg_end = (pos + dash_len).min(total_len);
-                    painter.line_segment([start + dir * pos, start + dir * seg_end], stroke);
-                    pos = seg_end;
-                }
-                1 | 3 => {
-                    pos += gap_len;
-                }
-                2 => {
-                    painter.circle_filled(start + dir * pos, stroke.width / 2.0, stroke.color);
-                    pos += stroke.width;
-                }
-                _ => {}
-            }
-            state = (state + 1) % 4;
-        }
+    /// 中心线：起点到终点依次经过的点
+    pub fn centerline(&self) -> Vec<Pos2> {
+        self.path.centerline(self.start, self.end)
     }
 
-    /// 绘制双点划线
-    fn draw_dash_dot_dot_line(
-        &self,
-        painter: &egui::Painter,
-        start: Pos2,
-        end: Pos2,
-        stroke: Stroke,
-    ) {
-        let dir = (end - start).normalized();
-        let total_len = (end - start).length();
-        let dash_len = 12.0;
-        let gap_len = 4.0;
-        let mut pos = 0.0;
-        let mut state = 0; // 0=dash, 1=gap, 2=dot, 3=gap, 4=dot, 5=gap
-
-        while pos < total_len {
-            match state {
-                0 => {
-                    let seg_end = (pos + dash_len).min(total_len);
-                    painter.line_segment([start + dir * pos, start + dir * seg_end], stroke);
-                    pos = seg_end;
-                }
-                1 | 3 | 5 => {
-                    pos += gap_len;
-                }
-                2 | 4 => {
-                    painter.circle_filled(start + dir * pos, stroke.width / 2.0, stroke.color);
-                    pos += stroke.width;
-                }
-                _ => {}
-            }
-            state = (state + 1) % 6;
-        }
-    }
-
-    // ========== 渲染到图像的方法 ==========
-
-    fn blend_rgba(line: Rgba<u8>, original: Rgba<u8>, weight: f32) -> Rgba<u8> {
-        interpolate(line, original, weight.clamp(0.0, 1.0))
+    /// 获取箭头的包围盒
+    pub fn bounding_rect(&self) -> Rect {
+        Rect::from_points(&self.centerline()).expand(self.stroke_width)
     }
 
-    fn round_point(p: (f32, f32)) -> Point<i32> {
-        Point::new(p.0.round() as i32, p.1.round() as i32)
+    /// 检测点是否在箭头附近（用于选中）
+    pub fn hit_test(&self, pos: Pos2, tolerance: f32) -> bool {
+        let (dist, _) = path::distance_to(&self.centerline(), pos);
+        dist <= self.stroke_width / 2.0 + tolerance
     }
 
-    fn draw_antialiased_circle(
-        img: &mut RgbaImage,
-        center: (f32, f32),
-        radius: f32,
-        color: Rgba<u8>,
-    ) {
-        if radius <= 0.0 {
-            return;
-        }
-
-        let r = radius.max(0.5);
-        let min_x = (center.0 - r - 1.0).floor() as i32;
-        let max_x = (center.0 + r + 1.0).ceil() as i32;
-        let min_y = (center.1 - r - 1.0).floor() as i32;
-        let max_y = (center.1 + r + 1.0).ceil() as i32;
-
-        let width = img.width() as i32;
-        let height = img.height() as i32;
-
-        for y in min_y..=max_y {
-            if y < 0 || y >= height {
-                continue;
-            }
-            for x in min_x..=max_x {
-                if x < 0 || x >= width {
-                    continue;
-                }
-
-                let dx = x as f32 + 0.5 - center.0;
-                let dy = y as f32 + 0.5 - center.1;
-                let dist = (dx * dx + dy * dy).sqrt();
-                let delta = r - dist;
-
-                if delta >= 0.5 {
-                    img.put_pixel(x as u32, y as u32, color);
-                } else if delta > -0.5 {
-                    let weight = (delta + 0.5).clamp(0.0, 1.0);
-                    let original = img.get_pixel(x as u32, y as u32);
-                    let blended = Self::blend_rgba(color, *original, weight);
-                    img.put_pixel(x as u32, y as u32, blended);
-                }
-            }
-        }
+    /// 箭头的几何形状，屏幕绘制和导出共用
+    pub fn geometry(&self) -> Geometry {
+        Geometry::of(self)
     }
 
-    fn draw_aa_line_with_width(
-        img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        width: f32,
-        color: Rgba<u8>,
-    ) {
-        let stroke_width = width.max(1.0);
-        let dx = end.0 - start.0;
-        let dy = end.1 - start.1;
-        let len = (dx * dx + dy * dy).sqrt();
-
-        if len == 0.0 {
-            Self::draw_antialiased_circle(img, start, stroke_width / 2.0, color);
-            return;
-        }
-
-        // 计算包围线段的矩形，使用抗锯齿多边形绘制
-        let perp = (-dy / len, dx / len);
-        let half = stroke_width / 2.0;
-
-        let p1 = (start.0 + perp.0 * half, start.1 + perp.1 * half);
-        let p2 = (start.0 - perp.0 * half, start.1 - perp.1 * half);
-        let p3 = (end.0 - perp.0 * half, end.1 - perp.1 * half);
-        let p4 = (end.0 + perp.0 * half, end.1 + perp.1 * half);
-
-        let points = [
-            Self::round_point(p1),
-            Self::round_point(p2),
-            Self::round_point(p3),
-            Self::round_point(p4),
-        ];
-        draw_antialiased_polygon_mut(img, &points, color, Self::blend_rgba);
-
-        // 圆角端点
-        Self::draw_antialiased_circle(img, start, half, color);
-        Self::draw_antialiased_circle(img, end, half, color);
-    }
-
-    /// 将箭头渲染到图像上
-    pub fn render_to_image(&self, img: &mut RgbaImage, offset_x: f32, offset_y: f32, ppp: f32) {
-        let color = Rgba([
-            self.color.r(),
-            self.color.g(),
-            self.color.b(),
-            self.color.a(),
-        ]);
-
-        // 转换坐标
-        let start = (
-            (self.start.x - offset_x) * ppp,
-            (self.start.y - offset_y) * ppp,
-        );
-        let end = ((self.end.x - offset_x) * ppp, (self.end.y - offset_y) * ppp);
-
-        let stroke_width_px = self.stroke_width * ppp;
-        let head_size = stroke_width_px * 3.0;
-
-        let dir_x = end.0 - start.0;
-        let dir_y = end.1 - start.1;
-        let len = (dir_x * dir_x + dir_y * dir_y).sqrt();
-        if len == 0.0 {
-            return;
-        }
-        let dir = (dir_x / len, dir_y / len);
-
-        match self.arrow_type {
-            ArrowType::Single => {
-                // 直线箭头：线条到三角形底部 + 填充三角形
-                let line_end = (end.0 - dir.0 * head_size, end.1 - dir.1 * head_size);
-                self.render_line_to_image(img, start, line_end, color, stroke_width_px);
-                Self::render_v_arrow_head(img, end, dir, head_size, stroke_width_px, color);
-            }
-            ArrowType::Double => {
-                // 双向箭头：两端都有填充三角形
-                let line_start = (start.0 + dir.0 * head_size, start.1 + dir.1 * head_size);
-                let line_end = (end.0 - dir.0 * head_size, end.1 - dir.1 * head_size);
-                self.render_line_to_image(img, line_start, line_end, color, stroke_width_px);
-                Self::render_v_arrow_head(img, end, dir, head_size, stroke_width_px, color);
-                Self::render_v_arrow_head(
-                    img,
-                    start,
-                    (-dir.0, -dir.1),
-                    head_size,
-                    stroke_width_px,
-                    color,
-                );
-            }
-            ArrowType::Hollow => {
-                // 空心箭头：整个形状只有轮廓
-                Self::render_hollow_arrow(img, start, end, dir, stroke_width_px, head_size, color);
-            }
-            ArrowType::Filled => {
-                // 实心箭头：渐变粗线条 + 实心三角形
-                let back = (end.0 - dir.0 * head_size, end.1 - dir.1 * head_size);
-                Self::render_tapered_line(img, start, back, dir, stroke_width_px, head_size, color);
-                Self::render_filled_triangle(img, end, dir, head_size, color);
-            }
-        }
+    /// 绘制箭头，效果画在箭头下面
+    pub fn draw(&self, painter: &egui::Painter) {
+        let geometry = self.geometry();
+        self.effects.paint(&geometry, painter);
+        geometry.paint(painter, self.color);
     }
 
-    /// 渲染 V 形箭头尖端 - 使用填充三角形
-    fn render_v_arrow_head(
-        img: &mut RgbaImage,
-        tip: (f32, f32),
-        dir: (f32, f32),
-        size: f32,
-        _stroke_width: f32,
-        color: Rgba<u8>,
-    ) {
-        let perpendicular = (-dir.1, dir.0);
-        let back = (tip.0 - dir.0 * size, tip.1 - dir.1 * size);
-        let left = (
-            back.0 + perpendicular.0 * size * 0.35,
-            back.1 + perpendicular.1 * size * 0.35,
-        );
-        let right = (
-            back.0 - perpendicular.0 * size * 0.35,
-            back.1 - perpendicular.1 * size * 0.35,
-        );
-
-        let points = [
-            Self::round_point(tip),
-            Self::round_point(left),
-            Self::round_point(right),
-        ];
-        draw_antialiased_polygon_mut(img, &points, color, Self::blend_rgba);
-    }
-
-    /// 渲染整个空心箭头
-    fn render_hollow_arrow(
-        img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        dir: (f32, f32),
-        stroke_width: f32,
-        head_size: f32,
-        color: Rgba<u8>,
-    ) {
-        let perpendicular = (-dir.1, dir.0);
-        let line_width = (stroke_width * 0.4).max(1.0);
-
-        // 计算关键点
-        let start_width = stroke_width * 0.15;
-        let end_width = head_size * 0.5;
-        let back = (end.0 - dir.0 * head_size, end.1 - dir.1 * head_size);
-
-        // 线条部分的四个角点
-        let p1 = (
-            start.0 + perpendicular.0 * start_width,
-            start.1 + perpendicular.1 * start_width,
-        );
-        let p2 = (
-            start.0 - perpendicular.0 * start_width,
-            start.1 - perpendicular.1 * start_width,
-        );
-        let p3 = (
-            back.0 - perpendicular.0 * end_width,
-            back.1 - perpendicular.1 * end_width,
-        );
-        let p4 = (
-            back.0 + perpendicular.0 * end_width,
-            back.1 + perpendicular.1 * end_width,
-        );
-
-        // 三角形的三个顶点
-        let tip = end;
-        let tri_left = (
-            back.0 + perpendicular.0 * head_size * 0.5,
-            back.1 + perpendicular.1 * head_size * 0.5,
-        );
-        let tri_right = (
-            back.0 - perpendicular.0 * head_size * 0.5,
-            back.1 - perpendicular.1 * head_size * 0.5,
-        );
-
-        // 绘制外轮廓
-        Self::draw_aa_line_with_width(img, p1, p4, line_width, color);
-        Self::draw_aa_line_with_width(img, p4, tri_left, line_width, color);
-        Self::draw_aa_line_with_width(img, tri_left, tip, line_width, color);
-        Self::draw_aa_line_with_width(img, tip, tri_right, line_width, color);
-        Self::draw_aa_line_with_width(img, tri_right, p3, line_width, color);
-        Self::draw_aa_line_with_width(img, p3, p2, line_width, color);
-        Self::draw_aa_line_with_width(img, p2, p1, line_width, color);
-    }
-
-    /// 渲染实心三角形
-    fn render_filled_triangle(
-        img: &mut RgbaImage,
-        tip: (f32, f32),
-        dir: (f32, f32),
-        size: f32,
-        color: Rgba<u8>,
-    ) {
-        let perpendicular = (-dir.1, dir.0);
-        let back = (tip.0 - dir.0 * size, tip.1 - dir.1 * size);
-        let left = (
-            back.0 + perpendicular.0 * size * 0.5,
-            back.1 + perpendicular.1 * size * 0.5,
-        );
-        let right = (
-            back.0 - perpendicular.0 * size * 0.5,
-            back.1 - perpendicular.1 * size * 0.5,
-        );
-
-        let points = [
-            Self::round_point(tip),
-            Self::round_point(left),
-            Self::round_point(right),
-        ];
-        draw_antialiased_polygon_mut(img, &points, color, Self::blend_rgba);
-    }
-
-    /// 渲染渐变粗细的线条
-    fn render_tapered_line(
-        img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        dir: (f32, f32),
-        stroke_width: f32,
-        head_size: f32,
-        color: Rgba<u8>,
-    ) {
-        let perpendicular = (-dir.1, dir.0);
-        let start_width = stroke_width * 0.3;
-        let end_width = head_size * 0.5;
-
-        let p1 = (
-            start.0 + perpendicular.0 * start_width * 0.5,
-            start.1 + perpendicular.1 * start_width * 0.5,
-        );
-        let p2 = (
-            start.0 - perpendicular.0 * start_width * 0.5,
-            start.1 - perpendicular.1 * start_width * 0.5,
-        );
-        let p3 = (
-            end.0 - perpendicular.0 * end_width,
-            end.1 - perpendicular.1 * end_width,
-        );
-        let p4 = (
-            end.0 + perpendicular.0 * end_width,
-            end.1 + perpendicular.1 * end_width,
-        );
-
-        let points = [
-            Self::round_point(p1),
-            Self::round_point(p4),
-            Self::round_point(p3),
-            Self::round_point(p2),
-        ];
-        draw_antialiased_polygon_mut(img, &points, color, Self::blend_rgba);
-    }
-
-    /// 渲染线段到图像
-    fn render_line_to_image(
+    /// 将箭头渲染到图像上：`offset` 为图像左上角的逻辑坐标，`ppp` 为逻辑坐标到像素的比例
+    pub fn render_to_image(
         &self,
         img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        color: Rgba<u8>,
-        stroke_width: f32,
+        offset_x: f32,
+        offset_y: f32,
+        ppp: f32,
+        rasterizer: Rasterizer,
     ) {
-        let width = stroke_width.max(1.0);
-
-        match self.line_style {
-            LineStyle::Solid => {
-                Self::draw_aa_line_with_width(img, start, end, width, color);
-            }
-            LineStyle::Dashed => {
-                Self::render_dashed_line(
-                    img,
-                    start,
-                    end,
-                    width,
-                    color,
-                    stroke_width * 1.5,
-                    stroke_width * 0.8,
-                );
-            }
-            LineStyle::Dotted => {
-                Self::render_dotted_line(img, start, end, width, color);
-            }
-            LineStyle::DashDot | LineStyle::DashDotDot => {
-                Self::render_dashed_line(
-                    img,
-                    start,
-                    end,
-                    width,
-                    color,
-                    stroke_width * 1.2,
-                    stroke_width * 0.6,
-                );
-            }
-        }
-    }
-
-    /// 渲染虚线到图像（静态方法）
-    fn render_dashed_line(
-        img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        width: f32,
-        color: Rgba<u8>,
-        dash_len: f32,
-        gap_len: f32,
-    ) {
-        let dx = end.0 - start.0;
-        let dy = end.1 - start.1;
-        let total_len = (dx * dx + dy * dy).sqrt();
-        if total_len == 0.0 {
-            return;
-        }
-
-        let dir = (dx / total_len, dy / total_len);
-        let mut pos = 0.0;
-        let mut is_dash = true;
-
-        while pos < total_len {
-            if is_dash {
-                let seg_start = (start.0 + dir.0 * pos, start.1 + dir.1 * pos);
-                let seg_end_pos = (pos + dash_len).min(total_len);
-                let seg_end = (start.0 + dir.0 * seg_end_pos, start.1 + dir.1 * seg_end_pos);
-                Self::draw_aa_line_with_width(img, seg_start, seg_end, width, color);
-                pos = seg_end_pos;
-            } else {
-                pos += gap_len;
-            }
-            is_dash = !is_dash;
-        }
-    }
-
-    /// 渲染点线到图像（静态方法）
-    fn render_dotted_line(
-        img: &mut RgbaImage,
-        start: (f32, f32),
-        end: (f32, f32),
-        width: f32,
-        color: Rgba<u8>,
-    ) {
-        let dx = end.0 - start.0;
-        let dy = end.1 - start.1;
-        let total_len = (dx * dx + dy * dy).sqrt();
-        if total_len == 0.0 {
-            return;
-        }
-
-        let dir = (dx / total_len, dy / total_len);
-        let dot_gap = (width * 2.0).max(1.0);
-        let mut pos = 0.0;
-
-        while pos < total_len {
-            let dot_pos = (start.0 + dir.0 * pos, start.1 + dir.1 * pos);
-            Self::draw_antialiased_circle(img, dot_pos, width / 2.0, color);
-            pos += dot_gap;
-        }
+        let geometry = self
+            .geometry()
+            .transformed(Pos2::new(offset_x, offset_y), ppp);
+        self.effects.scaled(ppp).render(&geometry, img, rasterizer);
+        rasterizer.rasterize(&geometry, img, self.color);
     }
 }
 
@@ -732,11 +128,22 @@ impl Arrow {
 pub enum DrawState {
     #[default]
     Idle,
-    Drawing,            // 正在绘制新箭头
-    Selected(usize),    // 选中了某个箭头
-    MovingStart(usize), // 移动箭头起点
-    MovingEnd(usize),   // 移动箭头终点
-    MovingWhole(usize), // 移动整个箭头
+    Drawing,                    // 正在绘制新箭头
+    Selected,                   // 选中了 `selection` 中的箭头
+    Selecting,                  // 正在框选
+    MovingStart(usize),         // 移动箭头起点
+    MovingEnd(usize),           // 移动箭头终点
+    MovingHandle(usize, usize), // 移动路径上的控制点
+    MovingSelection,            // 整体移动选中的箭头
+}
+
+/// 粘贴、复制出的箭头相对原位置的偏移
+const PASTE_OFFSET: f32 = 12.0;
+
+/// 系统剪贴板中的标注，字段名用来和普通文本区分
+#[derive(Serialize, Deserialize)]
+struct ClipboardArrows {
+    pxshot_arrows: Vec<Arrow>,
 }
 
 /// 箭头绘制管理器
@@ -745,7 +152,17 @@ pub struct ArrowDrawer {
     pub arrows: Vec<Arrow>,
     pub state: DrawState,
     pub current_start: Option<Pos2>,
-    drag_offset: Vec2,
+    /// 选中的箭头序号，升序
+    pub selection: Vec<usize>,
+    /// 框选的起点和当前位置
+    band: Option<(Pos2, Pos2)>,
+    drag_last: Pos2,
+    /// 最近复制或粘贴的箭头，用于计算粘贴偏移
+    clipboard: Vec<Arrow>,
+    paste_count: usize,
+    history: History,
+    /// 开始拖动时的箭头，松开后记入历史
+    move_before: Option<Vec<Arrow>>,
 }
 
 impl ArrowDrawer {
@@ -753,6 +170,7 @@ impl ArrowDrawer {
     pub fn start_drawing(&mut self, pos: Pos2) {
         self.state = DrawState::Drawing;
         self.current_start = Some(pos);
+        self.selection.clear();
     }
 
     /// 完成绘制
@@ -760,35 +178,139 @@ impl ArrowDrawer {
         if let Some(start) = self.current_start {
             // 只有拖拽了一定距离才创建箭头
             if (end - start).length() > 10.0 {
+                let before = self.arrows.clone();
                 let arrow = Arrow::new(start, end, config);
                 self.arrows.push(arrow);
+                self.history.record(before, &self.arrows);
             }
         }
         self.state = DrawState::Idle;
         self.current_start = None;
     }
 
+    /// 换成另一组箭头，清空选择和历史
+    pub fn load(&mut self, arrows: Vec<Arrow>) {
+        self.arrows = arrows;
+        self.selection.clear();
+        self.state = DrawState::Idle;
+        self.current_start = None;
+        self.band = None;
+        self.history = History::default();
+        self.move_before = None;
+    }
+
+    /// 清空所有箭头
+    pub fn clear(&mut self) {
+        self.load(Vec::new());
+    }
+
     /// 取消绘制
     pub fn cancel(&mut self) {
         self.state = DrawState::Idle;
         self.current_start = None;
+        self.band = None;
     }
 
-    /// 尝试选中箭头
-    pub fn try_select(&mut self, pos: Pos2) -> bool {
-        for (i, arrow) in self.arrows.iter().enumerate().rev() {
-            if arrow.hit_test(pos, 5.0) {
-                self.state = DrawState::Selected(i);
-                return true;
+    /// 清空选择
+    pub fn deselect(&mut self) {
+        self.selection.clear();
+        if self.state == DrawState::Selected {
+            self.state = DrawState::Idle;
+        }
+    }
+
+    /// 选中的唯一一个箭头
+    pub fn single_selected(&self) -> Option<usize> {
+        match self.selection[..] {
+            [idx] => Some(idx),
+            _ => None,
+        }
+    }
+
+    pub fn is_selected(&self, idx: usize) -> bool {
+        self.selection.binary_search(&idx).is_ok()
+    }
+
+    /// 位置上最上层的箭头
+    fn hit(&self, pos: Pos2) -> Option<usize> {
+        self.arrows
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, arrow)| arrow.hit_test(pos, 5.0))
+            .map(|(i, _)| i)
+    }
+
+    /// 空闲或选中时按下鼠标
+    ///
+    /// 点中箭头时选中并开始拖动，按住 Shift 点击增减选择；按住 Ctrl 在空白处拖动框选，
+    /// 否则开始绘制新箭头，Shift 留给绘制时锁定角度。
+    pub fn press(&mut self, pos: Pos2, snapped: Pos2, modifiers: egui::Modifiers) {
+        let shift = modifiers.shift;
+        if !shift && let Some(hit) = self.hit_endpoint(pos) {
+            // 点击选中箭头的端点或本身
+            self.start_move(pos, hit);
+        } else if self.try_select(pos, shift) {
+            // 选中后可以直接拖动
+            if !shift && let Some(hit) = self.hit_endpoint(pos) {
+                self.start_move(pos, hit);
+            }
+        } else if modifiers.command {
+            self.start_band(pos);
+        } else {
+            self.start_drawing(snapped);
+        }
+    }
+
+    /// 尝试选中箭头；`additive`（Shift）时切换该箭头是否在选择中
+    pub fn try_select(&mut self, pos: Pos2, additive: bool) -> bool {
+        let Some(idx) = self.hit(pos) else {
+            if !additive {
+                self.deselect();
             }
+            return false;
+        };
+        match self.selection.binary_search(&idx) {
+            Ok(i) if additive => {
+                self.selection.remove(i);
+            }
+            Ok(_) => {}
+            Err(i) if additive => self.selection.insert(i, idx),
+            Err(_) => self.selection = vec![idx],
+        }
+        self.state = if self.selection.is_empty() {
+            DrawState::Idle
+        } else {
+            DrawState::Selected
+        };
+        true
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = (0..self.arrows.len()).collect();
+        if !self.selection.is_empty() {
+            self.state = DrawState::Selected;
         }
-        self.state = DrawState::Idle;
-        false
     }
 
-    /// 检测是否点击了选中箭头的端点
+    /// 选中指定的箭头
+    fn select(&mut self, selection: Vec<usize>) {
+        self.selection = selection;
+        self.state = if self.selection.is_empty() {
+            DrawState::Idle
+        } else {
+            DrawState::Selected
+        };
+    }
+
+    /// 检测是否点击了选中箭头的端点或箭头本身
+    ///
+    /// 端点和控制点只在单选时可拖动，多选时只能整体移动。
     pub fn hit_endpoint(&self, pos: Pos2) -> Option<DrawState> {
-        if let DrawState::Selected(idx) = self.state
+        if self.state != DrawState::Selected {
+            return None;
+        }
+        if let Some(idx) = self.single_selected()
             && let Some(arrow) = self.arrows.get(idx)
         {
             let tolerance = arrow.stroke_width + 8.0;
@@ -798,20 +320,21 @@ impl ArrowDrawer {
             if (pos - arrow.end).length() < tolerance {
                 return Some(DrawState::MovingEnd(idx));
             }
-            if arrow.hit_test(pos, 5.0) {
-                return Some(DrawState::MovingWhole(idx));
+            for (i, &handle) in arrow.path.handles().iter().enumerate() {
+                if (pos - handle).length() < tolerance {
+                    return Some(DrawState::MovingHandle(idx, i));
+                }
             }
         }
-        None
+        self.hit(pos)
+            .filter(|&idx| self.is_selected(idx))
+            .map(|_| DrawState::MovingSelection)
     }
 
     /// 开始移动
     pub fn start_move(&mut self, pos: Pos2, new_state: DrawState) {
-        if let DrawState::MovingWhole(idx) = new_state
-            && let Some(arrow) = self.arrows.get(idx)
-        {
-            self.drag_offset = pos - arrow.start;
-        }
+        self.drag_last = pos;
+        self.move_before = Some(self.arrows.clone());
         self.state = new_state;
     }
 
@@ -828,35 +351,319 @@ impl ArrowDrawer {
                     arrow.end = pos;
                 }
             }
-            DrawState::MovingWhole(idx) => {
-                if let Some(arrow) = self.arrows.get_mut(idx) {
-                    let new_start = pos - self.drag_offset;
-                    let delta = new_start - arrow.start;
-                    arrow.start = new_start;
-                    arrow.end += delta;
+            DrawState::MovingHandle(idx, i) => {
+                if let Some(handle) = self
+                    .arrows
+                    .get_mut(idx)
+                    .and_then(|arrow| arrow.path.handles_mut().get_mut(i))
+                {
+                    *handle = pos;
                 }
             }
+            DrawState::MovingSelection => {
+                let delta = pos - self.drag_last;
+                self.translate_selected(delta);
+                self.drag_last = pos;
+            }
             _ => {}
         }
     }
 
     /// 结束移动
     pub fn finish_move(&mut self) {
-        if let DrawState::MovingStart(idx)
-        | DrawState::MovingEnd(idx)
-        | DrawState::MovingWhole(idx) = self.state
+        if matches!(
+            self.state,
+            DrawState::MovingStart(_)
+                | DrawState::MovingEnd(_)
+                | DrawState::MovingHandle(..)
+                | DrawState::MovingSelection
+        ) {
+            self.state = DrawState::Selected;
+        }
+        if let Some(before) = self.move_before.take() {
+            self.history.record(before, &self.arrows);
+        }
+    }
+
+    /// 平移所有选中的箭头
+    fn translate_selected(&mut self, delta: Vec2) {
+        for &idx in &self.selection {
+            if let Some(arrow) = self.arrows.get_mut(idx) {
+                arrow.translate(delta);
+            }
+        }
+    }
+
+    /// 开始框选
+    pub fn start_band(&mut self, pos: Pos2) {
+        self.band = Some((pos, pos));
+        self.state = DrawState::Selecting;
+    }
+
+    pub fn update_band(&mut self, pos: Pos2) {
+        if let Some((_, end)) = &mut self.band {
+            *end = pos;
+        }
+    }
+
+    /// 当前框选的矩形
+    pub fn band_rect(&self) -> Option<Rect> {
+        self.band.map(|(a, b)| Rect::from_two_pos(a, b))
+    }
+
+    /// 结束框选：完全落在框内的箭头加入选择
+    pub fn finish_band(&mut self) {
+        let mut selection = std::mem::take(&mut self.selection);
+        if let Some(band) = self.band_rect() {
+            for (i, arrow) in self.arrows.iter().enumerate() {
+                if band.contains_rect(Rect::from_points(&arrow.centerline())) {
+                    selection.push(i);
+                }
+            }
+        }
+        selection.sort_unstable();
+        selection.dedup();
+        self.band = None;
+        self.select(selection);
+    }
+
+    /// 双击折线箭头：在拐点上删除拐点，在线段上插入拐点
+    pub fn toggle_waypoint(&mut self, pos: Pos2) {
+        if self.state != DrawState::Selected {
+            return;
+        }
+        let Some(idx) = self.single_selected() else {
+            return;
+        };
+        if !self
+            .arrows
+            .get(idx)
+            .is_some_and(|arrow| matches!(arrow.path, ArrowPath::Polyline { .. }))
         {
-            self.state = DrawState::Selected(idx);
+            return;
         }
+
+        let before = self.arrows.clone();
+        let arrow = &mut self.arrows[idx];
+        let tolerance = arrow.stroke_width + 8.0;
+        let handles = arrow.path.handles();
+        if let Some(i) = handles.iter().position(|&p| (pos - p).length() < tolerance) {
+            arrow.path.remove_point(i);
+        } else if arrow.hit_test(pos, 5.0) {
+            // 中心线第 i 段之前有 i 个拐点
+            let (_, segment) = path::distance_to(&arrow.centerline(), pos);
+            arrow.path.insert_point(segment, pos);
+        }
+        self.history.record(before, &self.arrows);
     }
 
     /// 删除选中的箭头
     pub fn delete_selected(&mut self) {
-        if let DrawState::Selected(idx) = self.state {
+        let before = self.arrows.clone();
+        for &idx in self.selection.iter().rev() {
             if idx < self.arrows.len() {
                 self.arrows.remove(idx);
             }
-            self.state = DrawState::Idle;
+        }
+        self.selection.clear();
+        self.state = DrawState::Idle;
+        self.history.record(before, &self.arrows);
+    }
+
+    /// 把面板配置的变化应用到选中的箭头，只修改 `before` 和 `after` 不同的项
+    ///
+    /// 连续的修改（如拖动颜色滑块）在 `seal_history` 之前合并为一步。
+    pub fn restyle_selected(&mut self, before: &ArrowConfig, after: &ArrowConfig) {
+        let snapshot = self.arrows.clone();
+        for &idx in &self.selection {
+            let Some(arrow) = self.arrows.get_mut(idx) else {
+                continue;
+            };
+            if before.arrow_type != after.arrow_type {
+                arrow.arrow_type = after.arrow_type;
+            }
+            if before.line_style != after.line_style {
+                arrow.line_style = after.line_style;
+            }
+            if before.color != after.color {
+                arrow.color = after.color;
+            }
+            if before.stroke_width != after.stroke_width {
+                arrow.stroke_width = after.stroke_width;
+            }
+            if before.effects != after.effects {
+                arrow.effects = after.effects;
+            }
+            if before.path_kind != after.path_kind {
+                arrow.path = ArrowPath::new(after.path_kind, arrow.start, arrow.end);
+            }
+        }
+        self.history.record_merged(snapshot, &self.arrows);
+    }
+
+    /// 第一个选中箭头的样式，面板在有选中时显示它
+    pub fn selected_style(&self) -> Option<ArrowConfig> {
+        self.selection
+            .first()
+            .and_then(|&idx| self.arrows.get(idx))
+            .map(Arrow::style)
+    }
+
+    /// 把 `before` 到当前箭头的修改记为一步
+    pub(super) fn record_history(&mut self, before: Vec<Arrow>) {
+        self.history.record(before, &self.arrows);
+    }
+
+    /// 结束当前的合并修改，之后的修改另起一步
+    pub fn seal_history(&mut self) {
+        self.history.seal();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// 撤销上一步修改，只在没有进行中的操作时生效
+    pub fn undo(&mut self) {
+        if matches!(self.state, DrawState::Idle | DrawState::Selected)
+            && self.history.undo(&mut self.arrows)
+        {
+            self.clamp_selection();
+        }
+    }
+
+    /// 重做
+    pub fn redo(&mut self) {
+        if matches!(self.state, DrawState::Idle | DrawState::Selected)
+            && self.history.redo(&mut self.arrows)
+        {
+            self.clamp_selection();
+        }
+    }
+
+    /// 箭头数量变化后去掉失效的选中项
+    fn clamp_selection(&mut self) {
+        let len = self.arrows.len();
+        self.selection.retain(|&idx| idx < len);
+        let selection = std::mem::take(&mut self.selection);
+        self.select(selection);
+    }
+
+    /// 复制选中的箭头，返回写入系统剪贴板的文本
+    pub fn copy_selected(&mut self) -> Option<String> {
+        if self.selection.is_empty() {
+            return None;
+        }
+        self.clipboard = self
+            .selection
+            .iter()
+            .filter_map(|&idx| self.arrows.get(idx).cloned())
+            .collect();
+        self.paste_count = 0;
+        serde_json::to_string(&ClipboardArrows {
+            pxshot_arrows: self.clipboard.clone(),
+        })
+        .ok()
+    }
+
+    /// 粘贴系统剪贴板中的标注，`text` 不是复制的标注时忽略
+    pub fn paste(&mut self, text: &str) {
+        let Ok(copied) = serde_json::from_str::<ClipboardArrows>(text) else {
+            return;
+        };
+        if copied.pxshot_arrows != self.clipboard {
+            self.clipboard = copied.pxshot_arrows;
+            self.paste_count = 0;
+        }
+        // 同一份内容多次粘贴时依次错开，避免完全重叠
+        self.paste_count += 1;
+        let delta = Vec2::splat(PASTE_OFFSET * self.paste_count as f32);
+        let pasted = self.clipboard.clone();
+        self.insert_copies(pasted, delta);
+    }
+
+    /// 原地复制一份选中的箭头并选中副本
+    pub fn duplicate_selected(&mut self) {
+        let copies = self
+            .selection
+            .iter()
+            .filter_map(|&idx| self.arrows.get(idx).cloned())
+            .collect();
+        self.insert_copies(copies, Vec2::splat(PASTE_OFFSET));
+    }
+
+    fn insert_copies(&mut self, copies: Vec<Arrow>, delta: Vec2) {
+        let before = self.arrows.clone();
+        let first = self.arrows.len();
+        for mut arrow in copies {
+            arrow.translate(delta);
+            self.arrows.push(arrow);
+        }
+        self.select((first..self.arrows.len()).collect());
+        self.history.record(before, &self.arrows);
+    }
+
+    /// 是否正在拖动某个端点或控制点（新建箭头的终点也算）
+    pub fn is_dragging_point(&self) -> bool {
+        matches!(
+            self.state,
+            DrawState::Drawing
+                | DrawState::MovingStart(_)
+                | DrawState::MovingEnd(_)
+                | DrawState::MovingHandle(..)
+        )
+    }
+
+    /// 吸附当前拖动的点：`bounds` 为选区，`shift` 时按 15° 吸附角度
+    ///
+    /// 不在拖动时也会吸附，用于新箭头的起点，此时没有角度吸附。
+    pub fn snap(&self, pos: Pos2, bounds: Rect, shift: bool) -> (Pos2, Vec<SnapHint>) {
+        // 正在编辑的箭头不参与吸附
+        let editing = match self.state {
+            DrawState::MovingStart(idx)
+            | DrawState::MovingEnd(idx)
+            | DrawState::MovingHandle(idx, _) => Some(idx),
+            _ => None,
+        };
+        let targets = SnapTargets {
+            bounds: Some(bounds),
+            points: self
+                .arrows
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != editing)
+                .flat_map(|(_, arrow)| [arrow.start, arrow.end])
+                .collect(),
+        };
+        targets.snap(pos, self.snap_anchor(), shift)
+    }
+
+    /// 角度吸附的中心：拖动点在路径上的前一个（或后一个）点
+    fn snap_anchor(&self) -> Option<Pos2> {
+        match self.state {
+            DrawState::Drawing => self.current_start,
+            DrawState::MovingStart(idx) => self.arrows.get(idx).map(|arrow| match &arrow.path {
+                ArrowPath::Polyline { points } => points.first().copied().unwrap_or(arrow.end),
+                _ => arrow.end,
+            }),
+            DrawState::MovingEnd(idx) => self.arrows.get(idx).map(|arrow| match &arrow.path {
+                ArrowPath::Polyline { points } => points.last().copied().unwrap_or(arrow.start),
+                _ => arrow.start,
+            }),
+            DrawState::MovingHandle(idx, i) => match &self.arrows.get(idx)?.path {
+                // 折线拐点以前一个点为中心，得到水平/竖直的折线
+                ArrowPath::Polyline { points } => Some(if i == 0 {
+                    self.arrows[idx].start
+                } else {
+                    points[i - 1]
+                }),
+                _ => None,
+            },
+            _ => None,
         }
     }
 
@@ -867,23 +674,68 @@ impl ArrowDrawer {
         }
     }
 
-    /// 绘制选中状态的端点手柄
+    /// 绘制选中状态：单选时画端点手柄，多选时画每个箭头的虚线框
     pub fn draw_selection_handles(&self, painter: &egui::Painter) {
-        if let DrawState::Selected(idx)
-        | DrawState::MovingStart(idx)
-        | DrawState::MovingEnd(idx)
-        | DrawState::MovingWhole(idx) = self.state
-            && let Some(arrow) = self.arrows.get(idx)
-        {
-            let handle_size = 8.0;
-            let handle_color = Color32::from_rgb(0, 122, 255);
-            // 起点手柄
-            painter.circle_filled(arrow.start, handle_size, Color32::WHITE);
-            painter.circle_stroke(arrow.start, handle_size, Stroke::new(2.0, handle_color));
-            // 终点手柄
-            painter.circle_filled(arrow.end, handle_size, Color32::WHITE);
-            painter.circle_stroke(arrow.end, handle_size, Stroke::new(2.0, handle_color));
+        let handle_size = 8.0;
+        let handle_color = Color32::from_rgb(0, 122, 255);
+
+        if let Some(band) = self.band_rect() {
+            painter.rect_filled(band, 0.0, handle_color.gamma_multiply(0.15));
+            painter.rect_stroke(
+                band,
+                0.0,
+                Stroke::new(1.0, handle_color),
+                StrokeKind::Inside,
+            );
+        }
+
+        let Some(idx) = self.single_selected() else {
+            for &idx in &self.selection {
+                if let Some(arrow) = self.arrows.get(idx) {
+                    let rect = arrow.bounding_rect();
+                    let corners = [
+                        rect.left_top(),
+                        rect.right_top(),
+                        rect.right_bottom(),
+                        rect.left_bottom(),
+                        rect.left_top(),
+                    ];
+                    painter.add(egui::Shape::dashed_line(
+                        &corners,
+                        Stroke::new(1.0, handle_color),
+                        4.0,
+                        3.0,
+                    ));
+                }
+            }
+            return;
+        };
+        let Some(arrow) = self.arrows.get(idx) else {
+            return;
+        };
+        // 曲线的控制线
+        if let ArrowPath::Curve { control } = arrow.path {
+            let guide = Stroke::new(1.0, handle_color.gamma_multiply(0.7));
+            painter.line_segment([arrow.start, control], guide);
+            painter.line_segment([control, arrow.end], guide);
+        }
+        // 控制点手柄：小方块
+        for &handle in arrow.path.handles() {
+            let rect = Rect::from_center_size(handle, Vec2::splat(handle_size * 1.5));
+            painter.rect_filled(rect, 2.0, Color32::WHITE);
+            painter.rect_stroke(
+                rect,
+                2.0,
+                Stroke::new(2.0, handle_color),
+                StrokeKind::Inside,
+            );
         }
+        // 起点手柄
+        painter.circle_filled(arrow.start, handle_size, Color32::WHITE);
+        painter.circle_stroke(arrow.start, handle_size, Stroke::new(2.0, handle_color));
+        // 终点手柄
+        painter.circle_filled(arrow.end, handle_size, Color32::WHITE);
+        painter.circle_stroke(arrow.end, handle_size, Stroke::new(2.0, handle_color));
     }
 
     /// 绘制正在创建的箭头预览
@@ -895,9 +747,16 @@ impl ArrowDrawer {
     }
 
     /// 将所有箭头渲染到图像上
-    pub fn render_all_to_image(&self, img: &mut RgbaImage, offset_x: f32, offset_y: f32, ppp: f32) {
+    pub fn render_all_to_image(
+        &self,
+        img: &mut RgbaImage,
+        offset_x: f32,
+        offset_y: f32,
+        ppp: f32,
+        rasterizer: Rasterizer,
+    ) {
         for arrow in &self.arrows {
-            arrow.render_to_image(img, offset_x, offset_y, ppp);
+            arrow.render_to_image(img, offset_x, offset_y, ppp, rasterizer);
         }
     }
 
@@ -0,0 +1,124 @@
+use eframe::egui::{self, Color32, Pos2, Vec2};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use super::geometry::Geometry;
+use super::raster::{Rasterizer, basic};
+
+/// 描边：在标注外围画一圈对比色，避免与背景同色时看不清
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Outline {
+    pub enabled: bool,
+    pub color: Color32,
+    pub width: f32,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: Color32::WHITE,
+            width: 2.0,
+        }
+    }
+}
+
+/// 柔和投影
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shadow {
+    pub enabled: bool,
+    pub offset: Vec2,
+    pub blur: f32,    // 模糊半径
+    pub opacity: f32, // 0..1
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offset: Vec2::new(3.0, 3.0),
+            blur: 6.0,
+            opacity: 0.5,
+        }
+    }
+}
+
+impl Shadow {
+    pub fn color(&self) -> Color32 {
+        Color32::from_black_alpha((self.opacity.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// 高斯模糊的 σ，模糊半径约为 2σ
+    fn sigma(&self) -> f32 {
+        self.blur.max(0.0) / 2.0
+    }
+}
+
+/// 标注的附加效果，长度单位与所属箭头的坐标相同
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Effects {
+    pub outline: Outline,
+    pub shadow: Shadow,
+}
+
+impl Effects {
+    /// 长度同比缩放
+    pub fn scaled(&self, scale: f32) -> Self {
+        let mut effects = *self;
+        effects.outline.width *= scale;
+        effects.shadow.offset *= scale;
+        effects.shadow.blur *= scale;
+        effects
+    }
+
+    /// 描边后的外形，投影按它来计算
+    fn silhouette(&self, geometry: &Geometry) -> Geometry {
+        if self.outline.enabled {
+            geometry.dilated(self.outline.width)
+        } else {
+            geometry.clone()
+        }
+    }
+
+    fn shadow_geometry(&self, geometry: &Geometry) -> Geometry {
+        self.silhouette(geometry)
+            .transformed(Pos2::ZERO - self.shadow.offset, 1.0)
+    }
+
+    /// 在屏幕上绘制效果，需要在标注本身之前调用
+    pub fn paint(&self, geometry: &Geometry, painter: &egui::Painter) {
+        if self.shadow.enabled {
+            self.shadow_geometry(geometry).paint_layer(
+                painter,
+                self.shadow.color(),
+                self.shadow.sigma(),
+            );
+        }
+        if self.outline.enabled {
+            geometry
+                .dilated(self.outline.width)
+                .paint(painter, self.outline.color);
+        }
+    }
+
+    /// 把效果渲染到图像上，`geometry` 和效果的单位都是像素
+    pub fn render(&self, geometry: &Geometry, img: &mut RgbaImage, rasterizer: Rasterizer) {
+        if self.shadow.enabled
+            && let Some((mask, origin)) =
+                basic::layer(&self.shadow_geometry(geometry), self.shadow.sigma())
+        {
+            // 投影是模糊的，两种后端画出来没有差别，统一走基础实现
+            basic::composite(&mask, img, origin, self.shadow.color());
+        }
+        if self.outline.enabled {
+            rasterizer.rasterize(
+                &geometry.dilated(self.outline.width),
+                img,
+                self.outline.color,
+            );
+        }
+    }
+}
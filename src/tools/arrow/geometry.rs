@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use eframe::egui::{self, Color32, ColorImage, Pos2, Rect, Stroke, TextureOptions, Vec2};
+
+use super::drawable::Arrow;
+use super::path::{cumulative_lengths, point_at, sub_path};
+use super::raster::basic;
+use super::types::{ArrowType, LineStyle};
+
+/// 圆头线段；`from == to` 时是一个圆点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub from: Pos2,
+    pub to: Pos2,
+    pub width: f32,
+}
+
+impl Segment {
+    /// 胶囊形轮廓（凸多边形），两端为半圆
+    pub fn outline(&self) -> Vec<Pos2> {
+        let radius = self.width / 2.0;
+        let steps = arc_steps(radius);
+        let delta = self.to - self.from;
+
+        if delta.length_sq() == 0.0 {
+            return (0..steps * 2)
+                .map(|i| self.from + Vec2::angled(PI * i as f32 / steps as f32) * radius)
+                .collect();
+        }
+
+        let angle = delta.angle();
+        let arc = |center: Pos2, start: f32| {
+            (0..=steps)
+                .map(move |i| center + Vec2::angled(start + PI * i as f32 / steps as f32) * radius)
+        };
+        arc(self.to, angle - FRAC_PI_2)
+            .chain(arc(self.from, angle + FRAC_PI_2))
+            .collect()
+    }
+}
+
+/// 半圆细分的段数，半径越大越细
+fn arc_steps(radius: f32) -> usize {
+    ((radius * 2.0).ceil() as usize).clamp(4, 32)
+}
+
+/// 标注的几何形状：填充的凸多边形 + 圆头线段
+///
+/// 屏幕绘制和导出渲染都从这里取形状，两边的虚线长度、箭头尺寸因此始终一致。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Geometry {
+    pub polygons: Vec<Vec<Pos2>>,
+    pub segments: Vec<Segment>,
+}
+
+/// 箭头沿路径的骨架：主体中心线 + 箭头的位置和方向
+///
+/// 屏幕绘制、位图导出和 SVG 导出都由它生成形状，曲线和折线上的箭头因此一致。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skeleton {
+    /// 主体中心线，已为箭头让出位置
+    pub body: Vec<Pos2>,
+    /// 箭头尖端和指向，终点的在前
+    pub heads: Vec<(Pos2, Vec2)>,
+    pub head_size: f32,
+}
+
+impl Skeleton {
+    /// 路径长度为零时返回 `None`
+    pub fn of(arrow: &Arrow) -> Option<Self> {
+        let points = arrow.centerline();
+        let total = *cumulative_lengths(&points).last()?;
+        if total == 0.0 {
+            return None;
+        }
+
+        let head_size = arrow.stroke_width * 3.0;
+        let start = points[0];
+        let end = points[points.len() - 1];
+        // 箭头方向取尖端到箭头底部中心的连线，弯曲处也能贴合路径
+        let toward = |tip: Pos2, back: Pos2| {
+            let dir = (tip - back).normalized();
+            if dir.is_finite() { dir } else { Vec2::X }
+        };
+        let back = point_at(&points, total - head_size);
+        let mut heads = vec![(end, toward(end, back))];
+
+        let body = if arrow.arrow_type == ArrowType::Double {
+            let front = point_at(&points, head_size);
+            heads.push((start, toward(start, front)));
+            sub_path(&points, head_size, total - head_size)
+        } else {
+            sub_path(&points, 0.0, total - head_size)
+        };
+
+        Some(Self {
+            body,
+            heads,
+            head_size,
+        })
+    }
+
+    /// 以 `tip` 为尖端、底边宽 `2 * spread * head_size` 的三角形
+    pub fn head(&self, tip: Pos2, dir: Vec2, spread: f32) -> [Pos2; 3] {
+        let perp = Vec2::new(-dir.y, dir.x);
+        let back = tip - dir * self.head_size;
+        [
+            tip,
+            back + perp * self.head_size * spread,
+            back - perp * self.head_size * spread,
+        ]
+    }
+
+    /// 主体两侧的轮廓，半宽沿路径从 `from` 线性变化到 `to`
+    ///
+    /// 返回（左侧，右侧），两侧都从起点排到终点。
+    pub fn sides(&self, from: f32, to: f32) -> (Vec<Pos2>, Vec<Pos2>) {
+        let lengths = cumulative_lengths(&self.body);
+        let total = lengths.last().copied().unwrap_or_default();
+        let normals = vertex_normals(&self.body);
+
+        let mut left = Vec::with_capacity(self.body.len());
+        let mut right = Vec::with_capacity(self.body.len());
+        for ((&p, &length), &normal) in self.body.iter().zip(&lengths).zip(&normals) {
+            let t = if total > 0.0 { length / total } else { 0.0 };
+            let half = from + (to - from) * t;
+            left.push(p + normal * half);
+            right.push(p - normal * half);
+        }
+        (left, right)
+    }
+}
+
+/// 折线各顶点的法线，拐角处按斜接长度放大，保证两侧等宽
+fn vertex_normals(points: &[Pos2]) -> Vec<Vec2> {
+    // 每一段的法线，零长度的段沿用相邻段
+    let mut segment_normals: Vec<Option<Vec2>> = points
+        .windows(2)
+        .map(|pair| {
+            let dir = (pair[1] - pair[0]).normalized();
+            dir.is_finite().then(|| Vec2::new(-dir.y, dir.x))
+        })
+        .collect();
+    for i in 1..segment_normals.len() {
+        if segment_normals[i].is_none() {
+            segment_normals[i] = segment_normals[i - 1];
+        }
+    }
+    for i in (0..segment_normals.len().saturating_sub(1)).rev() {
+        if segment_normals[i].is_none() {
+            segment_normals[i] = segment_normals[i + 1];
+        }
+    }
+
+    (0..points.len())
+        .map(|i| {
+            let before = i.checked_sub(1).and_then(|j| segment_normals[j]);
+            let after = segment_normals.get(i).copied().flatten();
+            match (before, after) {
+                (Some(a), Some(b)) => {
+                    let mid = (a + b).normalized();
+                    if !mid.is_finite() {
+                        return a;
+                    }
+                    // 斜接放大，限制在两倍以内避免尖角过长
+                    mid / mid.dot(a).max(0.5)
+                }
+                (Some(n), None) | (None, Some(n)) => n,
+                (None, None) => Vec2::ZERO,
+            }
+        })
+        .collect()
+}
+
+impl Geometry {
+    /// 箭头的几何形状，坐标与箭头相同
+    pub fn of(arrow: &Arrow) -> Self {
+        let mut geometry = Self::default();
+        let Some(skeleton) = Skeleton::of(arrow) else {
+            return geometry;
+        };
+
+        let width = arrow.stroke_width;
+        let head_size = skeleton.head_size;
+        let (tip, dir) = skeleton.heads[0];
+
+        match arrow.arrow_type {
+            ArrowType::Single | ArrowType::Double => {
+                // 直线/双向箭头：线条到三角形底部 + 填充三角形
+                geometry.line(&skeleton.body, width, arrow.line_style);
+                for &(tip, dir) in &skeleton.heads {
+                    geometry
+                        .polygons
+                        .push(skeleton.head(tip, dir, 0.35).to_vec());
+                }
+            }
+            ArrowType::Hollow => {
+                // 空心箭头：从细到粗的轮廓 + 空心三角形，只画外轮廓
+                let (left, right) = skeleton.sides(width * 0.15, head_size * 0.5);
+                let [_, head_left, head_right] = skeleton.head(tip, dir, 0.5);
+                let outline: Vec<Pos2> = left
+                    .into_iter()
+                    .chain([head_left, tip, head_right])
+                    .chain(right.into_iter().rev())
+                    .collect();
+                for (i, &from) in outline.iter().enumerate() {
+                    let to = outline[(i + 1) % outline.len()];
+                    geometry.segments.push(Segment {
+                        from,
+                        to,
+                        width: width * 0.4,
+                    });
+                }
+            }
+            ArrowType::Filled => {
+                // 实心箭头：渐变粗线条 + 实心三角形，线条按路径分成凸四边形
+                let (left, right) = skeleton.sides(width * 0.15, head_size * 0.5);
+                for i in 0..left.len() - 1 {
+                    geometry
+                        .polygons
+                        .push(vec![left[i], left[i + 1], right[i + 1], right[i]]);
+                }
+                geometry
+                    .polygons
+                    .push(skeleton.head(tip, dir, 0.5).to_vec());
+            }
+        }
+        geometry
+    }
+
+    /// 按线段类型把折线拆成圆头线段，虚线沿路径连续
+    fn line(&mut self, points: &[Pos2], width: f32, style: LineStyle) {
+        let pattern = style
+            .dash_pattern(width)
+            .filter(|pattern| pattern.iter().sum::<f32>() > 0.0);
+        let Some(pattern) = pattern else {
+            self.polyline(points, width);
+            return;
+        };
+
+        let total = cumulative_lengths(points)
+            .last()
+            .copied()
+            .unwrap_or_default();
+        let mut pos = 0.0;
+        for pair in pattern.chunks(2).cycle() {
+            if pos > total {
+                break;
+            }
+            let end = (pos + pair[0]).min(total);
+            self.polyline(&sub_path(points, pos, end), width);
+            pos += pair[0] + pair.get(1).copied().unwrap_or_default();
+        }
+    }
+
+    fn polyline(&mut self, points: &[Pos2], width: f32) {
+        for pair in points.windows(2) {
+            self.segments.push(Segment {
+                from: pair[0],
+                to: pair[1],
+                width,
+            });
+        }
+    }
+
+    /// 坐标变换：`p' = (p - origin) * scale`，线宽同比缩放
+    pub fn transformed(&self, origin: Pos2, scale: f32) -> Self {
+        let map = |p: Pos2| Pos2::ZERO + (p - origin) * scale;
+        Self {
+            polygons: self
+                .polygons
+                .iter()
+                .map(|polygon| polygon.iter().copied().map(map).collect())
+                .collect(),
+            segments: self
+                .segments
+                .iter()
+                .map(|s| Segment {
+                    from: map(s.from),
+                    to: map(s.to),
+                    width: s.width * scale,
+                })
+                .collect(),
+        }
+    }
+
+    /// 向外扩张 `amount`：线段加粗，多边形的每条边变成圆头线段，用于描边
+    pub fn dilated(&self, amount: f32) -> Self {
+        let mut geometry = self.clone();
+        for segment in &mut geometry.segments {
+            segment.width += amount * 2.0;
+        }
+        for polygon in &self.polygons {
+            for (i, &from) in polygon.iter().enumerate() {
+                geometry.segments.push(Segment {
+                    from,
+                    to: polygon[(i + 1) % polygon.len()],
+                    width: amount * 2.0,
+                });
+            }
+        }
+        geometry
+    }
+
+    /// 包围所有图元的矩形，包含线宽
+    pub fn bounding_rect(&self) -> Rect {
+        let mut rect = Rect::NOTHING;
+        for polygon in &self.polygons {
+            for &p in polygon {
+                rect.extend_with(p);
+            }
+        }
+        for s in &self.segments {
+            let half = Vec2::splat(s.width / 2.0);
+            rect = rect
+                .union(Rect::from_min_max(s.from - half, s.from + half))
+                .union(Rect::from_min_max(s.to - half, s.to + half));
+        }
+        rect
+    }
+
+    /// 用 egui 绘制
+    ///
+    /// 不透明颜色直接画多边形，线段在下、多边形在上；半透明颜色若逐个图元绘制，
+    /// 重叠处会混合两次，因此先用导出时相同的覆盖率图画成一张纹理再整体绘制。
+    pub fn paint(&self, painter: &egui::Painter, color: Color32) {
+        if color.a() < 255 {
+            self.paint_layer(painter, color, 0.0);
+            return;
+        }
+        for segment in &self.segments {
+            painter.add(egui::Shape::convex_polygon(
+                segment.outline(),
+                color,
+                Stroke::NONE,
+            ));
+        }
+        for polygon in &self.polygons {
+            painter.add(egui::Shape::convex_polygon(
+                polygon.clone(),
+                color,
+                Stroke::NONE,
+            ));
+        }
+    }
+
+    /// 作为一层纹理绘制，纹理像素与屏幕像素对齐
+    ///
+    /// 覆盖率图与导出时的基础实现相同，`blur` 为高斯模糊的 σ（逻辑坐标）。
+    /// 纹理只保存覆盖率，颜色在绘制时作为染色，形状、模糊和缩放都不变时复用上一帧的纹理，
+    /// 调整投影的颜色和透明度不需要重新模糊。
+    pub fn paint_layer(&self, painter: &egui::Painter, color: Color32, blur: f32) {
+        if color.a() == 0 {
+            return;
+        }
+        let ctx = painter.ctx();
+        let ppp = ctx.pixels_per_point();
+        let key = self.layer_key(blur, ppp);
+        let layer = match LayerCache::get(ctx, key) {
+            Some(layer) => layer,
+            None => {
+                let Some(layer) = self.rasterize_layer(ctx, blur, ppp) else {
+                    return;
+                };
+                LayerCache::insert(ctx, key, layer.clone());
+                layer
+            }
+        };
+        painter.image(
+            layer.texture.id(),
+            layer.rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            color,
+        );
+    }
+
+    /// 把覆盖率图画成白色纹理
+    fn rasterize_layer(&self, ctx: &egui::Context, blur: f32, ppp: f32) -> Option<CachedLayer> {
+        let (mask, [x, y]) = basic::layer(&self.transformed(Pos2::ZERO, ppp), blur * ppp)?;
+        let pixels = mask
+            .pixels()
+            .map(|c| Color32::from_white_alpha(c[0]))
+            .collect();
+        let size = [mask.width() as usize, mask.height() as usize];
+        let image = ColorImage::new(size, pixels);
+        let texture = ctx.load_texture("annotation-layer", image, TextureOptions::NEAREST);
+        let min = Pos2::new(x as f32, y as f32) / ppp;
+        let size = Vec2::new(size[0] as f32, size[1] as f32) / ppp;
+        Some(CachedLayer {
+            texture,
+            rect: Rect::from_min_size(min, size),
+            pass: ctx.cumulative_pass_nr(),
+        })
+    }
+
+    /// 纹理缓存的键，包含决定纹理内容的全部参数
+    fn layer_key(&self, blur: f32, ppp: f32) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        blur.to_bits().hash(&mut hasher);
+        ppp.to_bits().hash(&mut hasher);
+        for polygon in &self.polygons {
+            polygon.len().hash(&mut hasher);
+            for p in polygon {
+                [p.x.to_bits(), p.y.to_bits()].hash(&mut hasher);
+            }
+        }
+        for s in &self.segments {
+            [s.from.x, s.from.y, s.to.x, s.to.y, s.width]
+                .map(f32::to_bits)
+                .hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// 缓存的一层纹理
+#[derive(Clone)]
+struct CachedLayer {
+    texture: egui::TextureHandle,
+    rect: Rect,
+    /// 最后一次使用时的帧号
+    pass: u64,
+}
+
+/// 半透明标注和投影的纹理缓存，存在 egui 的临时数据里
+///
+/// 上一帧以后没有再用到的纹理会被释放。
+#[derive(Clone, Default)]
+struct LayerCache {
+    layers: HashMap<u64, CachedLayer>,
+}
+
+impl LayerCache {
+    fn id() -> egui::Id {
+        egui::Id::new("annotation-layer-cache")
+    }
+
+    fn get(ctx: &egui::Context, key: u64) -> Option<CachedLayer> {
+        let pass = ctx.cumulative_pass_nr();
+        ctx.data_mut(|data| {
+            let cache = data.get_temp_mut_or_default::<Self>(Self::id());
+            cache.layers.retain(|_, layer| layer.pass + 1 >= pass);
+            let layer = cache.layers.get_mut(&key)?;
+            layer.pass = pass;
+            Some(layer.clone())
+        })
+    }
+
+    fn insert(ctx: &egui::Context, key: u64, layer: CachedLayer) {
+        ctx.data_mut(|data| {
+            let cache = data.get_temp_mut_or_default::<Self>(Self::id());
+            cache.layers.insert(key, layer);
+        });
+    }
+}
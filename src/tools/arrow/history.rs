@@ -0,0 +1,72 @@
+use super::drawable::Arrow;
+
+/// 最多保留的撤销步数
+const LIMIT: usize = 100;
+
+/// 标注的撤销/重做历史，每一步保存修改前的全部箭头
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Vec<Arrow>>,
+    redo: Vec<Vec<Arrow>>,
+    /// 正在合并连续的修改（如拖动滑块改颜色）
+    merging: bool,
+}
+
+impl History {
+    /// 记录一步修改，没有变化时忽略
+    pub fn record(&mut self, before: Vec<Arrow>, after: &[Arrow]) {
+        if before != after {
+            self.push(before);
+            self.merging = false;
+        }
+    }
+
+    /// 记录一步可合并的修改，直到 `seal` 之前的连续修改只算一步
+    pub fn record_merged(&mut self, before: Vec<Arrow>, after: &[Arrow]) {
+        if before != after && !self.merging {
+            self.push(before);
+            self.merging = true;
+        }
+    }
+
+    /// 结束合并
+    pub fn seal(&mut self) {
+        self.merging = false;
+    }
+
+    fn push(&mut self, before: Vec<Arrow>) {
+        self.undo.push(before);
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// 撤销，成功时把 `arrows` 换成上一步的状态
+    pub fn undo(&mut self, arrows: &mut Vec<Arrow>) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(arrows, previous));
+        self.merging = false;
+        true
+    }
+
+    /// 重做
+    pub fn redo(&mut self, arrows: &mut Vec<Arrow>) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(arrows, next));
+        self.merging = false;
+        true
+    }
+}
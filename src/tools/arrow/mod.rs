@@ -1,9 +1,24 @@
+mod arrange;
 mod config;
 mod drawable;
+mod effects;
+mod geometry;
+mod history;
 mod panel;
+mod path;
+mod raster;
+mod snap;
+mod styles;
 mod types;
 
+pub use arrange::{Align, ArrangeAction, Distribute};
 pub use config::ArrowConfig;
 pub use drawable::{Arrow, ArrowDrawer, DrawState};
+pub use effects::{Effects, Outline, Shadow};
+pub use geometry::{Geometry, Segment, Skeleton};
 pub use panel::{ArrowToolPanel, PopupState};
+pub use path::{ArrowPath, PathKind};
+pub use raster::Rasterizer;
+pub use snap::{SnapHint, SnapTargets, draw_snap_hints};
+pub use styles::{ArrowSettings, RECENT_COLORS, StylePreset, color_to_hex, parse_hex_color};
 pub use types::{ArrowType, LineStyle, PRESET_COLORS, PRESET_SIZES};
@@ -1,6 +1,8 @@
 use super::config::ArrowConfig;
+use super::path::PathKind;
+use super::styles::{ArrowSettings, RECENT_COLORS, StylePreset, color_to_hex, parse_hex_color};
 use super::types::{ArrowType, LineStyle, PRESET_COLORS};
-use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, StrokeKind, Vec2};
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, StrokeKind, UiBuilder, Vec2};
 
 /// 弹出面板的状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -9,30 +11,117 @@ pub enum PopupState {
     None,
     ArrowType,   // 显示箭头类型选择
     LineStyle,   // 显示线段类型选择
+    PathKind,    // 显示路径形状选择
     ColorPicker, // 显示颜色/大小选择器
+    Effects,     // 显示描边/投影设置
+    Presets,     // 显示样式预设
 }
 
 /// Arrow 工具面板
 pub struct ArrowToolPanel {
+    /// 新箭头使用的样式
     pub config: ArrowConfig,
+    /// 选中箭头的样式，有值时面板显示并修改它而不是 `config`
+    pub editing: Option<ArrowConfig>,
+    /// 命名的样式预设
+    pub presets: Vec<StylePreset>,
+    /// 最近使用的颜色，最新的在前
+    pub recent_colors: Vec<Color32>,
     pub popup_state: PopupState,
     pub panel_rect: Option<Rect>,
+    pub popup_rect: Option<Rect>,
+    hex_input: String,
+    preset_name: String,
+    /// 拖动透明度滑块时的原始颜色，避免预乘带来的精度损失累积
+    opacity_base: Option<[u8; 3]>,
 }
 
 impl Default for ArrowToolPanel {
     fn default() -> Self {
         Self {
             config: ArrowConfig::default(),
+            editing: None,
+            presets: Vec::new(),
+            recent_colors: Vec::new(),
             popup_state: PopupState::None,
             panel_rect: None,
+            popup_rect: None,
+            hex_input: String::new(),
+            preset_name: String::new(),
+            opacity_base: None,
         }
     }
 }
 
 impl ArrowToolPanel {
+    /// 面板当前显示的样式
+    pub fn style(&self) -> &ArrowConfig {
+        self.editing.as_ref().unwrap_or(&self.config)
+    }
+
+    fn style_mut(&mut self) -> &mut ArrowConfig {
+        self.editing.as_mut().unwrap_or(&mut self.config)
+    }
+
+    /// 用保存的设置初始化面板
+    pub fn load_settings(&mut self, settings: &ArrowSettings) {
+        self.config = settings.style.clone();
+        self.presets = settings.presets.clone();
+        self.recent_colors = settings.recent_colors.clone();
+    }
+
+    /// 需要持久化的设置
+    pub fn settings(&self) -> ArrowSettings {
+        ArrowSettings {
+            style: self.config.clone(),
+            presets: self.presets.clone(),
+            recent_colors: self.recent_colors.clone(),
+        }
+    }
+
+    /// 把颜色放到最近使用的最前面，固定的预设颜色不记录
+    pub fn push_recent_color(&mut self, color: Color32) {
+        if PRESET_COLORS.iter().any(|(preset, _)| *preset == color) {
+            return;
+        }
+        self.recent_colors.retain(|c| *c != color);
+        self.recent_colors.insert(0, color);
+        self.recent_colors.truncate(RECENT_COLORS);
+    }
+
+    /// 把当前显示的样式保存为预设，同名时覆盖
+    pub fn save_preset(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let style = self.style().clone();
+        match self.presets.iter_mut().find(|p| p.name == name) {
+            Some(preset) => preset.style = style,
+            None => self.presets.push(StylePreset {
+                name: name.to_string(),
+                style,
+            }),
+        }
+    }
+
+    /// 应用预设：没有选中箭头时改变新箭头的样式，否则修改选中的箭头
+    pub fn apply_preset(&mut self, index: usize) {
+        if let Some(preset) = self.presets.get(index) {
+            *self.style_mut() = preset.style.clone();
+        }
+    }
+
+    /// 只改变颜色的 RGB，保留透明度
+    fn set_rgb(&mut self, rgb: Color32) {
+        let [r, g, b, _] = rgb.to_srgba_unmultiplied();
+        let alpha = self.style().color.a();
+        self.style_mut().color = Color32::from_rgba_unmultiplied(r, g, b, alpha);
+    }
+
     /// 计算面板位置
     pub fn calc_panel_rect(&self, toolbar_rect: Rect, screen: Rect) -> Rect {
-        let width = 400.0;
+        let width = 620.0;
         let height = 44.0;
 
         // 默认放工具栏下方
@@ -152,7 +241,7 @@ impl ArrowToolPanel {
             rect.min + Vec2::new(6.0, 6.0),
             Pos2::new(rect.max.x - 16.0, rect.max.y - 6.0),
         );
-        self.draw_arrow_icon(painter, icon_rect, self.config.arrow_type);
+        self.draw_arrow_icon(painter, icon_rect, self.style().arrow_type);
 
         // 绘制下拉三角形
         Self::draw_dropdown_triangle(painter, Pos2::new(rect.max.x - 8.0, rect.center().y));
@@ -184,7 +273,7 @@ impl ArrowToolPanel {
             rect.min + Vec2::new(6.0, 6.0),
             Pos2::new(rect.max.x - 16.0, rect.max.y - 6.0),
         );
-        self.draw_line_icon(painter, icon_rect, self.config.line_style);
+        self.draw_line_icon(painter, icon_rect, self.style().line_style);
 
         // 绘制下拉三角形
         Self::draw_dropdown_triangle(painter, Pos2::new(rect.max.x - 8.0, rect.center().y));
@@ -192,6 +281,71 @@ impl ArrowToolPanel {
         response.clicked()
     }
 
+    /// 绘制路径形状按钮
+    fn draw_path_kind_button(&self, ui: &mut egui::Ui, rect: Rect) -> bool {
+        let response = ui.allocate_rect(rect, Sense::click());
+        let painter = ui.painter();
+
+        let bg_color = if response.hovered() {
+            Color32::from_rgb(70, 70, 70)
+        } else {
+            Color32::from_rgb(60, 60, 60)
+        };
+
+        painter.rect_filled(rect, 4.0, bg_color);
+        painter.rect_stroke(
+            rect,
+            4.0,
+            Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+            StrokeKind::Inside,
+        );
+
+        let icon_rect = Rect::from_min_max(
+            rect.min + Vec2::new(6.0, 6.0),
+            Pos2::new(rect.max.x - 16.0, rect.max.y - 6.0),
+        );
+        self.draw_path_icon(painter, icon_rect, self.style().path_kind);
+
+        Self::draw_dropdown_triangle(painter, Pos2::new(rect.max.x - 8.0, rect.center().y));
+
+        response.clicked()
+    }
+
+    /// 绘制路径形状图标
+    fn draw_path_icon(&self, painter: &egui::Painter, rect: Rect, path_kind: PathKind) {
+        let stroke = Stroke::new(2.0, Color32::GRAY);
+        let left = rect.left_bottom();
+        let right = rect.right_top();
+
+        match path_kind {
+            PathKind::Straight => {
+                painter.line_segment([left, right], stroke);
+            }
+            PathKind::Curve => {
+                let control = rect.left_top();
+                let points = (0..=12)
+                    .map(|i| {
+                        let t = i as f32 / 12.0;
+                        let u = 1.0 - t;
+                        (left.to_vec2() * (u * u)
+                            + control.to_vec2() * (2.0 * u * t)
+                            + right.to_vec2() * (t * t))
+                            .to_pos2()
+                    })
+                    .collect();
+                painter.add(egui::Shape::line(points, stroke));
+            }
+            PathKind::Elbow => {
+                let corner = Pos2::new(rect.center().x, left.y);
+                let corner2 = Pos2::new(rect.center().x, right.y);
+                painter.add(egui::Shape::line(
+                    vec![left, corner, corner2, right],
+                    stroke,
+                ));
+            }
+        }
+    }
+
     /// 绘制下拉三角形
     fn draw_dropdown_triangle(painter: &egui::Painter, center: Pos2) {
         let size = 4.0;
@@ -217,7 +371,7 @@ impl ArrowToolPanel {
 
         // 绘制颜色点
         let dot_pos = Pos2::new(rect.min.x + 16.0, rect.center().y);
-        painter.circle_filled(dot_pos, 6.0, self.config.color);
+        painter.circle_filled(dot_pos, 6.0, self.style().color);
         painter.circle_stroke(dot_pos, 6.0, Stroke::new(1.0, Color32::WHITE));
 
         // 绘制大小文字
@@ -225,7 +379,7 @@ impl ArrowToolPanel {
         painter.text(
             text_pos,
             egui::Align2::CENTER_CENTER,
-            format!("{}", self.config.stroke_width as i32),
+            format!("{}", self.style().stroke_width as i32),
             egui::FontId::proportional(14.0),
             Color32::WHITE,
         );
@@ -234,7 +388,12 @@ impl ArrowToolPanel {
     }
 
     /// 绘制预设颜色按钮
-    fn draw_color_preset_button(&self, ui: &mut egui::Ui, rect: Rect, color: Color32) -> bool {
+    fn draw_color_preset_button(
+        ui: &mut egui::Ui,
+        rect: Rect,
+        color: Color32,
+        selected: bool,
+    ) -> bool {
         let response = ui.allocate_rect(rect, Sense::click());
         let painter = ui.painter();
 
@@ -252,7 +411,7 @@ impl ArrowToolPanel {
         }
 
         // 如果当前选中，添加选中边框
-        if self.config.color == color {
+        if selected {
             painter.rect_stroke(
                 rect.expand(2.0),
                 2.0,
@@ -373,7 +532,7 @@ impl ArrowToolPanel {
 
         let mut clicked_type = None;
         for (arrow_type, item_rect, response) in &responses {
-            let bg_color = if *arrow_type == self.config.arrow_type {
+            let bg_color = if *arrow_type == self.style().arrow_type {
                 Color32::from_rgb(0, 122, 255)
             } else if response.hovered() {
                 Color32::from_rgb(60, 60, 60)
@@ -397,7 +556,7 @@ impl ArrowToolPanel {
 
         // 第三步：更新状态
         if let Some(arrow_type) = clicked_type {
-            self.config.arrow_type = arrow_type;
+            self.style_mut().arrow_type = arrow_type;
             self.popup_state = PopupState::None;
         }
     }
@@ -433,7 +592,7 @@ impl ArrowToolPanel {
 
         let mut clicked_style = None;
         for (line_style, item_rect, response) in &responses {
-            let bg_color = if *line_style == self.config.line_style {
+            let bg_color = if *line_style == self.style().line_style {
                 Color32::from_rgb(0, 122, 255)
             } else if response.hovered() {
                 Color32::from_rgb(60, 60, 60)
@@ -457,7 +616,67 @@ impl ArrowToolPanel {
 
         // 第三步：更新状态
         if let Some(line_style) = clicked_style {
-            self.config.line_style = line_style;
+            self.style_mut().line_style = line_style;
+            self.popup_state = PopupState::None;
+        }
+    }
+
+    /// 绘制路径形状弹出菜单
+    fn draw_path_kind_popup(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let item_height = 28.0;
+        let mut y = rect.min.y + 4.0;
+
+        // 第一步：收集所有响应
+        let mut responses = Vec::new();
+        for path_kind in PathKind::all() {
+            let item_rect = Rect::from_min_size(
+                Pos2::new(rect.min.x + 4.0, y),
+                Vec2::new(rect.width() - 8.0, item_height),
+            );
+            let response = ui.allocate_rect(item_rect, Sense::click());
+            responses.push((*path_kind, item_rect, response));
+            y += item_height;
+        }
+
+        // 第二步：绘制
+        let painter = ui.painter();
+
+        // 背景
+        painter.rect_filled(rect, 6.0, Color32::from_rgb(45, 45, 45));
+        painter.rect_stroke(
+            rect,
+            6.0,
+            Stroke::new(1.0, Color32::from_rgb(80, 80, 80)),
+            StrokeKind::Inside,
+        );
+
+        let mut clicked_kind = None;
+        for (path_kind, item_rect, response) in &responses {
+            let bg_color = if *path_kind == self.style().path_kind {
+                Color32::from_rgb(0, 122, 255)
+            } else if response.hovered() {
+                Color32::from_rgb(60, 60, 60)
+            } else {
+                Color32::TRANSPARENT
+            };
+
+            painter.rect_filled(*item_rect, 4.0, bg_color);
+
+            // 只绘制路径图标（居中）
+            let icon_rect = Rect::from_center_size(
+                item_rect.center(),
+                Vec2::new(item_rect.width() - 16.0, item_height - 10.0),
+            );
+            self.draw_path_icon(painter, icon_rect, *path_kind);
+
+            if response.clicked() {
+                clicked_kind = Some(*path_kind);
+            }
+        }
+
+        // 第三步：更新状态
+        if let Some(path_kind) = clicked_kind {
+            self.style_mut().path_kind = path_kind;
             self.popup_state = PopupState::None;
         }
     }
@@ -465,6 +684,7 @@ impl ArrowToolPanel {
     /// 绘制颜色选择器弹出菜单
     fn draw_color_picker_popup(&mut self, ui: &mut egui::Ui, rect: Rect) {
         let padding = 10.0;
+        let inner_width = rect.width() - padding * 2.0;
         let mut y = rect.min.y + padding;
 
         // 颜色选择区域 - 色相/饱和度方块
@@ -485,12 +705,33 @@ impl ArrowToolPanel {
         y += color_box_size + 12.0;
 
         // 大小滑块
+        let size_label_y = y;
         let slider_rect = Rect::from_min_size(
             Pos2::new(rect.min.x + padding, y + 16.0),
-            Vec2::new(rect.width() - padding * 2.0, 20.0),
+            Vec2::new(inner_width, 20.0),
         );
         let slider_response = ui.allocate_rect(slider_rect, Sense::click_and_drag());
 
+        y += 44.0;
+
+        // 透明度滑块
+        let opacity_label_y = y;
+        let opacity_rect = Rect::from_min_size(
+            Pos2::new(rect.min.x + padding, y + 16.0),
+            Vec2::new(inner_width, 20.0),
+        );
+        let opacity_response = ui.allocate_rect(opacity_rect, Sense::click_and_drag());
+
+        y += 44.0;
+
+        // 十六进制输入和最近使用的颜色
+        let hex_rect = Rect::from_min_size(
+            Pos2::new(rect.min.x + padding, y),
+            Vec2::new(inner_width, 22.0),
+        );
+        y += 30.0;
+        let recent_y = y;
+
         // 第二步：绘制
         let painter = ui.painter();
 
@@ -514,7 +755,7 @@ impl ArrowToolPanel {
             Pos2::new(brightness_rect.max.x + 8.0, color_box_rect.min.y),
             Vec2::new(24.0, 24.0),
         );
-        painter.rect_filled(preview_rect, 2.0, self.config.color);
+        painter.rect_filled(preview_rect, 2.0, self.style().color);
         painter.rect_stroke(
             preview_rect,
             2.0,
@@ -524,9 +765,9 @@ impl ArrowToolPanel {
 
         // 大小滑块标签
         painter.text(
-            Pos2::new(rect.min.x + padding, y),
+            Pos2::new(rect.min.x + padding, size_label_y),
             egui::Align2::LEFT_CENTER,
-            format!("{}px", self.config.stroke_width as i32),
+            format!("{}px", self.style().stroke_width as i32),
             egui::FontId::proportional(12.0),
             Color32::WHITE,
         );
@@ -540,11 +781,29 @@ impl ArrowToolPanel {
 
         // 滑块位置（1-48 范围）
         let max_size = 48.0;
-        let ratio = (self.config.stroke_width - 1.0) / (max_size - 1.0);
+        let ratio = (self.style().stroke_width - 1.0) / (max_size - 1.0);
         let thumb_x = slider_rect.min.x + ratio * slider_rect.width();
         let thumb_pos = Pos2::new(thumb_x, slider_rect.center().y);
         painter.circle_filled(thumb_pos, 8.0, Color32::WHITE);
 
+        // 透明度滑块：轨道从透明渐变到当前颜色
+        let color = self.style().color;
+        painter.text(
+            Pos2::new(rect.min.x + padding, opacity_label_y),
+            egui::Align2::LEFT_CENTER,
+            format!("Opacity {}%", (color.a() as f32 / 2.55).round() as i32),
+            egui::FontId::proportional(12.0),
+            Color32::WHITE,
+        );
+        let track_rect = Rect::from_min_size(
+            Pos2::new(opacity_rect.min.x, opacity_rect.center().y - 3.0),
+            Vec2::new(opacity_rect.width(), 6.0),
+        );
+        self.draw_opacity_track(painter, track_rect, color);
+        let thumb_x = opacity_rect.min.x + color.a() as f32 / 255.0 * opacity_rect.width();
+        let thumb_pos = Pos2::new(thumb_x, opacity_rect.center().y);
+        painter.circle_filled(thumb_pos, 8.0, Color32::WHITE);
+
         // 第三步：处理交互
         if (color_box_response.clicked() || color_box_response.dragged())
             && let Some(pos) = ui.input(|i| i.pointer.interact_pos())
@@ -554,7 +813,7 @@ impl ArrowToolPanel {
             // 简化颜色选择：x = 色相，y = 饱和度
             let hue = rel_x * 360.0;
             let saturation = 1.0 - rel_y;
-            self.config.color = Self::hsv_to_rgb(hue, saturation, 1.0);
+            self.set_rgb(Self::hsv_to_rgb(hue, saturation, 1.0));
         }
 
         if (brightness_response.clicked() || brightness_response.dragged())
@@ -564,18 +823,336 @@ impl ArrowToolPanel {
                 ((pos.y - brightness_rect.min.y) / brightness_rect.height()).clamp(0.0, 1.0);
             let value = 1.0 - rel_y;
             // 调整当前颜色的亮度
-            let (h, s, _) = Self::rgb_to_hsv(self.config.color);
-            self.config.color = Self::hsv_to_rgb(h, s, value);
+            let (h, s, _) = Self::rgb_to_hsv(self.style().color);
+            self.set_rgb(Self::hsv_to_rgb(h, s, value));
         }
 
         if (slider_response.clicked() || slider_response.dragged())
             && let Some(pos) = ui.input(|i| i.pointer.interact_pos())
         {
             let new_ratio = ((pos.x - slider_rect.min.x) / slider_rect.width()).clamp(0.0, 1.0);
-            self.config.stroke_width = (1.0 + new_ratio * (max_size - 1.0)).round();
+            self.style_mut().stroke_width = (1.0 + new_ratio * (max_size - 1.0)).round();
+        }
+
+        // 透明度最低 10%，完全透明的箭头没有意义
+        if opacity_response.is_pointer_button_down_on()
+            && let Some(pos) = ui.input(|i| i.pointer.interact_pos())
+        {
+            let [r, g, b, _] = color.to_srgba_unmultiplied();
+            let [r, g, b] = *self.opacity_base.get_or_insert([r, g, b]);
+            let ratio = ((pos.x - opacity_rect.min.x) / opacity_rect.width()).clamp(0.1, 1.0);
+            let alpha = (ratio * 255.0).round() as u8;
+            self.style_mut().color = Color32::from_rgba_unmultiplied(r, g, b, alpha);
+        } else {
+            self.opacity_base = None;
+        }
+
+        // 松开时记入最近使用的颜色
+        if [&color_box_response, &brightness_response, &opacity_response]
+            .iter()
+            .any(|r| r.clicked() || r.drag_stopped())
+        {
+            self.push_recent_color(self.style().color);
+        }
+
+        // 十六进制输入，输入合法时立即生效
+        ui.scope_builder(UiBuilder::new().max_rect(hex_rect), |ui| {
+            ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+            ui.horizontal(|ui| {
+                ui.label("Hex");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.hex_input)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY),
+                );
+                if response.changed()
+                    && let Some(color) = parse_hex_color(&self.hex_input)
+                {
+                    self.style_mut().color = color;
+                }
+                if response.lost_focus()
+                    && let Some(color) = parse_hex_color(&self.hex_input)
+                {
+                    self.push_recent_color(color);
+                }
+                if !response.has_focus() {
+                    self.hex_input = color_to_hex(self.style().color);
+                }
+            });
+        });
+
+        // 最近使用的颜色
+        let swatch = 18.0;
+        let mut clicked = None;
+        for (i, color) in self.recent_colors.iter().enumerate() {
+            let swatch_rect = Rect::from_min_size(
+                Pos2::new(rect.min.x + padding + i as f32 * (swatch + 3.0), recent_y),
+                Vec2::splat(swatch),
+            );
+            let selected = self.style().color == *color;
+            if Self::draw_color_preset_button(ui, swatch_rect, *color, selected) {
+                clicked = Some(*color);
+            }
+        }
+        if let Some(color) = clicked {
+            self.style_mut().color = color;
+            self.push_recent_color(color);
+        }
+    }
+
+    /// 透明度滑块的轨道：从透明渐变到不透明
+    fn draw_opacity_track(&self, painter: &egui::Painter, rect: Rect, color: Color32) {
+        let [r, g, b, _] = color.to_srgba_unmultiplied();
+        painter.rect_filled(rect, 3.0, Color32::from_rgb(80, 80, 80));
+        let steps = 20;
+        let step_w = rect.width() / steps as f32;
+        for i in 0..steps {
+            let alpha = ((i as f32 + 0.5) / steps as f32 * 255.0) as u8;
+            let cell_rect = Rect::from_min_size(
+                Pos2::new(rect.min.x + i as f32 * step_w, rect.min.y),
+                Vec2::new(step_w + 0.5, rect.height()),
+            );
+            painter.rect_filled(
+                cell_rect,
+                0.0,
+                Color32::from_rgba_unmultiplied(r, g, b, alpha),
+            );
         }
     }
 
+    /// 绘制样式预设按钮
+    fn draw_presets_button(&self, ui: &mut egui::Ui, rect: Rect) -> bool {
+        let response = ui
+            .allocate_rect(rect, Sense::click())
+            .on_hover_text("Style presets");
+        let painter = ui.painter();
+
+        let bg_color = if response.hovered() {
+            Color32::from_rgb(70, 70, 70)
+        } else {
+            Color32::from_rgb(60, 60, 60)
+        };
+        painter.rect_filled(rect, 4.0, bg_color);
+        painter.rect_stroke(
+            rect,
+            4.0,
+            Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+            StrokeKind::Inside,
+        );
+
+        // 列表图标：三行，每行一个圆点和一条线
+        let icon = Rect::from_center_size(rect.center(), Vec2::new(18.0, 12.0));
+        for i in 0..3 {
+            let y = icon.min.y + i as f32 * 6.0;
+            painter.circle_filled(Pos2::new(icon.min.x + 1.5, y), 1.5, Color32::GRAY);
+            painter.line_segment(
+                [Pos2::new(icon.min.x + 5.0, y), Pos2::new(icon.max.x, y)],
+                Stroke::new(1.5, Color32::GRAY),
+            );
+        }
+
+        response.clicked()
+    }
+
+    /// 样式预设弹出菜单的高度
+    fn presets_popup_height(&self) -> f32 {
+        20.0 + self.presets.len().max(1) as f32 * 26.0 + 40.0
+    }
+
+    /// 绘制样式预设弹出菜单：点击应用，× 删除，底部保存当前样式
+    fn draw_presets_popup(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let painter = ui.painter();
+        painter.rect_filled(rect, 6.0, Color32::from_rgb(45, 45, 45));
+        painter.rect_stroke(
+            rect,
+            6.0,
+            Stroke::new(1.0, Color32::from_rgb(80, 80, 80)),
+            StrokeKind::Inside,
+        );
+
+        let inner = rect.shrink(10.0);
+        ui.scope_builder(UiBuilder::new().max_rect(inner), |ui| {
+            ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+            let mut apply = None;
+            let mut remove = None;
+
+            if self.presets.is_empty() {
+                ui.add_sized([inner.width(), 20.0], egui::Label::new("No presets"));
+            }
+            for (i, preset) in self.presets.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.set_height(20.0);
+                    // 颜色和线宽示意
+                    let (swatch, _) = ui.allocate_exact_size(Vec2::splat(16.0), Sense::hover());
+                    let radius = (preset.style.stroke_width / 2.0).clamp(2.0, 7.0);
+                    ui.painter()
+                        .circle_filled(swatch.center(), radius, preset.style.color);
+                    if ui
+                        .add(egui::Button::new(&preset.name).frame(false))
+                        .clicked()
+                    {
+                        apply = Some(i);
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("×")
+                            .on_hover_text("Delete preset")
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                });
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut self.preset_name)
+                        .hint_text("Preset name")
+                        .desired_width(inner.width() - 60.0),
+                );
+                let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("Save").clicked() || enter {
+                    let name = std::mem::take(&mut self.preset_name);
+                    self.save_preset(&name);
+                }
+            });
+
+            if let Some(i) = apply {
+                self.apply_preset(i);
+                self.popup_state = PopupState::None;
+            } else if let Some(i) = remove {
+                self.presets.remove(i);
+            }
+        });
+    }
+
+    /// 绘制效果按钮，启用了任一效果时高亮
+    fn draw_effects_button(&self, ui: &mut egui::Ui, rect: Rect) -> bool {
+        let response = ui.allocate_rect(rect, Sense::click());
+        let painter = ui.painter();
+        let effects = self.style().effects;
+        let active = effects.outline.enabled || effects.shadow.enabled;
+
+        let bg_color = if active {
+            Color32::from_rgb(0, 122, 255)
+        } else if response.hovered() {
+            Color32::from_rgb(70, 70, 70)
+        } else {
+            Color32::from_rgb(60, 60, 60)
+        };
+        painter.rect_filled(rect, 4.0, bg_color);
+        painter.rect_stroke(
+            rect,
+            4.0,
+            Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+            StrokeKind::Inside,
+        );
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "fx",
+            egui::FontId::proportional(14.0),
+            Color32::WHITE,
+        );
+
+        response.clicked()
+    }
+
+    /// 绘制“设为默认”按钮，没有选中箭头或样式与默认相同时不可用
+    fn draw_set_default_button(&self, ui: &mut egui::Ui, rect: Rect) -> bool {
+        let enabled = self
+            .editing
+            .as_ref()
+            .is_some_and(|style| *style != self.config);
+        let response = ui
+            .allocate_rect(rect, Sense::click())
+            .on_hover_text("Set as default style");
+        let painter = ui.painter();
+
+        let bg_color = if enabled && response.hovered() {
+            Color32::from_rgb(70, 70, 70)
+        } else {
+            Color32::from_rgb(60, 60, 60)
+        };
+        painter.rect_filled(rect, 4.0, bg_color);
+        painter.rect_stroke(
+            rect,
+            4.0,
+            Stroke::new(1.0, Color32::from_rgb(100, 100, 100)),
+            StrokeKind::Inside,
+        );
+        let text_color = if enabled {
+            Color32::WHITE
+        } else {
+            Color32::from_gray(110)
+        };
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "★",
+            egui::FontId::proportional(14.0),
+            text_color,
+        );
+
+        enabled && response.clicked()
+    }
+
+    /// 绘制描边/投影设置弹出菜单
+    fn draw_effects_popup(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let painter = ui.painter();
+        painter.rect_filled(rect, 6.0, Color32::from_rgb(45, 45, 45));
+        painter.rect_stroke(
+            rect,
+            6.0,
+            Stroke::new(1.0, Color32::from_rgb(80, 80, 80)),
+            StrokeKind::Inside,
+        );
+
+        let inner = rect.shrink(10.0);
+        ui.scope_builder(UiBuilder::new().max_rect(inner), |ui| {
+            ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+            ui.spacing_mut().slider_width = 110.0;
+            let effects = &mut self.style_mut().effects;
+
+            ui.checkbox(&mut effects.outline.enabled, "Outline");
+            ui.add_enabled_ui(effects.outline.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut effects.outline.width, 1.0..=8.0).text("width"));
+            });
+
+            // 描边颜色：预设色块
+            let swatch = 18.0;
+            let (row, _) =
+                ui.allocate_exact_size(Vec2::new(inner.width(), swatch + 4.0), Sense::hover());
+            let mut clicked = None;
+            for (i, (color, _name)) in PRESET_COLORS.iter().enumerate() {
+                let swatch_rect = Rect::from_min_size(
+                    Pos2::new(row.min.x + 2.0 + i as f32 * (swatch + 6.0), row.min.y + 2.0),
+                    Vec2::splat(swatch),
+                );
+                let selected = effects.outline.color == *color;
+                if Self::draw_color_preset_button(ui, swatch_rect, *color, selected) {
+                    clicked = Some(*color);
+                }
+            }
+            if let Some(color) = clicked {
+                effects.outline.color = color;
+                effects.outline.enabled = true;
+            }
+
+            ui.separator();
+            ui.checkbox(&mut effects.shadow.enabled, "Shadow");
+            ui.add_enabled_ui(effects.shadow.enabled, |ui| {
+                let shadow = &mut effects.shadow;
+                ui.add(egui::Slider::new(&mut shadow.offset.x, -20.0..=20.0).text("x"));
+                ui.add(egui::Slider::new(&mut shadow.offset.y, -20.0..=20.0).text("y"));
+                ui.add(egui::Slider::new(&mut shadow.blur, 0.0..=30.0).text("blur"));
+                ui.add(egui::Slider::new(&mut shadow.opacity, 0.0..=1.0).text("opacity"));
+            });
+        });
+    }
+
     /// 绘制颜色选择方块
     fn draw_color_picker_box(&self, painter: &egui::Painter, rect: Rect) {
         let steps = 20;
@@ -662,9 +1239,10 @@ impl ArrowToolPanel {
 
     /// RGB 转 HSV
     fn rgb_to_hsv(color: Color32) -> (f32, f32, f32) {
-        let r = color.r() as f32 / 255.0;
-        let g = color.g() as f32 / 255.0;
-        let b = color.b() as f32 / 255.0;
+        let [r, g, b, _] = color.to_srgba_unmultiplied();
+        let r = r as f32 / 255.0;
+        let g = g as f32 / 255.0;
+        let b = b as f32 / 255.0;
 
         let max = r.max(g).max(b);
         let min = r.min(g).min(b);
@@ -687,6 +1265,12 @@ impl ArrowToolPanel {
         (h, s, v)
     }
 
+    /// 位置是否落在面板或弹出菜单上
+    pub fn contains(&self, pos: Pos2) -> bool {
+        self.panel_rect.is_some_and(|r| r.contains(pos))
+            || self.popup_rect.is_some_and(|r| r.contains(pos))
+    }
+
     /// 检查点击是否在面板外部
     pub fn is_click_outside(&self, pos: Pos2) -> bool {
         if let Some(panel_rect) = self.panel_rect {
@@ -741,7 +1325,20 @@ impl ArrowToolPanel {
         }
         x += line_btn_width + padding;
 
-        // 3. 颜色/大小按钮
+        // 3. 路径形状按钮（带下拉箭头）
+        let path_btn_width = 60.0;
+        let path_btn_rect =
+            Rect::from_min_size(Pos2::new(x, y), Vec2::new(path_btn_width, btn_height));
+        if self.draw_path_kind_button(ui, path_btn_rect) {
+            self.popup_state = if self.popup_state == PopupState::PathKind {
+                PopupState::None
+            } else {
+                PopupState::PathKind
+            };
+        }
+        x += path_btn_width + padding;
+
+        // 4. 颜色/大小按钮
         let color_btn_width = 60.0;
         let color_btn_rect =
             Rect::from_min_size(Pos2::new(x, y), Vec2::new(color_btn_width, btn_height));
@@ -754,33 +1351,92 @@ impl ArrowToolPanel {
         }
         x += color_btn_width + padding;
 
-        // 4. 快速预设颜色按钮
+        // 5. 效果按钮
+        let effects_btn_width = 40.0;
+        let effects_btn_rect =
+            Rect::from_min_size(Pos2::new(x, y), Vec2::new(effects_btn_width, btn_height));
+        if self.draw_effects_button(ui, effects_btn_rect) {
+            self.popup_state = if self.popup_state == PopupState::Effects {
+                PopupState::None
+            } else {
+                PopupState::Effects
+            };
+        }
+        x += effects_btn_width + padding;
+
+        // 6. 设为默认：把选中箭头的样式复制到新箭头的配置
+        let default_btn_width = 40.0;
+        let default_btn_rect =
+            Rect::from_min_size(Pos2::new(x, y), Vec2::new(default_btn_width, btn_height));
+        if self.draw_set_default_button(ui, default_btn_rect)
+            && let Some(style) = &self.editing
+        {
+            self.config = style.clone();
+        }
+        x += default_btn_width + padding;
+
+        // 7. 样式预设按钮
+        let presets_btn_width = 40.0;
+        let presets_btn_rect =
+            Rect::from_min_size(Pos2::new(x, y), Vec2::new(presets_btn_width, btn_height));
+        if self.draw_presets_button(ui, presets_btn_rect) {
+            self.popup_state = if self.popup_state == PopupState::Presets {
+                PopupState::None
+            } else {
+                PopupState::Presets
+            };
+        }
+        x += presets_btn_width + padding;
+
+        // 8. 快速预设颜色按钮
         let preset_size = btn_height;
         for (color, _name) in PRESET_COLORS {
             let preset_rect =
                 Rect::from_min_size(Pos2::new(x, y), Vec2::new(preset_size, preset_size));
-            if self.draw_color_preset_button(ui, preset_rect, *color) {
-                self.config.color = *color;
+            let selected = self.style().color == *color;
+            if Self::draw_color_preset_button(ui, preset_rect, *color, selected) {
+                self.style_mut().color = *color;
             }
             x += preset_size + 4.0;
         }
 
         // 绘制弹出面板
+        self.popup_rect = None;
         match self.popup_state {
             PopupState::ArrowType => {
                 // 4 items * 32px + 8px padding
                 let popup_rect = self.calc_popup_rect(arrow_btn_rect, 100.0, 136.0, screen);
                 self.draw_arrow_type_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
             }
             PopupState::LineStyle => {
                 // 5 items * 28px + 8px padding
                 let popup_rect = self.calc_popup_rect(line_btn_rect, 100.0, 148.0, screen);
                 self.draw_line_style_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
+            }
+            PopupState::PathKind => {
+                // 3 items * 28px + 8px padding
+                let popup_rect = self.calc_popup_rect(path_btn_rect, 100.0, 92.0, screen);
+                self.draw_path_kind_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
             }
             PopupState::ColorPicker => {
-                // 颜色方块 120 + 亮度条 16 + 间距 + padding
-                let popup_rect = self.calc_popup_rect(color_btn_rect, 190.0, 190.0, screen);
+                // 颜色方块 120 + 大小、透明度滑块各 44 + 十六进制 30 + 最近颜色 18 + padding
+                let popup_rect = self.calc_popup_rect(color_btn_rect, 190.0, 290.0, screen);
                 self.draw_color_picker_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
+            }
+            PopupState::Effects => {
+                let popup_rect = self.calc_popup_rect(effects_btn_rect, 220.0, 250.0, screen);
+                self.draw_effects_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
+            }
+            PopupState::Presets => {
+                let height = self.presets_popup_height();
+                let popup_rect = self.calc_popup_rect(presets_btn_rect, 220.0, height, screen);
+                self.draw_presets_popup(ui, popup_rect);
+                self.popup_rect = Some(popup_rect);
             }
             PopupState::None => {}
         }
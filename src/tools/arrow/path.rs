@@ -0,0 +1,219 @@
+use eframe::egui::{Pos2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// 新建箭头时使用的路径形状
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PathKind {
+    #[default]
+    Straight, // 直线
+    Curve, // 曲线
+    Elbow, // 折线
+}
+
+impl PathKind {
+    pub fn all() -> &'static [PathKind] {
+        &[PathKind::Straight, PathKind::Curve, PathKind::Elbow]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PathKind::Straight => "Straight",
+            PathKind::Curve => "Curve",
+            PathKind::Elbow => "Elbow",
+        }
+    }
+}
+
+/// 箭头起点和终点之间的路径
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ArrowPath {
+    #[default]
+    Straight,
+    /// 二次贝塞尔曲线，`control` 为控制点
+    Curve { control: Pos2 },
+    /// 折线，`points` 为中间的拐点
+    Polyline { points: Vec<Pos2> },
+}
+
+impl ArrowPath {
+    /// 按形状为新箭头生成路径
+    pub fn new(kind: PathKind, start: Pos2, end: Pos2) -> Self {
+        match kind {
+            PathKind::Straight => ArrowPath::Straight,
+            PathKind::Curve => {
+                // 控制点放在中垂线上，初始弯曲程度为长度的四分之一
+                let delta = end - start;
+                let perp = Vec2::new(-delta.y, delta.x);
+                ArrowPath::Curve {
+                    control: start + delta * 0.5 + perp * 0.25,
+                }
+            }
+            PathKind::Elbow => ArrowPath::Polyline {
+                points: vec![Pos2::new(end.x, start.y)],
+            },
+        }
+    }
+
+    pub fn kind(&self) -> PathKind {
+        match self {
+            ArrowPath::Straight => PathKind::Straight,
+            ArrowPath::Curve { .. } => PathKind::Curve,
+            ArrowPath::Polyline { .. } => PathKind::Elbow,
+        }
+    }
+
+    /// 可拖动的控制手柄（不含起点和终点）
+    pub fn handles(&self) -> &[Pos2] {
+        match self {
+            ArrowPath::Straight => &[],
+            ArrowPath::Curve { control } => std::slice::from_ref(control),
+            ArrowPath::Polyline { points } => points,
+        }
+    }
+
+    pub fn handles_mut(&mut self) -> &mut [Pos2] {
+        match self {
+            ArrowPath::Straight => &mut [],
+            ArrowPath::Curve { control } => std::slice::from_mut(control),
+            ArrowPath::Polyline { points } => points,
+        }
+    }
+
+    /// 对所有控制手柄做坐标变换
+    pub fn map(&mut self, f: impl Fn(Pos2) -> Pos2) {
+        for p in self.handles_mut() {
+            *p = f(*p);
+        }
+    }
+
+    /// 中心线：依次经过的点，曲线细分为短折线
+    pub fn centerline(&self, start: Pos2, end: Pos2) -> Vec<Pos2> {
+        match self {
+            ArrowPath::Straight => vec![start, end],
+            ArrowPath::Curve { control } => {
+                // 按控制多边形的长度决定细分段数
+                let approx = (*control - start).length() + (end - *control).length();
+                let steps = ((approx / 4.0).ceil() as usize).clamp(8, 64);
+                (0..=steps)
+                    .map(|i| {
+                        let t = i as f32 / steps as f32;
+                        let u = 1.0 - t;
+                        let p = start.to_vec2() * (u * u)
+                            + control.to_vec2() * (2.0 * u * t)
+                            + end.to_vec2() * (t * t);
+                        p.to_pos2()
+                    })
+                    .collect()
+            }
+            ArrowPath::Polyline { points } => std::iter::once(start)
+                .chain(points.iter().copied())
+                .chain(std::iter::once(end))
+                .collect(),
+        }
+    }
+
+    /// 在第 `index` 个拐点之前插入拐点，只对折线有效
+    pub fn insert_point(&mut self, index: usize, pos: Pos2) {
+        if let ArrowPath::Polyline { points } = self {
+            points.insert(index.min(points.len()), pos);
+        }
+    }
+
+    /// 删除一个拐点，最后一个拐点删除后变回直线
+    pub fn remove_point(&mut self, index: usize) {
+        if let ArrowPath::Polyline { points } = self {
+            if index < points.len() {
+                points.remove(index);
+            }
+            if points.is_empty() {
+                *self = ArrowPath::Straight;
+            }
+        }
+    }
+}
+
+/// 折线各顶点到起点的路程
+pub fn cumulative_lengths(points: &[Pos2]) -> Vec<f32> {
+    let mut total = 0.0;
+    let mut lengths = Vec::with_capacity(points.len());
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            total += (*p - points[i - 1]).length();
+        }
+        lengths.push(total);
+    }
+    lengths
+}
+
+/// 折线上路程为 `distance` 的点，超出两端时沿首尾两段延长
+pub fn point_at(points: &[Pos2], distance: f32) -> Pos2 {
+    let lengths = cumulative_lengths(points);
+    point_at_with(points, &lengths, distance)
+}
+
+fn point_at_with(points: &[Pos2], lengths: &[f32], distance: f32) -> Pos2 {
+    let last = points.len() - 1;
+    // 找到包含该路程的非零长度线段
+    let mut index = None;
+    for i in 0..last {
+        if lengths[i + 1] > lengths[i] {
+            if index.is_none() || distance >= lengths[i] {
+                index = Some(i);
+            }
+            if distance <= lengths[i + 1] {
+                break;
+            }
+        }
+    }
+    let Some(i) = index else {
+        return points[0];
+    };
+    let t = (distance - lengths[i]) / (lengths[i + 1] - lengths[i]);
+    points[i] + (points[i + 1] - points[i]) * t
+}
+
+/// 折线上路程 `from..to` 的一段，至少包含两个点
+///
+/// `to <= from` 时返回两个端点，与直线上反向的线段一致。
+pub fn sub_path(points: &[Pos2], from: f32, to: f32) -> Vec<Pos2> {
+    let lengths = cumulative_lengths(points);
+    let mut out = vec![point_at_with(points, &lengths, from)];
+    if to > from {
+        for (p, &length) in points.iter().zip(&lengths).skip(1) {
+            if length > from && length < to {
+                out.push(*p);
+            }
+        }
+    }
+    out.push(point_at_with(points, &lengths, to));
+    out
+}
+
+/// 点到折线的距离，以及最近的那一段的序号
+pub fn distance_to(points: &[Pos2], pos: Pos2) -> (f32, usize) {
+    let mut best = (f32::INFINITY, 0);
+    for (i, pair) in points.windows(2).enumerate() {
+        let dist = point_to_segment_distance(pos, pair[0], pair[1]);
+        if dist < best.0 {
+            best = (dist, i);
+        }
+    }
+    if points.len() == 1 {
+        best = ((pos - points[0]).length(), 0);
+    }
+    best
+}
+
+/// 点到线段的距离
+fn point_to_segment_distance(point: Pos2, line_start: Pos2, line_end: Pos2) -> f32 {
+    let line = line_end - line_start;
+    let len_sq = line.length_sq();
+
+    if len_sq == 0.0 {
+        return (point - line_start).length();
+    }
+
+    let t = ((point - line_start).dot(line) / len_sq).clamp(0.0, 1.0);
+    let projection = line_start + line * t;
+    (point - projection).length()
+}
@@ -0,0 +1,167 @@
+use eframe::egui::{Color32, Pos2, Vec2};
+use image::{GrayImage, Luma, RgbaImage};
+use imageproc::drawing::draw_antialiased_polygon_mut;
+use imageproc::filter::gaussian_blur_f32;
+use imageproc::point::Point;
+
+use crate::tools::arrow::{Geometry, Segment};
+
+/// 基础实现：先把整个标注压平成覆盖率图，再一次性合成
+pub fn rasterize(geometry: &Geometry, img: &mut RgbaImage, color: Color32) {
+    if let Some((mask, origin)) = layer(geometry, 0.0) {
+        composite(&mask, img, origin, color);
+    }
+}
+
+/// 只覆盖形状所在区域的覆盖率图层，`blur` 为高斯模糊的 σ（像素）
+///
+/// 返回图层和它左上角的像素坐标，形状为空时返回 `None`。
+pub fn layer(geometry: &Geometry, blur: f32) -> Option<(GrayImage, [i32; 2])> {
+    let bounds = geometry.bounding_rect();
+    if !bounds.is_positive() {
+        return None;
+    }
+    let margin = 2.0 + blur.max(0.0) * 3.0;
+    let min = (bounds.min.to_vec2() - Vec2::splat(margin)).floor();
+    let max = (bounds.max.to_vec2() + Vec2::splat(margin)).ceil();
+    let size = max - min;
+
+    let local = geometry.transformed(Pos2::new(min.x, min.y), 1.0);
+    let mut mask = coverage_mask(&local, size.x as u32, size.y as u32);
+    if blur > 0.0 {
+        mask = gaussian_blur_f32(&mask, blur);
+    }
+    Some((mask, [min.x as i32, min.y as i32]))
+}
+
+/// 标注的覆盖率图，坐标单位为像素
+///
+/// 重叠的图元（线条、箭头、圆头）取覆盖率的最大值，半透明颜色因此只混合一次。
+fn coverage_mask(geometry: &Geometry, width: u32, height: u32) -> GrayImage {
+    let mut mask = GrayImage::new(width, height);
+    for segment in &geometry.segments {
+        draw_segment(&mut mask, segment);
+    }
+    for polygon in &geometry.polygons {
+        let points: Vec<Point<i32>> = polygon.iter().map(|p| round_point((p.x, p.y))).collect();
+        if points.len() >= 3 && points.first() != points.last() {
+            draw_antialiased_polygon_mut(&mut mask, &points, Luma([255]), max_coverage);
+        }
+    }
+    mask
+}
+
+/// 按覆盖率做 source-over 合成，与屏幕预览一样在 sRGB 空间中混合
+///
+/// `origin` 为覆盖率图左上角在图像中的像素坐标。
+pub fn composite(mask: &GrayImage, img: &mut RgbaImage, origin: [i32; 2], color: Color32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let src = [r, g, b].map(f32::from);
+    let opacity = a as f32 / 255.0;
+    let (width, height) = (img.width() as i32, img.height() as i32);
+
+    for (x, y, coverage) in mask.enumerate_pixels() {
+        let (x, y) = (origin[0] + x as i32, origin[1] + y as i32);
+        if x < 0 || y < 0 || x >= width || y >= height {
+            continue;
+        }
+        let dst = img.get_pixel_mut(x as u32, y as u32);
+        let alpha = coverage[0] as f32 / 255.0 * opacity;
+        if alpha <= 0.0 {
+            continue;
+        }
+        let dst_alpha = dst[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for i in 0..3 {
+            let blended = (src[i] * alpha + dst[i] as f32 * dst_alpha * (1.0 - alpha)) / out_alpha;
+            dst[i] = blended.round().clamp(0.0, 255.0) as u8;
+        }
+        dst[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+/// 覆盖率取最大值，而不是叠加
+fn max_coverage(_: Luma<u8>, original: Luma<u8>, weight: f32) -> Luma<u8> {
+    let coverage = (weight.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Luma([original[0].max(coverage)])
+}
+
+fn round_point(p: (f32, f32)) -> Point<i32> {
+    Point::new(p.0.round() as i32, p.1.round() as i32)
+}
+
+fn draw_antialiased_circle(mask: &mut GrayImage, center: (f32, f32), radius: f32) {
+    if radius <= 0.0 {
+        return;
+    }
+
+    let r = radius.max(0.5);
+    let min_x = (center.0 - r - 1.0).floor() as i32;
+    let max_x = (center.0 + r + 1.0).ceil() as i32;
+    let min_y = (center.1 - r - 1.0).floor() as i32;
+    let max_y = (center.1 + r + 1.0).ceil() as i32;
+
+    let width = mask.width() as i32;
+    let height = mask.height() as i32;
+
+    for y in min_y..=max_y {
+        if y < 0 || y >= height {
+            continue;
+        }
+        for x in min_x..=max_x {
+            if x < 0 || x >= width {
+                continue;
+            }
+
+            let dx = x as f32 + 0.5 - center.0;
+            let dy = y as f32 + 0.5 - center.1;
+            let dist = (dx * dx + dy * dy).sqrt();
+            let weight = r - dist + 0.5;
+
+            if weight > 0.0 {
+                let original = *mask.get_pixel(x as u32, y as u32);
+                mask.put_pixel(
+                    x as u32,
+                    y as u32,
+                    max_coverage(Luma([255]), original, weight),
+                );
+            }
+        }
+    }
+}
+
+/// 圆头线段：矩形主体 + 两端的圆
+fn draw_segment(mask: &mut GrayImage, segment: &Segment) {
+    let start = (segment.from.x, segment.from.y);
+    let end = (segment.to.x, segment.to.y);
+    let half = segment.width / 2.0;
+    let dx = end.0 - start.0;
+    let dy = end.1 - start.1;
+    let len = (dx * dx + dy * dy).sqrt();
+
+    if len == 0.0 {
+        draw_antialiased_circle(mask, start, half);
+        return;
+    }
+
+    // 计算包围线段的矩形，使用抗锯齿多边形绘制
+    let perp = (-dy / len, dx / len);
+    let p1 = (start.0 + perp.0 * half, start.1 + perp.1 * half);
+    let p2 = (start.0 - perp.0 * half, start.1 - perp.1 * half);
+    let p3 = (end.0 - perp.0 * half, end.1 - perp.1 * half);
+    let p4 = (end.0 + perp.0 * half, end.1 + perp.1 * half);
+
+    let points = [
+        round_point(p1),
+        round_point(p2),
+        round_point(p3),
+        round_point(p4),
+    ];
+    if points[0] != points[3] {
+        draw_antialiased_polygon_mut(mask, &points, Luma([255]), max_coverage);
+    }
+
+    // 圆角端点
+    draw_antialiased_circle(mask, start, half);
+    draw_antialiased_circle(mask, end, half);
+}
@@ -0,0 +1,49 @@
+pub(super) mod basic;
+#[cfg(feature = "skia")]
+mod skia;
+
+use eframe::egui::Color32;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use super::geometry::Geometry;
+
+/// 导出时把标注画到图像上的后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rasterizer {
+    Basic, // imageproc 多边形
+    Skia,  // tiny-skia，需要 `skia` feature，未编译时退回 Basic
+}
+
+impl Default for Rasterizer {
+    fn default() -> Self {
+        Self::available()[0]
+    }
+}
+
+impl Rasterizer {
+    /// 当前编译进来的后端，首选的在前
+    pub fn available() -> &'static [Rasterizer] {
+        #[cfg(feature = "skia")]
+        return &[Rasterizer::Skia, Rasterizer::Basic];
+        #[cfg(not(feature = "skia"))]
+        return &[Rasterizer::Basic];
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rasterizer::Basic => "Basic",
+            Rasterizer::Skia => "tiny-skia",
+        }
+    }
+
+    /// 把几何形状光栅化到图像上，坐标单位为像素
+    pub fn rasterize(self, geometry: &Geometry, img: &mut RgbaImage, color: Color32) {
+        match self {
+            #[cfg(feature = "skia")]
+            Rasterizer::Skia => skia::rasterize(geometry, img, color),
+            _ => basic::rasterize(geometry, img, color),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use eframe::egui::{Color32, Pos2, Vec2};
+use image::{GrayImage, RgbaImage};
+use tiny_skia::{FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+
+use super::basic;
+use crate::tools::arrow::{Geometry, Segment};
+
+/// tiny-skia 实现：先把整个标注画成覆盖率图层，再一次性合成
+pub fn rasterize(geometry: &Geometry, img: &mut RgbaImage, color: Color32) {
+    // 图层只覆盖形状所在区域，留出抗锯齿的边距
+    let bounds = geometry.bounding_rect();
+    if !bounds.is_positive() {
+        return;
+    }
+    let min = (bounds.min.to_vec2() - Vec2::splat(2.0)).floor();
+    let max = (bounds.max.to_vec2() + Vec2::splat(2.0)).ceil();
+    let size = max - min;
+    let Some(mut layer) = Pixmap::new(size.x as u32, size.y as u32) else {
+        return;
+    };
+    let transform = Transform::from_translate(-min.x, -min.y);
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(255, 255, 255, 255);
+    paint.anti_alias = true;
+
+    for (path, width) in stroke_paths(&geometry.segments) {
+        match width {
+            Some(width) => {
+                let stroke = Stroke {
+                    width,
+                    line_cap: LineCap::Round,
+                    line_join: LineJoin::Round,
+                    ..Stroke::default()
+                };
+                layer.stroke_path(&path, &paint, &stroke, transform, None);
+            }
+            None => layer.fill_path(&path, &paint, FillRule::Winding, transform, None),
+        }
+    }
+    for polygon in &geometry.polygons {
+        let mut builder = PathBuilder::new();
+        for (i, p) in polygon.iter().enumerate() {
+            if i == 0 {
+                builder.move_to(p.x, p.y);
+            } else {
+                builder.line_to(p.x, p.y);
+            }
+        }
+        builder.close();
+        if let Some(path) = builder.finish() {
+            layer.fill_path(&path, &paint, FillRule::Winding, transform, None);
+        }
+    }
+
+    composite(&layer, img, [min.x as i32, min.y as i32], color);
+}
+
+/// 把首尾相接、线宽相同的线段连成折线，得到真正的拐角连接
+///
+/// 返回路径和线宽；单独的圆点返回填充用的圆，线宽为 `None`。
+fn stroke_paths(segments: &[Segment]) -> Vec<(Path, Option<f32>)> {
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < segments.len() {
+        let first = segments[i];
+        if first.from == first.to {
+            if let Some(circle) =
+                PathBuilder::from_circle(first.from.x, first.from.y, first.width / 2.0)
+            {
+                paths.push((circle, None));
+            }
+            i += 1;
+            continue;
+        }
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(first.from.x, first.from.y);
+        builder.line_to(first.to.x, first.to.y);
+        let mut last = first;
+        i += 1;
+        while let Some(&next) = segments.get(i) {
+            if next.width != first.width || !close(next.from, last.to) || next.from == next.to {
+                break;
+            }
+            builder.line_to(next.to.x, next.to.y);
+            last = next;
+            i += 1;
+        }
+        if last != first && close(last.to, first.from) {
+            builder.close();
+        }
+        if let Some(path) = builder.finish() {
+            paths.push((path, Some(first.width)));
+        }
+    }
+    paths
+}
+
+fn close(a: Pos2, b: Pos2) -> bool {
+    (a - b).length_sq() < 1e-6
+}
+
+/// 取出图层的覆盖率，与基础实现和屏幕预览一样在 sRGB 空间中合成
+fn composite(layer: &Pixmap, img: &mut RgbaImage, origin: [i32; 2], color: Color32) {
+    // 图层用不透明白色绘制，alpha 通道就是覆盖率
+    let coverage = layer.data().chunks_exact(4).map(|px| px[3]).collect();
+    if let Some(mask) = GrayImage::from_raw(layer.width(), layer.height(), coverage) {
+        basic::composite(&mask, img, origin, color);
+    }
+}
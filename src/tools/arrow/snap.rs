@@ -0,0 +1,182 @@
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect, Stroke, Vec2};
+
+/// 吸附距离（逻辑像素）
+pub const SNAP_RADIUS: f32 = 8.0;
+/// 按住 Shift 时的角度步长（度）
+pub const ANGLE_STEP: f32 = 15.0;
+
+/// 当前生效的吸附，用于绘制提示
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapHint {
+    /// 角度吸附：以 `anchor` 为中心，`pos` 为吸附后的位置
+    Angle {
+        anchor: Pos2,
+        pos: Pos2,
+        degrees: f32,
+    },
+    /// 吸附到选区边缘
+    Edge { from: Pos2, to: Pos2 },
+    /// 吸附到其他端点
+    Point(Pos2),
+}
+
+/// 吸附目标：选区边缘和其他标注的端点
+#[derive(Debug, Clone, Default)]
+pub struct SnapTargets {
+    pub bounds: Option<Rect>,
+    pub points: Vec<Pos2>,
+}
+
+/// 把 `pos` 绕 `anchor` 的方向吸附到 15° 的整数倍，距离不变
+///
+/// 返回吸附后的位置和角度（屏幕上向右为 0°，逆时针为正）。
+pub fn snap_angle(anchor: Pos2, pos: Pos2) -> (Pos2, f32) {
+    let delta = pos - anchor;
+    let step = ANGLE_STEP.to_radians();
+    let angle = (delta.angle() / step).round() * step;
+    let degrees = (-angle.to_degrees()).round().rem_euclid(360.0);
+    (anchor + Vec2::angled(angle) * delta.length(), degrees)
+}
+
+impl SnapTargets {
+    /// 吸附端点位置；`anchor` 为同一线段另一端，按住 Shift 时以它为中心吸附角度
+    pub fn snap(&self, pos: Pos2, anchor: Option<Pos2>, shift: bool) -> (Pos2, Vec<SnapHint>) {
+        let mut hints = Vec::new();
+
+        if shift && let Some(anchor) = anchor.filter(|a| *a != pos) {
+            // 角度锁定：只允许沿射线吸附到选区边缘
+            let (mut snapped, degrees) = snap_angle(anchor, pos);
+            if let Some((on_edge, edge)) = self.ray_to_edge(anchor, snapped) {
+                snapped = on_edge;
+                hints.push(edge);
+            }
+            hints.insert(
+                0,
+                SnapHint::Angle {
+                    anchor,
+                    pos: snapped,
+                    degrees,
+                },
+            );
+            return (snapped, hints);
+        }
+
+        // 其他端点优先
+        let nearest = self
+            .points
+            .iter()
+            .map(|&p| (p, (p - pos).length()))
+            .filter(|&(_, dist)| dist <= SNAP_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((point, _)) = nearest {
+            return (point, vec![SnapHint::Point(point)]);
+        }
+
+        // 选区边缘：横竖方向分别吸附
+        let mut snapped = pos;
+        if let Some(bounds) = self.bounds {
+            for x in [bounds.left(), bounds.right()] {
+                if (pos.x - x).abs() <= SNAP_RADIUS {
+                    snapped.x = x;
+                    hints.push(SnapHint::Edge {
+                        from: Pos2::new(x, bounds.top()),
+                        to: Pos2::new(x, bounds.bottom()),
+                    });
+                    break;
+                }
+            }
+            for y in [bounds.top(), bounds.bottom()] {
+                if (pos.y - y).abs() <= SNAP_RADIUS {
+                    snapped.y = y;
+                    hints.push(SnapHint::Edge {
+                        from: Pos2::new(bounds.left(), y),
+                        to: Pos2::new(bounds.right(), y),
+                    });
+                    break;
+                }
+            }
+        }
+        (snapped, hints)
+    }
+
+    /// 射线 `anchor -> pos` 与附近的选区边缘的交点
+    fn ray_to_edge(&self, anchor: Pos2, pos: Pos2) -> Option<(Pos2, SnapHint)> {
+        let bounds = self.bounds?;
+        let dir = pos - anchor;
+        let mut best: Option<(Pos2, SnapHint, f32)> = None;
+        let mut consider = |hit: Pos2, edge: SnapHint| {
+            let dist = (hit - pos).length();
+            if dist <= SNAP_RADIUS && best.is_none_or(|(_, _, d)| dist < d) {
+                best = Some((hit, edge, dist));
+            }
+        };
+
+        if dir.x != 0.0 {
+            for x in [bounds.left(), bounds.right()] {
+                let hit = anchor + dir * ((x - anchor.x) / dir.x);
+                if (bounds.top()..=bounds.bottom()).contains(&hit.y) {
+                    let edge = SnapHint::Edge {
+                        from: Pos2::new(x, bounds.top()),
+                        to: Pos2::new(x, bounds.bottom()),
+                    };
+                    consider(hit, edge);
+                }
+            }
+        }
+        if dir.y != 0.0 {
+            for y in [bounds.top(), bounds.bottom()] {
+                let hit = anchor + dir * ((y - anchor.y) / dir.y);
+                if (bounds.left()..=bounds.right()).contains(&hit.x) {
+                    let edge = SnapHint::Edge {
+                        from: Pos2::new(bounds.left(), y),
+                        to: Pos2::new(bounds.right(), y),
+                    };
+                    consider(hit, edge);
+                }
+            }
+        }
+        best.map(|(hit, edge, _)| (hit, edge))
+    }
+}
+
+/// 绘制吸附提示
+pub fn draw_snap_hints(painter: &egui::Painter, hints: &[SnapHint]) {
+    let color = Color32::from_rgb(255, 64, 200);
+    for hint in hints {
+        match *hint {
+            SnapHint::Angle {
+                anchor,
+                pos,
+                degrees,
+            } => {
+                // 沿吸附方向延长的虚线 + 角度标签
+                let dir = (pos - anchor).normalized();
+                if !dir.is_finite() {
+                    continue;
+                }
+                let far = pos + dir * 40.0;
+                painter.add(egui::Shape::dashed_line(
+                    &[anchor, far],
+                    Stroke::new(1.0, color),
+                    6.0,
+                    4.0,
+                ));
+                let label_pos = anchor + Vec2::new(12.0, -12.0);
+                painter.text(
+                    label_pos,
+                    Align2::LEFT_BOTTOM,
+                    format!("{degrees:.0}°"),
+                    FontId::proportional(12.0),
+                    color,
+                );
+            }
+            SnapHint::Edge { from, to } => {
+                painter.line_segment([from, to], Stroke::new(2.0, color));
+            }
+            SnapHint::Point(point) => {
+                painter.circle_stroke(point, 6.0, Stroke::new(2.0, color));
+                painter.circle_filled(point, 2.0, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use super::config::ArrowConfig;
+use super::effects::{Effects, Outline};
+use super::types::ArrowType;
+
+/// 最多保留的最近使用颜色
+pub const RECENT_COLORS: usize = 8;
+
+/// 用户命名的样式预设
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StylePreset {
+    pub name: String,
+    pub style: ArrowConfig,
+}
+
+/// 持久化的箭头工具设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArrowSettings {
+    /// 新箭头的样式
+    pub style: ArrowConfig,
+    pub presets: Vec<StylePreset>,
+    /// 最近使用的颜色，最新的在前
+    pub recent_colors: Vec<Color32>,
+}
+
+impl Default for ArrowSettings {
+    fn default() -> Self {
+        Self {
+            style: ArrowConfig::default(),
+            presets: vec![
+                StylePreset {
+                    name: "Callout".to_string(),
+                    style: ArrowConfig {
+                        arrow_type: ArrowType::Filled,
+                        stroke_width: 6.0,
+                        effects: Effects {
+                            outline: Outline {
+                                enabled: true,
+                                ..Outline::default()
+                            },
+                            ..Effects::default()
+                        },
+                        ..ArrowConfig::default()
+                    },
+                },
+                StylePreset {
+                    name: "Highlight".to_string(),
+                    style: ArrowConfig {
+                        color: Color32::from_rgba_unmultiplied(255, 230, 0, 128),
+                        stroke_width: 16.0,
+                        ..ArrowConfig::default()
+                    },
+                },
+            ],
+            recent_colors: Vec::new(),
+        }
+    }
+}
+
+/// 颜色的十六进制表示，不透明时省略透明度：`#rrggbb` 或 `#rrggbbaa`
+pub fn color_to_hex(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+/// 解析 `#rgb`、`#rrggbb`、`#rrggbbaa` 等格式，`#` 可以省略
+pub fn parse_hex_color(text: &str) -> Option<Color32> {
+    let text = text.trim();
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    Color32::from_hex(&format!("#{hex}")).ok()
+}
@@ -1,7 +1,8 @@
 use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
 
 /// 箭头类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ArrowType {
     #[default]
     Single, // 直线箭头 (单向)
@@ -31,7 +32,7 @@ impl ArrowType {
 }
 
 /// 线段类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum LineStyle {
     #[default]
     Solid, // 实线
@@ -61,6 +62,30 @@ impl LineStyle {
             LineStyle::DashDotDot => "Dash-Dot-Dot",
         }
     }
+
+    /// 虚线模式：交替的实线长度和间隔长度，实线长度为 0 表示圆点；实线为 `None`
+    ///
+    /// 线段带圆头，端点各向外延伸 `width / 2`，所以间隔里加上一个线宽，
+    /// 保证可见的空隙与线宽无关。长度单位与 `width` 相同（逻辑坐标）。
+    pub fn dash_pattern(&self, width: f32) -> Option<Vec<f32>> {
+        const DASH: f32 = 12.0;
+        const DASH_GAP: f32 = 6.0;
+        const DOT_GAP: f32 = 4.0;
+        match self {
+            LineStyle::Solid => None,
+            LineStyle::Dashed => Some(vec![DASH, DASH_GAP + width]),
+            LineStyle::Dotted => Some(vec![0.0, width * 2.0]),
+            LineStyle::DashDot => Some(vec![DASH, DOT_GAP + width, 0.0, DOT_GAP + width]),
+            LineStyle::DashDotDot => Some(vec![
+                DASH,
+                DOT_GAP + width,
+                0.0,
+                DOT_GAP + width,
+                0.0,
+                DOT_GAP + width,
+            ]),
+        }
+    }
 }
 
 /// 预设颜色
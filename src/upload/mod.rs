@@ -0,0 +1,321 @@
+mod panel;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ureq::http::{Method, Request};
+
+pub(crate) use panel::uploader_ui;
+
+/// 请求体的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UploadBody {
+    Multipart, // multipart/form-data，文件放在 `file_field` 字段
+    Binary,    // 请求体就是 PNG 数据
+}
+
+/// 从响应中取出链接的方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ResponseUrl {
+    /// 整个响应体
+    Body,
+    /// JSON 路径，例如 `data.link` 或 `files[0].url`
+    Json { path: String },
+    /// 正则表达式及捕获组，0 表示整个匹配
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        group: usize,
+    },
+}
+
+impl ResponseUrl {
+    /// 从响应体中提取链接
+    pub fn extract(&self, body: &str) -> Result<String> {
+        let url = match self {
+            ResponseUrl::Body => body.trim().to_string(),
+            ResponseUrl::Json { path } => {
+                let value: Value = serde_json::from_str(body).context("response is not JSON")?;
+                match json_path(&value, path) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Null) | None => bail!("no `{path}` in response"),
+                    Some(other) => other.to_string(),
+                }
+            }
+            ResponseUrl::Regex { pattern, group } => {
+                let regex = Regex::new(pattern).context("bad URL pattern")?;
+                let captures = regex
+                    .captures(body)
+                    .ok_or_else(|| anyhow!("pattern `{pattern}` did not match the response"))?;
+                captures
+                    .get(*group)
+                    .ok_or_else(|| anyhow!("pattern `{pattern}` has no group {group}"))?
+                    .as_str()
+                    .to_string()
+            }
+        };
+        if url.is_empty() {
+            bail!("empty URL in response");
+        }
+        Ok(url)
+    }
+}
+
+/// 按 `a.b[0].c` 形式的路径取 JSON 值
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, indices) = match part.find('[') {
+            Some(i) => part.split_at(i),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.strip_suffix(']')?.parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// 自定义上传器，字段对应 ShareX 的 `.sxcu` 配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploaderConfig {
+    pub name: String,
+    /// 请求地址，`{filename}` 会被替换为文件名
+    pub url: String,
+    pub method: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: UploadBody,
+    pub file_field: String,
+    /// multipart 中额外的文本字段
+    pub fields: BTreeMap<String, String>,
+    pub response_url: ResponseUrl,
+}
+
+impl Default for UploaderConfig {
+    fn default() -> Self {
+        Self {
+            name: "Custom uploader".to_string(),
+            url: String::new(),
+            method: "POST".to_string(),
+            headers: BTreeMap::new(),
+            body: UploadBody::Multipart,
+            file_field: "file".to_string(),
+            fields: BTreeMap::new(),
+            response_url: ResponseUrl::Body,
+        }
+    }
+}
+
+/// `.sxcu` 文件中用到的字段
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Sxcu {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    request_method: Option<String>,
+    #[serde(rename = "RequestURL")]
+    request_url: String,
+    #[serde(default)]
+    parameters: BTreeMap<String, String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    arguments: BTreeMap<String, String>,
+    #[serde(default)]
+    file_form_name: Option<String>,
+    #[serde(default)]
+    regex_list: Vec<String>,
+    #[serde(default, rename = "URL")]
+    url: Option<String>,
+}
+
+impl UploaderConfig {
+    /// 导入 ShareX 的 `.sxcu` 配置
+    pub fn from_sxcu(text: &str) -> Result<Self> {
+        let sxcu: Sxcu = serde_json::from_str(text).context("invalid .sxcu file")?;
+
+        let body = match sxcu.body.as_deref() {
+            None | Some("MultipartFormData") => UploadBody::Multipart,
+            Some("Binary") => UploadBody::Binary,
+            Some(other) => bail!("unsupported body type {other}"),
+        };
+
+        let mut url = sxcu.request_url;
+        if !sxcu.parameters.is_empty() {
+            let query: Vec<String> = sxcu
+                .parameters
+                .iter()
+                .map(|(k, v)| format!("{}={}", encode_query(k), encode_query(v)))
+                .collect();
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query.join("&"));
+        }
+
+        let response_url = match sxcu.url.as_deref().map(str::trim) {
+            None | Some("") | Some("$response$") | Some("{response}") => ResponseUrl::Body,
+            Some(template) => parse_sxcu_url(template, &sxcu.regex_list)?,
+        };
+
+        let defaults = Self::default();
+        Ok(Self {
+            name: sxcu.name.unwrap_or(defaults.name),
+            url,
+            method: sxcu.request_method.unwrap_or(defaults.method),
+            headers: sxcu.headers,
+            body,
+            file_field: sxcu.file_form_name.unwrap_or(defaults.file_field),
+            fields: sxcu.arguments,
+            response_url,
+        })
+    }
+}
+
+/// 解析 `.sxcu` 中的 URL 模板；只支持单个 json 或 regex 占位符
+fn parse_sxcu_url(template: &str, regex_list: &[String]) -> Result<ResponseUrl> {
+    // 新版写作 `$json:path$`，旧版写作 `{json:path}`
+    let inner = template
+        .strip_prefix('$')
+        .and_then(|t| t.strip_suffix('$'))
+        .or_else(|| template.strip_prefix('{').and_then(|t| t.strip_suffix('}')))
+        .filter(|t| !t.contains(['$', '{', '}']))
+        .ok_or_else(|| anyhow!("unsupported URL template {template}"))?;
+
+    if let Some(path) = inner.strip_prefix("json:") {
+        return Ok(ResponseUrl::Json {
+            path: path.to_string(),
+        });
+    }
+    if let Some(spec) = inner.strip_prefix("regex:") {
+        // `regex:1` 或 `regex:1|2`、`regex:1,2`：第几个表达式（从 1 开始）和捕获组
+        let (index, group) = spec.split_once(['|', ',']).unwrap_or((spec, "0"));
+        let index: usize = index.trim().parse().context("bad regex index")?;
+        let group: usize = group.trim().parse().context("bad regex group")?;
+        let pattern = index
+            .checked_sub(1)
+            .and_then(|i| regex_list.get(i))
+            .ok_or_else(|| anyhow!("no regex #{index} in RegexList"))?;
+        return Ok(ResponseUrl::Regex {
+            pattern: pattern.clone(),
+            group,
+        });
+    }
+    bail!("unsupported URL template {template}")
+}
+
+/// 对查询参数做百分号编码
+fn encode_query(text: &str) -> String {
+    let mut out = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// 上传 PNG 数据，返回响应中的链接
+pub fn upload(config: &UploaderConfig, png: &[u8], file_name: &str) -> Result<String> {
+    if config.url.trim().is_empty() {
+        bail!("no upload URL configured");
+    }
+    let method = Method::from_bytes(config.method.trim().to_uppercase().as_bytes())
+        .with_context(|| format!("bad HTTP method {}", config.method))?;
+
+    let mut request = Request::builder()
+        .method(method.clone())
+        .uri(config.url.replace("{filename}", &encode_query(file_name)));
+    for (name, value) in config.headers.iter().filter(|(name, _)| !name.is_empty()) {
+        request = request.header(name, value.replace("{filename}", file_name));
+    }
+
+    let body = match config.body {
+        UploadBody::Multipart => {
+            let boundary = format!(
+                "----pxshot{}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos()
+            );
+            request = request.header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={boundary}"),
+            );
+            multipart_body(&boundary, config, png, file_name)
+        }
+        UploadBody::Binary => {
+            if !config
+                .headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("content-type"))
+            {
+                request = request.header("Content-Type", "image/png");
+            }
+            png.to_vec()
+        }
+    };
+
+    let agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(60)))
+        .http_status_as_error(false)
+        .build()
+        .new_agent();
+    let mut response = if method == Method::GET {
+        agent.run(request.body(())?)
+    } else {
+        agent.run(request.body(body)?)
+    }
+    .with_context(|| format!("upload to {} failed", config.url))?;
+
+    let status = response.status();
+    let text = response
+        .body_mut()
+        .read_to_string()
+        .context("cannot read upload response")?;
+    if !status.is_success() {
+        let snippet: String = text.trim().chars().take(200).collect();
+        bail!("server returned {status}: {snippet}");
+    }
+    config.response_url.extract(&text)
+}
+
+/// 生成 multipart/form-data 请求体：先是文本字段，最后是文件
+fn multipart_body(boundary: &str, config: &UploaderConfig, png: &[u8], file_name: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, value) in config.fields.iter().filter(|(name, _)| !name.is_empty()) {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{}\r\n",
+                value.replace("{filename}", file_name)
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{file_name}\"\r\nContent-Type: image/png\r\n\r\n",
+            config.file_field
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(png);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{self, Color32};
+
+use super::{ResponseUrl, UploadBody, UploaderConfig};
+
+const METHODS: [&str; 4] = ["POST", "PUT", "PATCH", "GET"];
+
+/// 编辑上传器配置，返回是否有修改
+pub(crate) fn uploader_ui(ui: &mut egui::Ui, config: &mut UploaderConfig) -> bool {
+    let mut changed = false;
+
+    sxcu_import_ui(ui, config, &mut changed);
+    ui.horizontal(|ui| {
+        ui.label("Name");
+        changed |= ui.text_edit_singleline(&mut config.name).changed();
+    });
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("method")
+            .width(70.0)
+            .selected_text(&config.method)
+            .show_ui(ui, |ui| {
+                for method in METHODS {
+                    changed |= ui
+                        .selectable_value(&mut config.method, method.to_string(), method)
+                        .changed();
+                }
+            });
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut config.url).hint_text("https://example.com/upload"),
+            )
+            .on_hover_text("{filename} is replaced with the URL-encoded file name")
+            .changed();
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Body");
+        changed |= ui
+            .selectable_value(&mut config.body, UploadBody::Multipart, "Form data")
+            .changed();
+        changed |= ui
+            .selectable_value(&mut config.body, UploadBody::Binary, "Binary")
+            .changed();
+    });
+    if config.body == UploadBody::Multipart {
+        ui.horizontal(|ui| {
+            ui.label("File field");
+            changed |= ui.text_edit_singleline(&mut config.file_field).changed();
+        });
+        ui.label("Form fields");
+        changed |= map_ui(ui, "fields", &mut config.fields);
+    }
+    ui.label("Headers");
+    changed |= map_ui(ui, "headers", &mut config.headers);
+
+    ui.horizontal(|ui| {
+        ui.label("Link from");
+        let kind = match config.response_url {
+            ResponseUrl::Body => 0,
+            ResponseUrl::Json { .. } => 1,
+            ResponseUrl::Regex { .. } => 2,
+        };
+        for (i, label) in ["Response", "JSON path", "Regex"].into_iter().enumerate() {
+            if ui.selectable_label(kind == i, label).clicked() && kind != i {
+                config.response_url = match i {
+                    0 => ResponseUrl::Body,
+                    1 => ResponseUrl::Json {
+                        path: "url".to_string(),
+                    },
+                    _ => ResponseUrl::Regex {
+                        pattern: "https?://\\S+".to_string(),
+                        group: 0,
+                    },
+                };
+                changed = true;
+            }
+        }
+    });
+    match &mut config.response_url {
+        ResponseUrl::Body => {}
+        ResponseUrl::Json { path } => {
+            ui.horizontal(|ui| {
+                ui.label("Path");
+                changed |= ui
+                    .add(egui::TextEdit::singleline(path).hint_text("data.link"))
+                    .changed();
+            });
+        }
+        ResponseUrl::Regex { pattern, group } => {
+            ui.horizontal(|ui| {
+                ui.label("Pattern");
+                changed |= ui.text_edit_singleline(pattern).changed();
+                ui.label("Group");
+                changed |= ui.add(egui::DragValue::new(group)).changed();
+            });
+        }
+    }
+    changed
+}
+
+/// 从 `.sxcu` 文件导入，错误保存在临时状态里显示
+fn sxcu_import_ui(ui: &mut egui::Ui, config: &mut UploaderConfig, changed: &mut bool) {
+    let path_id = ui.id().with("sxcu_path");
+    let error_id = ui.id().with("sxcu_error");
+    let mut path: String = ui.data(|d| d.get_temp(path_id)).unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        ui.label("Import");
+        ui.add(egui::TextEdit::singleline(&mut path).hint_text("path/to/uploader.sxcu"));
+        if ui.button("Load").clicked() {
+            let result = std::fs::read_to_string(path.trim())
+                .map_err(anyhow::Error::from)
+                .and_then(|text| UploaderConfig::from_sxcu(&text));
+            let error = match result {
+                Ok(imported) => {
+                    *config = imported;
+                    *changed = true;
+                    None
+                }
+                Err(e) => Some(format!("import failed: {e:#}")),
+            };
+            ui.data_mut(|d| d.insert_temp(error_id, error));
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(path_id, path));
+    if let Some(Some(error)) = ui.data(|d| d.get_temp::<Option<String>>(error_id)) {
+        ui.colored_label(Color32::LIGHT_RED, error);
+    }
+}
+
+/// 编辑键值对列表
+///
+/// 编辑时行的顺序保持不变，键在输入框失去焦点时才写回；键重复时不写回并提示。
+fn map_ui(ui: &mut egui::Ui, id: &str, map: &mut BTreeMap<String, String>) -> bool {
+    let state_id = ui.id().with(id).with("rows");
+    // 正在编辑的行，连同写回时的配置；配置在别处被修改（例如导入）时重新读取
+    let mut rows = ui
+        .data(|d| d.get_temp::<(BTreeMap<String, String>, Vec<(String, String)>)>(state_id))
+        .filter(|(saved, _)| saved == map)
+        .map(|(_, rows)| rows)
+        .unwrap_or_else(|| map.clone().into_iter().collect());
+    let mut commit = false;
+    let mut remove = None;
+
+    ui.push_id(id, |ui| {
+        for (i, (key, value)) in rows.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    commit |= ui
+                        .add(egui::TextEdit::singleline(key).desired_width(100.0))
+                        .lost_focus();
+                    commit |= ui.text_edit_singleline(value).changed();
+                    if ui.button("✖").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            });
+        }
+        if ui.button("Add").clicked() && rows.iter().all(|(key, _)| !key.is_empty()) {
+            rows.push(Default::default());
+            commit = true;
+        }
+    });
+    if let Some(i) = remove {
+        rows.remove(i);
+        commit = true;
+    }
+
+    let mut changed = false;
+    let duplicate = rows
+        .iter()
+        .enumerate()
+        .find(|(i, (key, _))| rows[..*i].iter().any(|(other, _)| other == key));
+    match duplicate {
+        Some((_, (key, _))) => {
+            ui.colored_label(Color32::LIGHT_RED, format!("Duplicate name \"{key}\""));
+        }
+        None if commit => {
+            let edited: BTreeMap<String, String> = rows.iter().cloned().collect();
+            if edited != *map {
+                *map = edited;
+                changed = true;
+            }
+        }
+        None => {}
+    }
+    ui.data_mut(|d| d.insert_temp(state_id, (map.clone(), rows)));
+    changed
+}
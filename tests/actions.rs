@@ -0,0 +1,199 @@
+//! 动作链在后台线程按顺序执行
+
+use std::time::{Duration, Instant};
+
+use eframe::egui::Pos2;
+use pxshot::actions::{
+    Action, ActionChain, ActionKind, ActionLog, CaptureInfo, CaptureSource, Prepare, UiStep,
+    run_command, save_to,
+};
+use pxshot::capture::RgbaImage;
+
+fn info() -> CaptureInfo {
+    CaptureInfo {
+        source: CaptureSource::Region,
+        annotated: false,
+        width: 4,
+        height: 3,
+        window_title: None,
+        timestamp: 0,
+        path: None,
+    }
+}
+
+fn command(program: &str, stop_on_failure: bool) -> Action {
+    Action {
+        stop_on_failure,
+        ..Action::new(ActionKind::Command {
+            program: program.to_string(),
+            args: Vec::new(),
+            stdin_png: false,
+        })
+    }
+}
+
+/// 代替界面线程处理请求，直到动作链结束
+fn finish(mut chain: ActionChain, mut on_step: impl FnMut(&UiStep)) -> Option<String> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        while let Some(request) = chain.try_request() {
+            on_step(&request.step);
+            request.reply(Ok("done".to_string()));
+        }
+        if let Some(error) = chain.try_finish() {
+            return error;
+        }
+        assert!(Instant::now() < deadline, "chain did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn start(actions: Vec<Action>, log: &ActionLog) -> ActionChain {
+    let image = RgbaImage::new(4, 3);
+    ActionChain::start(
+        actions,
+        image,
+        info(),
+        Pos2::ZERO,
+        log.clone(),
+        Prepare::default(),
+    )
+}
+
+#[test]
+fn runs_in_order_with_ui_steps() {
+    let log = ActionLog::default();
+    let chain = start(
+        vec![
+            command("true", false),
+            Action::new(ActionKind::Pin),
+            command("false", false),
+            Action::new(ActionKind::Copy),
+        ],
+        &log,
+    );
+    let mut steps = Vec::new();
+    let error = finish(chain, |step| steps.push(matches!(step, UiStep::Pin { .. })));
+    // 没有勾选失败即停止时继续执行
+    assert_eq!(steps, [true, false]);
+    assert!(error.unwrap().starts_with("Run false failed"));
+
+    let results: Vec<bool> = log.reports().iter().map(|r| r.result.is_ok()).collect();
+    assert_eq!(results, [true, true, false, true]);
+}
+
+#[test]
+fn stops_on_failure() {
+    let log = ActionLog::default();
+    let chain = start(
+        vec![command("false", true), Action::new(ActionKind::Copy)],
+        &log,
+    );
+    let mut steps = 0;
+    assert!(finish(chain, |_| steps += 1).is_some());
+    assert_eq!(steps, 0);
+    assert_eq!(log.reports().len(), 1);
+}
+
+/// 压缩后仍然远大于管道缓冲区的图像
+fn noisy_image() -> RgbaImage {
+    RgbaImage::from_fn(400, 400, |x, y| {
+        let v = (x * 7919 + y * 104_729).wrapping_mul(2_654_435_761);
+        image::Rgba(v.to_le_bytes())
+    })
+}
+
+#[test]
+fn command_output_and_stdin_do_not_deadlock() {
+    // 先写满标准输出再读标准输入
+    let args = ["-c", "head -c 1000000 /dev/zero; cat > /dev/null"].map(str::to_string);
+    let result = run_command("sh", &args, Some(&noisy_image()), &info());
+    assert!(result.is_ok(), "{result:?}");
+}
+
+#[test]
+fn command_may_ignore_stdin() {
+    let args = ["-c", "exit 0"].map(str::to_string);
+    let result = run_command("sh", &args, Some(&noisy_image()), &info());
+    assert!(result.is_ok(), "{result:?}");
+}
+
+#[test]
+fn edit_is_skipped_for_edited_images() {
+    let log = ActionLog::default();
+    let info = CaptureInfo {
+        source: CaptureSource::Edit,
+        ..info()
+    };
+    let chain = ActionChain::start(
+        vec![Action::new(ActionKind::Edit)],
+        RgbaImage::new(4, 3),
+        info,
+        Pos2::ZERO,
+        log.clone(),
+        Prepare::default(),
+    );
+    let mut steps = 0;
+    assert_eq!(finish(chain, |_| steps += 1), None);
+    assert_eq!(steps, 0);
+    assert!(log.reports().is_empty());
+}
+
+#[test]
+fn placeholders_cannot_escape_the_folder() {
+    let info = CaptureInfo {
+        window_title: Some("../../evil\n/name".to_string()),
+        ..info()
+    };
+    assert_eq!(
+        info.expand_file_name("{title}-{width}.png"),
+        ".._.._evil__name-4.png"
+    );
+    // 命令参数原样替换
+    assert_eq!(info.expand("{title}"), "../../evil\n/name");
+
+    let dir = std::env::temp_dir().join(format!("pxshot-test-{}-save", std::process::id()));
+    let path = save_to(&dir, "{title}.png", &RgbaImage::new(4, 3), &info).unwrap();
+    assert_eq!(path.parent(), Some(dir.as_path()));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn prepare_saves_file_before_actions() {
+    let dir = std::env::temp_dir().join(format!("pxshot-prepare-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("capture.png");
+    let (reply, response) = std::sync::mpsc::channel();
+    let chain = ActionChain::start(
+        vec![Action::new(ActionKind::Copy)],
+        RgbaImage::new(4, 3),
+        info(),
+        Pos2::ZERO,
+        ActionLog::default(),
+        Prepare {
+            file: Some(file.clone()),
+            reply: Some(reply),
+            ..Prepare::default()
+        },
+    );
+
+    let mut steps = Vec::new();
+    assert_eq!(
+        finish(chain, |step| steps.push(match step {
+            UiStep::Saved(path) => format!("saved {}", path.display()),
+            UiStep::Copy { file, .. } => format!("copy {:?}", file),
+            _ => "other".to_string(),
+        })),
+        None
+    );
+    assert_eq!(
+        steps,
+        [
+            format!("saved {}", file.display()),
+            format!("copy {:?}", Some(&file)),
+        ]
+    );
+    assert_eq!(response.recv().unwrap().path, Some(file.clone()));
+    assert!(file.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,183 @@
+//! 多选、排列和复制粘贴
+
+use eframe::egui::{Color32, Modifiers, Pos2, Rect, Vec2};
+use pxshot::tools::arrow::{Align, ArrowConfig, ArrowDrawer, Distribute, DrawState};
+
+/// 三个水平箭头，左端分别在 x = 10、40、100
+fn drawer() -> ArrowDrawer {
+    let mut drawer = ArrowDrawer::default();
+    let config = ArrowConfig::default();
+    for (x, y) in [(10.0, 20.0), (40.0, 60.0), (100.0, 100.0)] {
+        drawer.start_drawing(Pos2::new(x, y));
+        drawer.finish_drawing(Pos2::new(x + 30.0, y), &config);
+    }
+    drawer
+}
+
+#[test]
+fn shift_click_toggles_selection() {
+    let mut drawer = drawer();
+    assert!(drawer.try_select(Pos2::new(20.0, 20.0), false));
+    assert!(drawer.try_select(Pos2::new(110.0, 100.0), true));
+    assert_eq!(drawer.selection, [0, 2]);
+    assert!(drawer.try_select(Pos2::new(20.0, 20.0), true));
+    assert_eq!(drawer.selection, [2]);
+
+    // 不按 Shift 点击空白处清空选择
+    assert!(!drawer.try_select(Pos2::new(300.0, 300.0), false));
+    assert!(drawer.selection.is_empty());
+    assert_eq!(drawer.state, DrawState::Idle);
+}
+
+#[test]
+fn band_selects_arrows_inside() {
+    let mut drawer = drawer();
+    drawer.start_band(Pos2::new(0.0, 0.0));
+    drawer.update_band(Pos2::new(80.0, 70.0));
+    drawer.finish_band();
+    assert_eq!(drawer.selection, [0, 1]);
+    assert_eq!(drawer.state, DrawState::Selected);
+}
+
+#[test]
+fn group_move_and_delete() {
+    let mut drawer = drawer();
+    drawer.select_all();
+    let hit = drawer.hit_endpoint(Pos2::new(55.0, 60.0)).unwrap();
+    assert_eq!(hit, DrawState::MovingSelection);
+    drawer.start_move(Pos2::new(55.0, 60.0), hit);
+    drawer.update_move(Pos2::new(60.0, 70.0));
+    drawer.finish_move();
+    assert_eq!(drawer.arrows[0].start, Pos2::new(15.0, 30.0));
+    assert_eq!(drawer.arrows[2].end, Pos2::new(135.0, 110.0));
+
+    drawer.selection = vec![0, 2];
+    drawer.delete_selected();
+    assert_eq!(drawer.arrows.len(), 1);
+    assert_eq!(drawer.arrows[0].start, Pos2::new(45.0, 70.0));
+}
+
+#[test]
+fn restyle_only_changes_edited_fields() {
+    let mut drawer = drawer();
+    drawer.arrows[1].stroke_width = 8.0;
+    drawer.selection = vec![0, 1];
+    let before = ArrowConfig::default();
+    let after = ArrowConfig {
+        color: Color32::BLUE,
+        ..before.clone()
+    };
+    drawer.restyle_selected(&before, &after);
+    assert_eq!(drawer.arrows[0].color, Color32::BLUE);
+    assert_eq!(drawer.arrows[1].color, Color32::BLUE);
+    assert_eq!(drawer.arrows[1].stroke_width, 8.0);
+    assert_eq!(drawer.arrows[2].color, Color32::RED);
+}
+
+#[test]
+fn align_to_group_or_selection_bounds() {
+    let mut drawer = drawer();
+    drawer.selection = vec![0, 1, 2];
+    let bounds = Rect::from_min_size(Pos2::ZERO, Vec2::splat(200.0));
+    drawer.align_selected(Align::Left, bounds);
+    for arrow in &drawer.arrows {
+        assert_eq!(arrow.start.x, 10.0);
+    }
+
+    // 单选时对齐到选区
+    drawer.selection = vec![1];
+    drawer.align_selected(Align::Bottom, bounds);
+    assert_eq!(drawer.arrows[1].start.y, 200.0);
+}
+
+#[test]
+fn distribute_by_centers() {
+    let mut drawer = drawer();
+    drawer.selection = vec![0, 1, 2];
+    drawer.distribute_selected(Distribute::Horizontal);
+    let centers: Vec<f32> = drawer
+        .arrows
+        .iter()
+        .map(|a| (a.start.x + a.end.x) / 2.0)
+        .collect();
+    assert_eq!(centers, [25.0, 70.0, 115.0]);
+    // 另一方向不变
+    assert_eq!(drawer.arrows[1].start.y, 60.0);
+}
+
+#[test]
+fn z_order_keeps_selection() {
+    let mut drawer = drawer();
+    drawer.selection = vec![0];
+    drawer.bring_to_front();
+    assert_eq!(drawer.arrows[2].start, Pos2::new(10.0, 20.0));
+    assert_eq!(drawer.selection, [2]);
+
+    drawer.selection = vec![1, 2];
+    drawer.send_to_back();
+    assert_eq!(drawer.arrows[0].start, Pos2::new(100.0, 100.0));
+    assert_eq!(drawer.arrows[1].start, Pos2::new(10.0, 20.0));
+    assert_eq!(drawer.selection, [0, 1]);
+}
+
+#[test]
+fn copy_paste_and_duplicate() {
+    let mut drawer = drawer();
+    drawer.selection = vec![0, 1];
+    let text = drawer.copy_selected().unwrap();
+
+    drawer.paste(&text);
+    assert_eq!(drawer.arrows.len(), 5);
+    assert_eq!(drawer.selection, [3, 4]);
+    assert_eq!(drawer.arrows[3].start, Pos2::new(22.0, 32.0));
+
+    // 再次粘贴继续错开
+    drawer.paste(&text);
+    assert_eq!(drawer.arrows[5].start, Pos2::new(34.0, 44.0));
+
+    // 普通文本不是标注
+    drawer.paste("hello");
+    assert_eq!(drawer.arrows.len(), 7);
+
+    drawer.selection = vec![2];
+    drawer.duplicate_selected();
+    assert_eq!(drawer.arrows.len(), 8);
+    assert_eq!(drawer.arrows[7].start, Pos2::new(112.0, 112.0));
+    assert_eq!(drawer.selection, [7]);
+}
+
+#[test]
+fn shift_press_on_canvas_draws() {
+    let mut drawer = drawer();
+    drawer.try_select(Pos2::new(20.0, 20.0), false);
+
+    // 先按住 Shift 再拖动：绘制锁定角度的新箭头，而不是框选
+    drawer.press(
+        Pos2::new(300.0, 300.0),
+        Pos2::new(300.0, 300.0),
+        Modifiers::SHIFT,
+    );
+    assert_eq!(drawer.state, DrawState::Drawing);
+    assert!(drawer.band_rect().is_none());
+}
+
+#[test]
+fn command_drag_on_canvas_selects() {
+    // Ctrl 拖动框选，Ctrl+Shift 在原有选择上追加
+    let mut drawer = drawer();
+    drawer.try_select(Pos2::new(110.0, 100.0), false);
+    drawer.press(
+        Pos2::ZERO,
+        Pos2::ZERO,
+        Modifiers::COMMAND | Modifiers::SHIFT,
+    );
+    assert_eq!(drawer.state, DrawState::Selecting);
+    drawer.update_band(Pos2::new(50.0, 30.0));
+    drawer.finish_band();
+    assert_eq!(drawer.selection, [0, 2]);
+
+    drawer.press(Pos2::ZERO, Pos2::ZERO, Modifiers::COMMAND);
+    drawer.update_band(Pos2::new(50.0, 30.0));
+    drawer.finish_band();
+    assert_eq!(drawer.selection, [0]);
+}
@@ -0,0 +1,70 @@
+//! 命令行参数解析
+
+use pxshot::capture::RectPx;
+use pxshot::cli::{self, Command};
+use pxshot::ipc::Request;
+use pxshot::mode::CaptureTarget;
+
+fn parse(args: &[&str]) -> anyhow::Result<Command> {
+    cli::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn last_region_needs_interactive_target() {
+    for args in [
+        ["capture", "--last-region", "--full"].as_slice(),
+        &["capture", "--region", "10x10+0+0", "--last-region"],
+    ] {
+        let error = parse(args).unwrap_err().to_string();
+        assert!(error.contains("--last-region"), "{error}");
+    }
+
+    let Command::Capture {
+        target,
+        last_region,
+        ..
+    } = parse(&["capture", "--last-region"]).unwrap()
+    else {
+        panic!("expected a capture command");
+    };
+    assert_eq!(target, CaptureTarget::Interactive);
+    assert!(last_region);
+}
+
+#[test]
+fn ctl_capture_region_needs_position() {
+    assert!(parse(&["ctl", "capture-region", "100x50"]).is_err());
+    assert!(matches!(
+        parse(&["ctl", "capture-region", "100x50+10+20"]).unwrap(),
+        Command::Ctl(Request::CaptureRegion {
+            region: Some(RectPx {
+                x: 10,
+                y: 20,
+                w: 100,
+                h: 50
+            })
+        })
+    ));
+}
+
+#[test]
+fn capture_region_needs_position() {
+    let error = parse(&["capture", "--region", "800x600"])
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("--region needs WxH+X+Y"), "{error}");
+
+    let Command::Capture { target, .. } = parse(&["capture", "--region", "800x600+10+20"]).unwrap()
+    else {
+        panic!("expected a capture command");
+    };
+    assert_eq!(
+        target,
+        CaptureTarget::Region(RectPx {
+            x: 10,
+            y: 20,
+            w: 800,
+            h: 600
+        })
+    );
+}
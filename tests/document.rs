@@ -0,0 +1,71 @@
+//! 项目文件的读写和旧版本兼容
+
+use eframe::egui::Pos2;
+use pxshot::capture::RgbaImage;
+use pxshot::document::{AnnotationDoc, PROJECT_VERSION, Project};
+use pxshot::tools::arrow::{Arrow, ArrowConfig, ArrowPath, Effects};
+
+fn project() -> Project {
+    let arrow = Arrow::new(
+        Pos2::new(2.0, 3.0),
+        Pos2::new(10.0, 8.0),
+        &ArrowConfig::default(),
+    );
+    Project::new(
+        RgbaImage::new(4, 3),
+        AnnotationDoc {
+            ppp: 1.0,
+            arrows: vec![arrow],
+        },
+    )
+}
+
+#[test]
+fn saves_current_version() {
+    let bytes = project().to_bytes().unwrap();
+    let file: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(file["version"], PROJECT_VERSION);
+}
+
+#[test]
+fn loads_version_1_without_paths() {
+    let mut file: serde_json::Value =
+        serde_json::from_slice(&project().to_bytes().unwrap()).unwrap();
+    file["version"] = 1.into();
+    for arrow in file["annotations"]["arrows"].as_array_mut().unwrap() {
+        arrow.as_object_mut().unwrap().remove("path");
+    }
+
+    let loaded = Project::from_bytes(&serde_json::to_vec(&file).unwrap()).unwrap();
+    assert_eq!(loaded.annotations.arrows.len(), 1);
+    assert_eq!(loaded.annotations.arrows[0].path, ArrowPath::Straight);
+    assert_eq!(loaded.annotations.arrows[0].end, Pos2::new(10.0, 8.0));
+}
+
+#[test]
+fn loads_version_1_without_effects() {
+    let mut project = project();
+    project.annotations.arrows[0].effects.outline.enabled = true;
+    project.annotations.arrows[0].effects.shadow.enabled = true;
+    let mut file: serde_json::Value = serde_json::from_slice(&project.to_bytes().unwrap()).unwrap();
+    file["version"] = 1.into();
+    for arrow in file["annotations"]["arrows"].as_array_mut().unwrap() {
+        let arrow = arrow.as_object_mut().unwrap();
+        arrow.remove("path");
+        arrow.remove("effects");
+    }
+
+    let loaded = Project::from_bytes(&serde_json::to_vec(&file).unwrap()).unwrap();
+    let arrow = &loaded.annotations.arrows[0];
+    assert_eq!(arrow.path, ArrowPath::Straight);
+    assert_eq!(arrow.effects, Effects::default());
+    assert_eq!(arrow.start, Pos2::new(2.0, 3.0));
+}
+
+#[test]
+fn rejects_newer_versions() {
+    let mut file: serde_json::Value =
+        serde_json::from_slice(&project().to_bytes().unwrap()).unwrap();
+    file["version"] = (PROJECT_VERSION + 1).into();
+    assert!(Project::from_bytes(&serde_json::to_vec(&file).unwrap()).is_err());
+}
@@ -0,0 +1,414 @@
+//! 标注光栅化的金样图像测试
+//!
+//! 每种箭头类型和缩放比例生成一张样张：行为线段类型，列为预设线宽 × 亚像素偏移；
+//! 曲线/折线路径和描边/投影效果各另有一张样张。
+//! 结果与 `tests/golden/` 中的参考 PNG 做感知比较，失败时在 target 目录写出
+//! 实际结果和差异图。设置 `PXSHOT_BLESS=1` 运行可重新生成参考图像。
+//!
+//! 每个编译进来的光栅化后端各有一套参考图像；启用 `skia` feature 时还会
+//! 把 tiny-skia 的结果与基础实现对比。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eframe::egui::{Color32, Pos2, Rect};
+use image::{Rgba, RgbaImage};
+use pxshot::tools::arrow::{
+    Arrow, ArrowConfig, ArrowDrawer, ArrowPath, ArrowType, Effects, Geometry, LineStyle,
+    PRESET_SIZES, Rasterizer,
+};
+
+/// 每个格子的画布大小（像素）
+const CELL_W: u32 = 192;
+const CELL_H: u32 = 144;
+/// 测试的缩放比例
+const SCALES: &[f32] = &[1.0, 1.5, 2.0];
+/// 画布左上角的逻辑坐标，第二项用来覆盖亚像素位置
+const OFFSETS: &[(f32, f32)] = &[(0.0, 0.0), (-0.37, -0.61)];
+/// 箭头的逻辑坐标
+const START: Pos2 = Pos2::new(10.0, 10.0);
+const END: Pos2 = Pos2::new(84.0, 58.0);
+
+/// YIQ 色差阈值（0..1），与 pixelmatch 的默认值相同
+const THRESHOLD: f32 = 0.1;
+/// 允许超过阈值的像素比例
+const MAX_MISMATCH_RATIO: f32 = 0.001;
+
+const BLESS_VAR: &str = "PXSHOT_BLESS";
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+/// 固定背景：左半白色、右半深灰，方便看出抗锯齿边缘在两种底色上的效果
+fn canvas() -> RgbaImage {
+    RgbaImage::from_fn(CELL_W, CELL_H, |x, _| {
+        if x < CELL_W / 2 {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([64, 64, 64, 255])
+        }
+    })
+}
+
+fn arrow(arrow_type: ArrowType, line_style: LineStyle, stroke_width: f32) -> Arrow {
+    let config = ArrowConfig {
+        arrow_type,
+        line_style,
+        color: Color32::RED,
+        stroke_width,
+        ..ArrowConfig::default()
+    };
+    Arrow::new(START, END, &config)
+}
+
+/// 一种箭头类型在某个缩放比例下的样张
+fn render_sheet(arrow_type: ArrowType, ppp: f32, rasterizer: Rasterizer) -> RgbaImage {
+    let columns = (PRESET_SIZES.len() * OFFSETS.len()) as u32;
+    let rows = LineStyle::all().len() as u32;
+    let mut sheet = RgbaImage::new(CELL_W * columns, CELL_H * rows);
+
+    for (row, &style) in LineStyle::all().iter().enumerate() {
+        let mut column = 0;
+        for &width in PRESET_SIZES {
+            for &(ox, oy) in OFFSETS {
+                let mut cell = canvas();
+                arrow(arrow_type, style, width).render_to_image(&mut cell, ox, oy, ppp, rasterizer);
+                image::imageops::replace(
+                    &mut sheet,
+                    &cell,
+                    (column * CELL_W) as i64,
+                    (row as u32 * CELL_H) as i64,
+                );
+                column += 1;
+            }
+        }
+    }
+    sheet
+}
+
+/// YIQ 空间的色差，归一化到 0..1（参考 pixelmatch）
+fn color_delta(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+    // 先与白色背景混合，透明度差异也会体现出来
+    let blend = |c: Rgba<u8>| {
+        let alpha = c[3] as f32 / 255.0;
+        [0, 1, 2].map(|i| 255.0 + (c[i] as f32 - 255.0) * alpha)
+    };
+    let [r1, g1, b1] = blend(a);
+    let [r2, g2, b2] = blend(b);
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+
+    let y = dr * 0.298_895_3 + dg * 0.586_622_5 + db * 0.114_482_2;
+    let i = dr * 0.595_978 - dg * 0.274_176_3 - db * 0.321_801_7;
+    let q = dr * 0.211_470_2 - dg * 0.522_617_2 + db * 0.311_147;
+    // 35215 是 YIQ 色差的最大值
+    (0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / 35215.0
+}
+
+/// 比较结果：超过阈值的像素数和差异图
+fn compare(actual: &RgbaImage, expected: &RgbaImage, threshold: f32) -> (usize, RgbaImage) {
+    let mut mismatched = 0;
+    let diff = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let a = *actual.get_pixel(x, y);
+        let e = *expected.get_pixel(x, y);
+        if color_delta(a, e) > threshold * threshold {
+            mismatched += 1;
+            Rgba([255, 0, 255, 255])
+        } else {
+            // 相同的像素淡化显示作为背景
+            let luma = (a[0] as u32 * 299 + a[1] as u32 * 587 + a[2] as u32 * 114) / 1000;
+            let faded = (255 - (255 - luma) / 4) as u8;
+            Rgba([faded, faded, faded, 255])
+        }
+    });
+    (mismatched, diff)
+}
+
+/// 与参考图像比较；`PXSHOT_BLESS` 已设置时改为写入参考图像
+fn check_golden(name: &str, actual: &RgbaImage) -> Result<(), String> {
+    let golden = golden_dir().join(format!("{name}.png"));
+    if std::env::var_os(BLESS_VAR).is_some() {
+        fs::create_dir_all(golden_dir()).map_err(|e| e.to_string())?;
+        actual.save(&golden).map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let out = output_dir();
+    fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+    let actual_path = out.join(format!("{name}.actual.png"));
+
+    let expected = match image::open(&golden) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            let _ = actual.save(&actual_path);
+            return Err(format!(
+                "{name}: cannot read {} ({e}); run with {BLESS_VAR}=1 to create it",
+                golden.display()
+            ));
+        }
+    };
+    if expected.dimensions() != actual.dimensions() {
+        let _ = actual.save(&actual_path);
+        return Err(format!(
+            "{name}: size {:?} differs from golden {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+
+    let (mismatched, diff) = compare(actual, &expected, THRESHOLD);
+    let total = (actual.width() * actual.height()) as usize;
+    if mismatched as f32 > total as f32 * MAX_MISMATCH_RATIO {
+        let diff_path = out.join(format!("{name}.diff.png"));
+        let _ = actual.save(&actual_path);
+        let _ = diff.save(&diff_path);
+        return Err(format!(
+            "{name}: {mismatched} of {total} pixels differ; see {} and {}",
+            actual_path.display(),
+            diff_path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn file_name(arrow_type: ArrowType, ppp: f32, rasterizer: Rasterizer) -> String {
+    let suffix = match rasterizer {
+        Rasterizer::Basic => "",
+        Rasterizer::Skia => "-skia",
+    };
+    format!(
+        "arrow-{}-{}x{suffix}",
+        arrow_type.name().to_lowercase(),
+        format!("{ppp}").replace('.', "_")
+    )
+}
+
+/// 检查一种箭头类型的所有样张，汇总全部失败后再报错
+fn check_arrow_type(arrow_type: ArrowType) {
+    let mut failures = Vec::new();
+    for &rasterizer in Rasterizer::available() {
+        for &ppp in SCALES {
+            let sheet = render_sheet(arrow_type, ppp, rasterizer);
+            if let Err(e) = check_golden(&file_name(arrow_type, ppp, rasterizer), &sheet) {
+                failures.push(e);
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn golden_single() {
+    check_arrow_type(ArrowType::Single);
+}
+
+#[test]
+fn golden_double() {
+    check_arrow_type(ArrowType::Double);
+}
+
+#[test]
+fn golden_hollow() {
+    check_arrow_type(ArrowType::Hollow);
+}
+
+#[test]
+fn golden_filled() {
+    check_arrow_type(ArrowType::Filled);
+}
+
+/// 效果样张：行为描边、投影、两者同时，列为箭头类型
+fn render_effects_sheet(ppp: f32, rasterizer: Rasterizer) -> RgbaImage {
+    let mut outline = Effects::default();
+    outline.outline.enabled = true;
+    let mut shadow = Effects::default();
+    shadow.shadow.enabled = true;
+    let both = Effects {
+        outline: outline.outline,
+        shadow: shadow.shadow,
+    };
+
+    let rows = [outline, shadow, both];
+    let columns = ArrowType::all();
+    let mut sheet = RgbaImage::new(CELL_W * columns.len() as u32, CELL_H * rows.len() as u32);
+    for (row, effects) in rows.iter().enumerate() {
+        for (column, &arrow_type) in columns.iter().enumerate() {
+            let mut arrow = arrow(arrow_type, LineStyle::Dashed, 6.0);
+            arrow.effects = *effects;
+            let mut cell = canvas();
+            arrow.render_to_image(&mut cell, -0.37, -0.61, ppp, rasterizer);
+            image::imageops::replace(
+                &mut sheet,
+                &cell,
+                (column as u32 * CELL_W) as i64,
+                (row as u32 * CELL_H) as i64,
+            );
+        }
+    }
+    sheet
+}
+
+/// 路径样张：行为曲线、折线，列为箭头类型 × 实线/虚线
+fn render_paths_sheet(ppp: f32, rasterizer: Rasterizer) -> RgbaImage {
+    let paths = [
+        ArrowPath::Curve {
+            control: Pos2::new(84.0, 4.0),
+        },
+        ArrowPath::Polyline {
+            points: vec![Pos2::new(40.0, 10.0), Pos2::new(40.0, 58.0)],
+        },
+    ];
+    let styles = [LineStyle::Solid, LineStyle::Dashed];
+    let columns = (ArrowType::all().len() * styles.len()) as u32;
+    let mut sheet = RgbaImage::new(CELL_W * columns, CELL_H * paths.len() as u32);
+    for (row, path) in paths.iter().enumerate() {
+        let mut column = 0;
+        for &arrow_type in ArrowType::all() {
+            for &style in &styles {
+                let mut arrow = arrow(arrow_type, style, 6.0);
+                arrow.path = path.clone();
+                let mut cell = canvas();
+                arrow.render_to_image(&mut cell, -0.37, -0.61, ppp, rasterizer);
+                image::imageops::replace(
+                    &mut sheet,
+                    &cell,
+                    (column * CELL_W) as i64,
+                    (row as u32 * CELL_H) as i64,
+                );
+                column += 1;
+            }
+        }
+    }
+    sheet
+}
+
+#[test]
+fn golden_paths() {
+    let mut failures = Vec::new();
+    for &rasterizer in Rasterizer::available() {
+        for &ppp in SCALES {
+            let name = file_name(ArrowType::Single, ppp, rasterizer).replace("single", "paths");
+            if let Err(e) = check_golden(&name, &render_paths_sheet(ppp, rasterizer)) {
+                failures.push(e);
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn golden_effects() {
+    let mut failures = Vec::new();
+    for &rasterizer in Rasterizer::available() {
+        for &ppp in SCALES {
+            let name = file_name(ArrowType::Single, ppp, rasterizer).replace("single", "effects");
+            if let Err(e) = check_golden(&name, &render_effects_sheet(ppp, rasterizer)) {
+                failures.push(e);
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// `render_all_to_image` 与逐个渲染的结果相同
+#[test]
+fn drawer_renders_every_arrow() {
+    let mut drawer = ArrowDrawer::default();
+    for (i, &arrow_type) in ArrowType::all().iter().enumerate() {
+        let mut arrow = arrow(arrow_type, LineStyle::all()[i], PRESET_SIZES[i % 2]);
+        arrow.start.y += i as f32 * 12.0;
+        arrow.end.y += i as f32 * 12.0;
+        drawer.arrows.push(arrow);
+    }
+
+    let mut expected = canvas();
+    for arrow in &drawer.arrows {
+        arrow.render_to_image(&mut expected, -0.37, -0.61, 1.5, Rasterizer::Basic);
+    }
+    let mut actual = canvas();
+    drawer.render_all_to_image(&mut actual, -0.37, -0.61, 1.5, Rasterizer::Basic);
+
+    assert_eq!(compare(&actual, &expected, THRESHOLD).0, 0);
+}
+
+/// tiny-skia 与基础实现画出的形状一致，只在抗锯齿边缘上有差别
+#[cfg(feature = "skia")]
+#[test]
+fn skia_matches_basic() {
+    // 两者都在 sRGB 空间混合，但基础实现把顶点取整到像素，边缘会有半个像素的出入；
+    // 只统计明显的差异，并允许其占比略高于金样比较
+    const EDGE_THRESHOLD: f32 = 0.5;
+    const MAX_EDGE_RATIO: f32 = 0.01;
+
+    let mut failures = Vec::new();
+    for &arrow_type in ArrowType::all() {
+        for &ppp in SCALES {
+            let basic = render_sheet(arrow_type, ppp, Rasterizer::Basic);
+            let skia = render_sheet(arrow_type, ppp, Rasterizer::Skia);
+            let (mismatched, diff) = compare(&skia, &basic, EDGE_THRESHOLD);
+            let total = (basic.width() * basic.height()) as f32;
+            if mismatched as f32 > total * MAX_EDGE_RATIO {
+                let name = format!(
+                    "{}-skia-vs-basic",
+                    file_name(arrow_type, ppp, Rasterizer::Basic)
+                );
+                let path = output_dir().join(format!("{name}.diff.png"));
+                let _ = fs::create_dir_all(output_dir());
+                let _ = diff.save(&path);
+                failures.push(format!(
+                    "{name}: {mismatched} pixels differ; see {}",
+                    path.display()
+                ));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// 半透明颜色只混合一次：线条、箭头、圆头的重叠处不会比单层更深
+#[test]
+fn translucent_overlaps_blend_once() {
+    let color = Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+    let white = || RgbaImage::from_pixel(CELL_W, CELL_H, Rgba([255, 255, 255, 255]));
+    let full = Rect::from_min_max(Pos2::ZERO, Pos2::new(CELL_W as f32, CELL_H as f32));
+
+    let mut failures = Vec::new();
+    for &rasterizer in Rasterizer::available() {
+        // 完全覆盖一次的结果作为下限
+        let mut reference = white();
+        let layer = Geometry {
+            polygons: vec![vec![
+                full.left_top(),
+                full.right_top(),
+                full.right_bottom(),
+                full.left_bottom(),
+            ]],
+            segments: Vec::new(),
+        };
+        rasterizer.rasterize(&layer, &mut reference, color);
+        let floor = reference.get_pixel(CELL_W / 2, CELL_H / 2)[1];
+
+        for &arrow_type in ArrowType::all() {
+            for &style in LineStyle::all() {
+                let mut arrow = arrow(arrow_type, style, 12.0);
+                arrow.color = color;
+                let mut img = white();
+                arrow.render_to_image(&mut img, -0.37, -0.61, 1.5, rasterizer);
+                let darkest = img.pixels().map(|p| p[1]).min().unwrap_or(255);
+                if darkest < floor {
+                    failures.push(format!(
+                        "{} {:?} {:?}: darkest {darkest} < {floor}",
+                        rasterizer.name(),
+                        arrow_type,
+                        style
+                    ));
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
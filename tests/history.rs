@@ -0,0 +1,114 @@
+//! 修改已有标注的样式和撤销/重做
+
+use eframe::egui::{Color32, Pos2};
+use pxshot::tools::arrow::{
+    ArrowConfig, ArrowDrawer, ArrowPath, ArrowToolPanel, ArrowType, DrawState, PathKind,
+};
+
+fn drawer() -> ArrowDrawer {
+    let mut drawer = ArrowDrawer::default();
+    let config = ArrowConfig::default();
+    for y in [20.0, 60.0] {
+        drawer.start_drawing(Pos2::new(10.0, y));
+        drawer.finish_drawing(Pos2::new(110.0, y), &config);
+    }
+    drawer
+}
+
+#[test]
+fn panel_shows_selected_style() {
+    let mut drawer = drawer();
+    drawer.arrows[1].color = Color32::BLUE;
+    drawer.arrows[1].stroke_width = 6.0;
+    assert!(drawer.try_select(Pos2::new(50.0, 60.0), false));
+
+    let mut panel = ArrowToolPanel::default();
+    panel.editing = drawer.selected_style();
+    assert_eq!(panel.style().color, Color32::BLUE);
+    assert_eq!(panel.style().stroke_width, 6.0);
+    // 新箭头的配置不受影响
+    assert_eq!(panel.config.color, Color32::RED);
+
+    drawer.deselect();
+    panel.editing = drawer.selected_style();
+    assert_eq!(panel.style().color, Color32::RED);
+}
+
+#[test]
+fn restyle_is_undoable() {
+    let mut drawer = drawer();
+    drawer.selection = vec![0];
+    drawer.state = DrawState::Selected;
+    let before = drawer.selected_style().unwrap();
+    let after = ArrowConfig {
+        arrow_type: ArrowType::Double,
+        path_kind: PathKind::Curve,
+        ..before.clone()
+    };
+    drawer.restyle_selected(&before, &after);
+    assert_eq!(drawer.arrows[0].arrow_type, ArrowType::Double);
+    assert!(matches!(drawer.arrows[0].path, ArrowPath::Curve { .. }));
+
+    drawer.undo();
+    assert_eq!(drawer.arrows[0].arrow_type, ArrowType::Single);
+    assert_eq!(drawer.arrows[0].path, ArrowPath::Straight);
+    // 选择保留
+    assert_eq!(drawer.selection, [0]);
+
+    drawer.redo();
+    assert_eq!(drawer.arrows[0].arrow_type, ArrowType::Double);
+}
+
+#[test]
+fn continuous_restyle_is_one_step() {
+    let mut drawer = drawer();
+    drawer.selection = vec![1];
+    drawer.state = DrawState::Selected;
+    for width in [5.0, 6.0, 7.0] {
+        let before = drawer.selected_style().unwrap();
+        let after = ArrowConfig {
+            stroke_width: width,
+            ..before.clone()
+        };
+        drawer.restyle_selected(&before, &after);
+    }
+    drawer.seal_history();
+    assert_eq!(drawer.arrows[1].stroke_width, 7.0);
+
+    drawer.undo();
+    assert_eq!(drawer.arrows[1].stroke_width, 4.0);
+    // 再撤销就是第二个箭头的创建
+    drawer.undo();
+    assert_eq!(drawer.arrows.len(), 1);
+    assert!(drawer.selection.is_empty());
+    assert_eq!(drawer.state, DrawState::Idle);
+}
+
+#[test]
+fn edits_are_recorded() {
+    let mut drawer = drawer();
+    drawer.select_all();
+    drawer.start_move(Pos2::new(50.0, 20.0), DrawState::MovingSelection);
+    drawer.update_move(Pos2::new(60.0, 20.0));
+    drawer.update_move(Pos2::new(70.0, 30.0));
+    drawer.finish_move();
+    drawer.delete_selected();
+    assert!(drawer.arrows.is_empty());
+
+    drawer.undo();
+    assert_eq!(drawer.arrows[0].start, Pos2::new(30.0, 30.0));
+    drawer.undo();
+    assert_eq!(drawer.arrows[0].start, Pos2::new(10.0, 20.0));
+
+    // 新的修改清空重做
+    drawer.selection = vec![0];
+    drawer.duplicate_selected();
+    assert!(!drawer.can_redo());
+    drawer.undo();
+    assert_eq!(drawer.arrows.len(), 2);
+    assert!(drawer.can_redo());
+
+    // 加载新的标注后历史清空
+    drawer.clear();
+    assert!(!drawer.can_undo());
+}
@@ -0,0 +1,50 @@
+//! 截图历史的磁盘存储
+
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use pxshot::capture::{RectPx, RgbaImage};
+use pxshot::document::AnnotationDoc;
+use pxshot::history::{CaptureMeta, History, format_timestamp};
+
+#[test]
+fn open_removes_abandoned_entries() {
+    let dir = std::env::temp_dir().join(format!("pxshot-history-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let image = RgbaImage::new(4, 3);
+    let rect = RectPx {
+        x: 0,
+        y: 0,
+        w: 4,
+        h: 3,
+    };
+
+    let mut history = History::open(dir.clone()).unwrap();
+    history
+        .add(
+            &image,
+            &image,
+            &AnnotationDoc::default(),
+            CaptureMeta::new(rect, &image),
+        )
+        .unwrap();
+    // 很久以前分配但没有写完的记录，以及另一个实例正在写入的记录
+    let abandoned = history.reserve().unwrap();
+    fs::File::open(&abandoned)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(3600))
+        .unwrap();
+    let writing = history.reserve().unwrap();
+
+    let history = History::open(dir.clone()).unwrap();
+    assert_eq!(history.entries().len(), 1);
+    assert!(!abandoned.exists());
+    assert!(writing.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn timestamps_are_labelled_utc() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20 UTC");
+}
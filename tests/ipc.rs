@@ -0,0 +1,133 @@
+//! 控制 socket 的协议测试：在进程内启动服务端，用客户端函数收发命令
+
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use pxshot::capture::RectPx;
+use pxshot::ipc::{IpcServer, Request, Response, send};
+
+fn socket_path() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("pxshot-test-{}-{n}.sock", std::process::id()))
+}
+
+/// 在后台线程中按固定规则回复，模拟界面线程
+fn serve(server: IpcServer, count: usize) -> thread::JoinHandle<Vec<Request>> {
+    thread::spawn(move || {
+        let mut seen = Vec::new();
+        while seen.len() < count {
+            let Some(call) = server.recv_timeout(Duration::from_secs(5)) else {
+                break;
+            };
+            let response = match &call.request {
+                Request::CaptureFull => Response::path("/tmp/full.png".into()),
+                Request::CaptureRegion { region: Some(_) } => {
+                    Response::path("/tmp/region.png".into())
+                }
+                Request::GetLast => Response::error("nothing captured yet"),
+                _ => Response::ok(),
+            };
+            seen.push(call.request.clone());
+            call.respond(response);
+        }
+        seen
+    })
+}
+
+#[test]
+fn round_trip_commands() {
+    let path = socket_path();
+    let handler = serve(IpcServer::bind(&path).unwrap(), 4);
+
+    let full = send(&path, &Request::CaptureFull).unwrap();
+    assert_eq!(full, Response::path("/tmp/full.png".into()));
+
+    let region = Request::CaptureRegion {
+        region: Some(RectPx {
+            x: 10,
+            y: 20,
+            w: 300,
+            h: 200,
+        }),
+    };
+    let response = send(&path, &region).unwrap();
+    assert_eq!(response.path, Some(PathBuf::from("/tmp/region.png")));
+
+    let last = send(&path, &Request::GetLast).unwrap();
+    assert!(!last.ok);
+    assert_eq!(last.error.as_deref(), Some("nothing captured yet"));
+
+    assert!(send(&path, &Request::Quit).unwrap().ok);
+
+    let seen = handler.join().unwrap();
+    assert_eq!(seen[1], region);
+    assert_eq!(seen[3], Request::Quit);
+}
+
+#[test]
+fn wire_format_is_tagged_json_lines() {
+    let path = socket_path();
+    let handler = serve(IpcServer::bind(&path).unwrap(), 2);
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+
+    // 同一连接上可以连续发送多条命令
+    stream
+        .write_all(b"{\"cmd\":\"pin\",\"path\":\"/tmp/a.png\"}\n{\"cmd\":\"capture-full\"}\n")
+        .unwrap();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line.trim(), r#"{"ok":true}"#);
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line.trim(), r#"{"ok":true,"path":"/tmp/full.png"}"#);
+
+    // 无法解析的命令直接由服务端回复错误，不会交给界面线程
+    stream.write_all(b"{\"cmd\":\"explode\"}\n").unwrap();
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    let response: Response = serde_json::from_str(&line).unwrap();
+    assert!(!response.ok);
+    assert!(response.error.unwrap().starts_with("bad request"));
+
+    let seen = handler.join().unwrap();
+    assert_eq!(
+        seen[0],
+        Request::Pin {
+            path: Some("/tmp/a.png".into())
+        }
+    );
+}
+
+#[test]
+fn stale_socket_is_replaced_and_live_one_is_refused() {
+    let path = socket_path();
+    {
+        let _first = IpcServer::bind(&path).unwrap();
+        let err = IpcServer::bind(&path).err().expect("second bind must fail");
+        assert!(err.to_string().contains("another instance"));
+    }
+    // 服务端退出时删除 socket
+    assert!(!path.exists());
+
+    // 残留的 socket 文件（没有进程在监听）会被替换
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let handler = serve(IpcServer::bind(&path).unwrap(), 1);
+    assert!(send(&path, &Request::Quit).unwrap().ok);
+    handler.join().unwrap();
+}
+
+#[test]
+fn client_reports_missing_instance() {
+    let err = send(&socket_path(), &Request::GetLast).unwrap_err();
+    assert!(err.to_string().contains("no pxshot instance"));
+}
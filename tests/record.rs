@@ -0,0 +1,72 @@
+//! 录屏的逐帧编码
+
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+use pxshot::capture::RgbaImage;
+use pxshot::record::{Frame, FrameEncoder, RecordConfig, RecordFormat};
+
+fn frames() -> Vec<Frame> {
+    (0..3u8)
+        .map(|i| Frame {
+            image: RgbaImage::from_pixel(8, 6, image::Rgba([i * 80, 0, 0, 255])),
+            delay: Duration::from_millis(100 * (u64::from(i) + 1)),
+        })
+        .collect()
+}
+
+fn encode(format: RecordFormat, name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("pxshot-test-{}-{name}", std::process::id()));
+    let config = RecordConfig {
+        format,
+        ..RecordConfig::default()
+    };
+    let mut encoder = FrameEncoder::new(&config, &path, (8, 6)).unwrap();
+    for frame in frames() {
+        encoder.push(&frame).unwrap();
+    }
+    encoder.finish().unwrap();
+    path
+}
+
+#[test]
+fn gif_is_streamed() {
+    let path = encode(RecordFormat::Gif, "stream.gif");
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+    let mut delays = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+    assert_eq!(delays, [10, 20, 30]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn apng_is_spilled_then_encoded() {
+    let path = encode(RecordFormat::Apng, "stream.png");
+    let decoder = png::Decoder::new(BufReader::new(File::open(&path).unwrap()));
+    let mut reader = decoder.read_info().unwrap();
+    let animation = reader.info().animation_control().unwrap();
+    assert_eq!(animation.num_frames, 3);
+
+    let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+    let mut reds = Vec::new();
+    for _ in 0..3 {
+        reader.next_frame(&mut buf).unwrap();
+        reds.push(buf[0]);
+    }
+    assert_eq!(reds, [0, 80, 160]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn abort_removes_output() {
+    let path = std::env::temp_dir().join(format!("pxshot-test-{}-abort.gif", std::process::id()));
+    let mut encoder = FrameEncoder::new(&RecordConfig::default(), &path, (8, 6)).unwrap();
+    encoder.push(&frames()[0]).unwrap();
+    encoder.abort(&path);
+    assert!(!path.exists());
+}
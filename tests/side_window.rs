@@ -0,0 +1,42 @@
+//! 辅助窗口放在选区外
+
+use eframe::egui::{Pos2, Rect, Vec2};
+use pxshot::overlay::side_window_placement;
+
+const MONITOR: Vec2 = Vec2::new(1920.0, 1080.0);
+const SIZE: Vec2 = Vec2::new(220.0, 110.0);
+
+fn place(min: (f32, f32), max: (f32, f32)) -> Option<Rect> {
+    let rect = Rect::from_min_max(Pos2::new(min.0, min.1), Pos2::new(max.0, max.1));
+    let pos = side_window_placement(rect, SIZE, MONITOR)?;
+    let window = Rect::from_min_size(pos, SIZE);
+    assert!(!window.intersects(rect));
+    assert!(Rect::from_min_size(Pos2::ZERO, MONITOR).contains_rect(window));
+    Some(window)
+}
+
+#[test]
+fn tries_right_left_below_above() {
+    // 右侧
+    let window = place((100.0, 100.0), (500.0, 400.0)).unwrap();
+    assert_eq!(window.min, Pos2::new(516.0, 100.0));
+    // 右侧放不下时放左侧
+    let window = place((1000.0, 100.0), (1800.0, 400.0)).unwrap();
+    assert_eq!(window.max.x, 984.0);
+    // 左右都放不下时放下方
+    let window = place((100.0, 100.0), (1800.0, 400.0)).unwrap();
+    assert_eq!(window.min, Pos2::new(100.0, 416.0));
+    // 只剩上方
+    let window = place((100.0, 500.0), (1800.0, 1000.0)).unwrap();
+    assert_eq!(window.max.y, 484.0);
+    // 贴近屏幕底部时向上挪，仍然在屏幕内
+    let window = place((100.0, 1000.0), (500.0, 1070.0)).unwrap();
+    assert_eq!(window.max.y, MONITOR.y);
+}
+
+#[test]
+fn full_screen_has_no_place() {
+    assert_eq!(place((0.0, 0.0), (1920.0, 1080.0)), None);
+    // 四周都不够宽
+    assert_eq!(place((100.0, 50.0), (1820.0, 1030.0)), None);
+}
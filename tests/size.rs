@@ -0,0 +1,203 @@
+//! 选区尺寸输入、自定义比例和锁定比例的调整
+
+use eframe::egui::{Pos2, Rect, Vec2, pos2, vec2};
+use pxshot::overlay::{
+    AspectRatio, HitZone, SizeInput, lock_aspect, parse_aspect_input, parse_size_input,
+    rect_from_size_input,
+};
+
+fn size(w: u32, h: u32, pos: Option<(u32, u32)>) -> Option<SizeInput> {
+    Some(SizeInput { w, h, pos })
+}
+
+fn rect(min: (f32, f32), max: (f32, f32)) -> Rect {
+    Rect::from_min_max(pos2(min.0, min.1), pos2(max.0, max.1))
+}
+
+#[test]
+fn parses_size_forms() {
+    assert_eq!(parse_size_input("800×600"), size(800, 600, None));
+    assert_eq!(parse_size_input("800x600"), size(800, 600, None));
+    assert_eq!(parse_size_input(" 800 X 600 "), size(800, 600, None));
+    assert_eq!(parse_size_input("800*600"), size(800, 600, None));
+    assert_eq!(
+        parse_size_input("800x600+10+20"),
+        size(800, 600, Some((10, 20)))
+    );
+    assert_eq!(
+        parse_size_input("800x600@10,20"),
+        size(800, 600, Some((10, 20)))
+    );
+    assert_eq!(
+        parse_size_input("800×600 + 0 + 0"),
+        size(800, 600, Some((0, 0)))
+    );
+}
+
+#[test]
+fn rejects_invalid_sizes() {
+    for text in [
+        "",
+        "abc",
+        "800",
+        "800x",
+        "x600",
+        "800xabc",
+        "0x600",
+        "800x0",
+        "-800x600",
+        "800x-600",
+        "800x600+10",
+        "800x600+1+2+3",
+        "800x600+-1+2",
+        "800x600@10",
+        "800x600@a,b",
+        "800x600#10,20",
+    ] {
+        assert_eq!(parse_size_input(text), None, "{text:?}");
+    }
+}
+
+#[test]
+fn parses_aspect_ratios() {
+    assert_eq!(
+        parse_aspect_input("16:9"),
+        Some(AspectRatio::Custom(16.0, 9.0))
+    );
+    assert_eq!(
+        parse_aspect_input(" 4 : 3 "),
+        Some(AspectRatio::Custom(4.0, 3.0))
+    );
+    assert_eq!(
+        parse_aspect_input("1.85:1"),
+        Some(AspectRatio::Custom(1.85, 1.0))
+    );
+    assert_eq!(
+        parse_aspect_input("16:9").and_then(|a| a.ratio()),
+        Some(16.0 / 9.0)
+    );
+
+    for text in [
+        "", "16", "16x9", "a:b", "16:", "0:1", "16:0", "-16:9", "16:-9",
+    ] {
+        assert_eq!(parse_aspect_input(text), None, "{text:?}");
+    }
+}
+
+#[test]
+fn lock_aspect_keeps_the_drag_direction() {
+    let anchor = Pos2::ZERO;
+    // 宽度为主时按宽度算高度，反之按高度算宽度
+    assert_eq!(lock_aspect(anchor, pos2(40.0, 5.0), 2.0), pos2(40.0, 20.0));
+    assert_eq!(lock_aspect(anchor, pos2(10.0, 30.0), 2.0), pos2(60.0, 30.0));
+    // 四个方向都保持原来的符号
+    assert_eq!(
+        lock_aspect(anchor, pos2(-30.0, 10.0), 2.0),
+        pos2(-30.0, 15.0)
+    );
+    assert_eq!(
+        lock_aspect(anchor, pos2(10.0, -30.0), 2.0),
+        pos2(60.0, -30.0)
+    );
+    assert_eq!(
+        lock_aspect(anchor, pos2(-40.0, -40.0), 2.0),
+        pos2(-80.0, -40.0)
+    );
+}
+
+#[test]
+fn locked_drag_for_each_zone() {
+    let screen = rect((0.0, 0.0), (1000.0, 1000.0));
+    // 200×100，比例 2:1
+    let start = rect((100.0, 100.0), (300.0, 200.0));
+    let cases = [
+        // 角以对角为锚点
+        (
+            HitZone::TopLeft,
+            vec2(-40.0, -10.0),
+            rect((60.0, 80.0), (300.0, 200.0)),
+        ),
+        (
+            HitZone::TopRight,
+            vec2(40.0, 0.0),
+            rect((100.0, 80.0), (340.0, 200.0)),
+        ),
+        (
+            HitZone::BottomLeft,
+            vec2(0.0, 30.0),
+            rect((40.0, 100.0), (300.0, 230.0)),
+        ),
+        (
+            HitZone::BottomRight,
+            vec2(20.0, 20.0),
+            rect((100.0, 100.0), (340.0, 220.0)),
+        ),
+        // 边以对边中点为锚点
+        (
+            HitZone::Top,
+            vec2(0.0, -20.0),
+            rect((80.0, 80.0), (320.0, 200.0)),
+        ),
+        (
+            HitZone::Bottom,
+            vec2(0.0, 20.0),
+            rect((80.0, 100.0), (320.0, 220.0)),
+        ),
+        (
+            HitZone::Left,
+            vec2(-40.0, 0.0),
+            rect((60.0, 90.0), (300.0, 210.0)),
+        ),
+        (
+            HitZone::Right,
+            vec2(40.0, 0.0),
+            rect((100.0, 90.0), (340.0, 210.0)),
+        ),
+        // 移动和空白处不受比例影响
+        (
+            HitZone::Inside,
+            vec2(10.0, 10.0),
+            rect((110.0, 110.0), (310.0, 210.0)),
+        ),
+        (HitZone::None, vec2(10.0, 10.0), start),
+    ];
+    for (zone, delta, expected) in cases {
+        let result = zone.apply_drag(start, delta, screen, Some(2.0));
+        assert_eq!(result, expected, "{zone:?}");
+        assert_eq!(result.width(), result.height() * 2.0, "{zone:?}");
+    }
+}
+
+#[test]
+fn locked_drag_off_screen_keeps_the_rect() {
+    let screen = rect((0.0, 0.0), (320.0, 320.0));
+    let start = rect((100.0, 100.0), (300.0, 200.0));
+    let result = HitZone::BottomRight.apply_drag(start, vec2(100.0, 100.0), screen, Some(2.0));
+    assert_eq!(result, start);
+}
+
+#[test]
+fn size_input_maps_pixels_to_points() {
+    let screen = rect((0.0, 0.0), (960.0, 540.0));
+    let current = rect((20.0, 30.0), (120.0, 130.0));
+    let to_rect = |w, h, pos| rect_from_size_input(SizeInput { w, h, pos }, current, screen, 2.0);
+
+    // 有位置时按物理像素换算
+    assert_eq!(
+        to_rect(800, 600, Some((100, 200))),
+        Rect::from_min_size(pos2(50.0, 100.0), vec2(400.0, 300.0))
+    );
+    // 没有位置时保留当前选区的左上角
+    assert_eq!(
+        to_rect(800, 600, None),
+        Rect::from_min_size(pos2(20.0, 30.0), vec2(400.0, 300.0))
+    );
+    // 超出屏幕时推回屏幕内
+    assert_eq!(
+        to_rect(800, 600, Some((1800, 1000))),
+        Rect::from_min_size(pos2(560.0, 240.0), vec2(400.0, 300.0))
+    );
+    // 比屏幕大时缩到屏幕大小
+    assert_eq!(to_rect(4000, 4000, Some((10, 10))), screen);
+    assert_eq!(to_rect(4000, 4000, None).size(), Vec2::new(960.0, 540.0));
+}
@@ -0,0 +1,59 @@
+//! 箭头端点吸附
+
+use eframe::egui::{Pos2, Rect};
+use pxshot::tools::arrow::{SnapHint, SnapTargets};
+
+fn targets() -> SnapTargets {
+    SnapTargets {
+        bounds: Some(Rect::from_min_max(
+            Pos2::new(0.0, 0.0),
+            Pos2::new(200.0, 100.0),
+        )),
+        points: vec![Pos2::new(50.0, 50.0)],
+    }
+}
+
+fn assert_near(a: Pos2, b: Pos2) {
+    assert!((a - b).length() < 1e-3, "{a:?} != {b:?}");
+}
+
+#[test]
+fn shift_snaps_to_15_degrees() {
+    let anchor = Pos2::new(100.0, 50.0);
+    // 约 -3° 吸附到水平，长度不变
+    let (pos, hints) = targets().snap(Pos2::new(140.0, 52.0), Some(anchor), true);
+    assert_near(pos, Pos2::new(100.0 + 40.05, 50.0));
+    assert!(matches!(hints[0], SnapHint::Angle { degrees, .. } if degrees == 0.0));
+
+    // 向右上约 43° 吸附到 45°
+    let (pos, hints) = targets().snap(Pos2::new(130.0, 22.0), Some(anchor), true);
+    let delta = pos - anchor;
+    assert!((delta.x + delta.y).abs() < 1e-3);
+    assert!(matches!(hints[0], SnapHint::Angle { degrees, .. } if degrees == 45.0));
+}
+
+#[test]
+fn shift_snaps_along_the_ray_to_selection_edges() {
+    let anchor = Pos2::new(100.0, 50.0);
+    let (pos, hints) = targets().snap(Pos2::new(195.0, 51.0), Some(anchor), true);
+    assert_near(pos, Pos2::new(200.0, 50.0));
+    assert!(matches!(hints[1], SnapHint::Edge { .. }));
+}
+
+#[test]
+fn endpoints_win_over_edges() {
+    let (pos, hints) = targets().snap(Pos2::new(54.0, 47.0), None, false);
+    assert_eq!(pos, Pos2::new(50.0, 50.0));
+    assert_eq!(hints, vec![SnapHint::Point(Pos2::new(50.0, 50.0))]);
+}
+
+#[test]
+fn edges_snap_each_axis() {
+    let (pos, hints) = targets().snap(Pos2::new(4.0, 96.0), None, false);
+    assert_eq!(pos, Pos2::new(0.0, 100.0));
+    assert_eq!(hints.len(), 2);
+
+    let (pos, hints) = targets().snap(Pos2::new(120.0, 30.0), None, false);
+    assert_eq!(pos, Pos2::new(120.0, 30.0));
+    assert!(hints.is_empty());
+}
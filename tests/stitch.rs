@@ -0,0 +1,108 @@
+//! 长截图的帧拼接
+
+use pxshot::capture::RgbaImage;
+use pxshot::scroll::{StitchResult, Stitcher};
+
+const WIDTH: u32 = 16;
+
+/// 合成的页面：第 `row` 行的像素由 `content` 决定
+fn page(rows: impl IntoIterator<Item = u32>, content: impl Fn(u32) -> u32) -> RgbaImage {
+    let rows: Vec<u32> = rows.into_iter().collect();
+    RgbaImage::from_fn(WIDTH, rows.len() as u32, |x, y| {
+        let v = content(rows[y as usize]);
+        // 每行内部有变化，不是纯色行
+        image::Rgba([(v >> 8) as u8, v as u8, x as u8, 255])
+    })
+}
+
+fn unique(row: u32) -> u32 {
+    row
+}
+
+/// 页面中从 `offset` 开始高 `height` 的一帧
+fn frame(offset: u32, height: u32) -> RgbaImage {
+    page(offset..offset + height, unique)
+}
+
+#[test]
+fn exact_overlap_appends_new_rows() {
+    let mut stitcher = Stitcher::new(frame(0, 100));
+    assert_eq!(stitcher.push(&frame(30, 100)), StitchResult::Appended(30));
+    assert_eq!(stitcher.push(&frame(30, 100)), StitchResult::Unchanged);
+    assert_eq!(stitcher.push(&frame(75, 100)), StitchResult::Appended(45));
+    assert_eq!(stitcher.image(), &frame(0, 175));
+}
+
+#[test]
+fn no_overlap_appends_whole_frame() {
+    let mut stitcher = Stitcher::new(frame(0, 100));
+    assert_eq!(stitcher.push(&frame(500, 100)), StitchResult::NoOverlap);
+    assert_eq!(stitcher.image().height(), 200);
+    // 之后从新的一帧接着拼
+    assert_eq!(stitcher.push(&frame(520, 100)), StitchResult::Appended(20));
+    assert_eq!(stitcher.image().height(), 220);
+}
+
+#[test]
+fn static_header_and_footer_are_kept_once() {
+    const HEADER: u32 = 12;
+    const FOOTER: u32 = 20;
+    const BODY: u32 = 80;
+    // 页眉页脚用页面之外的行号
+    let with_bars = |offset: u32, body: u32| {
+        let rows = (10_000..10_000 + HEADER)
+            .chain(offset..offset + body)
+            .chain(20_000..20_000 + FOOTER);
+        page(rows, unique)
+    };
+
+    let mut stitcher = Stitcher::new(with_bars(0, BODY));
+    assert_eq!(
+        stitcher.push(&with_bars(25, BODY)),
+        StitchResult::Appended(25)
+    );
+    assert_eq!(
+        stitcher.push(&with_bars(50, BODY)),
+        StitchResult::Appended(25)
+    );
+    assert_eq!(stitcher.image(), &with_bars(0, BODY + 50));
+}
+
+#[test]
+fn repeated_rows_do_not_fake_a_scroll() {
+    // 周期为 4 的重复行，每 16 行有一行不同的内容
+    let content = |row: u32| {
+        if row.is_multiple_of(16) {
+            row
+        } else {
+            5_000 + row % 4
+        }
+    };
+    let frame = |offset: u32| page(offset..offset + 120, content);
+
+    let mut stitcher = Stitcher::new(frame(0));
+    assert_eq!(stitcher.push(&frame(36)), StitchResult::Appended(36));
+    assert_eq!(stitcher.image(), &page(0..156, content));
+}
+
+#[test]
+fn uniform_rows_of_different_colors_do_not_match() {
+    // 每 40 行一块：10 行相同的文字，其余是纯色背景，颜色每三块循环一次
+    let banded = |offset: u32, height: u32| {
+        RgbaImage::from_fn(WIDTH, height, |x, y| {
+            let row = offset + y;
+            let (block, line) = (row / 40, row % 40);
+            if line < 10 {
+                image::Rgba([line as u8, 0, x as u8, 255])
+            } else {
+                let gray = (block % 3) as u8 * 100;
+                image::Rgba([gray, gray, gray, 255])
+            }
+        })
+    };
+
+    // 只比较文字行的话，错开一块也能对齐
+    let mut stitcher = Stitcher::new(banded(0, 160));
+    assert_eq!(stitcher.push(&banded(80, 160)), StitchResult::Appended(80));
+    assert_eq!(stitcher.image(), &banded(0, 240));
+}
@@ -0,0 +1,97 @@
+//! 样式预设、最近使用的颜色和颜色输入
+
+use eframe::egui::Color32;
+use pxshot::config::Config;
+use pxshot::tools::arrow::{
+    ArrowConfig, ArrowSettings, ArrowToolPanel, ArrowType, RECENT_COLORS, color_to_hex,
+    parse_hex_color,
+};
+
+#[test]
+fn hex_colors() {
+    assert_eq!(color_to_hex(Color32::RED), "#ff0000");
+    let translucent = Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+    assert_eq!(color_to_hex(translucent), "#ff000080");
+    assert_eq!(parse_hex_color("#ff000080"), Some(translucent));
+    // `#` 可以省略，支持简写
+    assert_eq!(parse_hex_color(" 00f "), Some(Color32::BLUE));
+    assert_eq!(parse_hex_color("#12345"), None);
+    assert_eq!(parse_hex_color("red"), None);
+}
+
+#[test]
+fn recent_colors_are_deduplicated_and_bounded() {
+    let mut panel = ArrowToolPanel::default();
+    let gray = |v: u8| Color32::from_rgb(v, v, v);
+    for v in 0..10 {
+        panel.push_recent_color(gray(100 + v));
+    }
+    assert_eq!(panel.recent_colors.len(), RECENT_COLORS);
+    assert_eq!(panel.recent_colors[0], gray(109));
+
+    // 再次使用移到最前
+    panel.push_recent_color(gray(105));
+    assert_eq!(panel.recent_colors[0], gray(105));
+    assert_eq!(panel.recent_colors.len(), RECENT_COLORS);
+    assert_eq!(
+        panel
+            .recent_colors
+            .iter()
+            .filter(|c| **c == gray(105))
+            .count(),
+        1
+    );
+
+    // 固定的预设颜色不记录
+    panel.push_recent_color(Color32::RED);
+    assert_eq!(panel.recent_colors[0], gray(105));
+}
+
+#[test]
+fn presets_save_and_apply() {
+    let mut panel = ArrowToolPanel::default();
+    panel.presets.clear();
+    panel.config.arrow_type = ArrowType::Hollow;
+    panel.save_preset(" Mine ");
+    panel.config.color = Color32::BLUE;
+    // 同名覆盖
+    panel.save_preset("Mine");
+    assert_eq!(panel.presets.len(), 1);
+    assert_eq!(panel.presets[0].name, "Mine");
+    assert_eq!(panel.presets[0].style.color, Color32::BLUE);
+    panel.save_preset("  ");
+    assert_eq!(panel.presets.len(), 1);
+
+    // 有选中的箭头时应用到选中的样式，默认样式不变
+    panel.config = ArrowConfig::default();
+    panel.editing = Some(ArrowConfig::default());
+    panel.apply_preset(0);
+    assert_eq!(panel.style().arrow_type, ArrowType::Hollow);
+    assert_eq!(panel.config.arrow_type, ArrowType::Single);
+
+    panel.editing = None;
+    panel.apply_preset(0);
+    assert_eq!(panel.config.color, Color32::BLUE);
+}
+
+#[test]
+fn settings_round_trip_through_config() {
+    let mut panel = ArrowToolPanel::default();
+    panel.load_settings(&ArrowSettings::default());
+    assert!(!panel.presets.is_empty());
+    panel.config.stroke_width = 9.0;
+    panel.push_recent_color(Color32::from_rgb(1, 2, 3));
+    panel.save_preset("Thick");
+
+    let config = Config {
+        arrow: panel.settings(),
+        ..Config::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.arrow, panel.settings());
+
+    // 旧配置没有 arrow 字段时使用默认值
+    let old: Config = serde_json::from_str(r#"{"rasterizer": "basic"}"#).unwrap();
+    assert_eq!(old.arrow, ArrowSettings::default());
+}
@@ -0,0 +1,51 @@
+//! SVG 导出
+
+use eframe::egui::{Color32, Pos2};
+use pxshot::capture::RgbaImage;
+use pxshot::document::{AnnotationDoc, Project};
+use pxshot::export::project_to_svg;
+use pxshot::tools::arrow::{Arrow, ArrowConfig, ArrowType};
+
+fn svg(arrow_type: ArrowType, shadow: bool) -> String {
+    let config = ArrowConfig {
+        arrow_type,
+        color: Color32::from_rgba_unmultiplied(255, 0, 0, 128),
+        ..ArrowConfig::default()
+    };
+    let mut arrow = Arrow::new(Pos2::new(10.0, 10.0), Pos2::new(80.0, 40.0), &config);
+    arrow.effects.shadow.enabled = shadow;
+    let project = Project::new(
+        RgbaImage::new(100, 60),
+        AnnotationDoc {
+            ppp: 1.0,
+            arrows: vec![arrow],
+        },
+    );
+    project_to_svg(&project).unwrap()
+}
+
+/// 半透明箭头的透明度只在整组上应用一次，线条和箭头重叠处不会更深
+#[test]
+fn translucent_arrow_blends_once() {
+    for arrow_type in [ArrowType::Single, ArrowType::Filled, ArrowType::Hollow] {
+        let svg = svg(arrow_type, false);
+        assert!(
+            svg.contains(r#"data-path="Straight" opacity="0.502">"#),
+            "{svg}"
+        );
+        assert!(!svg.contains("fill-opacity"), "{svg}");
+        assert!(!svg.contains("stroke-opacity"), "{svg}");
+    }
+}
+
+/// 有效果时透明度放进滤镜，只淡化箭头本身
+#[test]
+fn translucent_arrow_keeps_effects_opaque() {
+    let svg = svg(ArrowType::Filled, true);
+    assert!(svg.contains(r#"filter="url(#arrow-0-effects)">"#), "{svg}");
+    assert!(!svg.contains(r#" opacity="#), "{svg}");
+    assert!(
+        svg.contains(r#"in="SourceGraphic" result="body"><feFuncA type="linear" slope="0.502"/>"#)
+    );
+    assert!(svg.contains(r#"<feMergeNode in="body"/>"#));
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+use pxshot::upload::{ResponseUrl, UploadBody, UploaderConfig, upload};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake image data";
+
+/// 收到的请求
+struct Received {
+    request_line: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Received {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn body_text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// 只处理一个请求的本地 HTTP 服务器，返回地址和收到的请求
+fn mock_server(status: u16, content_type: &str, body: &str) -> (String, JoinHandle<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (k, v) = line.split_once(':').unwrap();
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+        let length = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, v)| v.parse().unwrap());
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+        Received {
+            request_line: request_line.trim_end().to_string(),
+            headers,
+            body,
+        }
+    });
+    (addr, handle)
+}
+
+#[test]
+fn multipart_upload_with_json_path() {
+    let (addr, server) = mock_server(
+        200,
+        "application/json",
+        r#"{"data": {"files": [{"link": "https://files.example/abc.png"}]}}"#,
+    );
+    let config = UploaderConfig {
+        name: "mock".to_string(),
+        url: format!("{addr}/upload?name={{filename}}"),
+        headers: BTreeMap::from([("Authorization".to_string(), "Bearer secret".to_string())]),
+        fields: BTreeMap::from([("album".to_string(), "screenshots".to_string())]),
+        file_field: "image".to_string(),
+        response_url: ResponseUrl::Json {
+            path: "data.files[0].link".to_string(),
+        },
+        ..UploaderConfig::default()
+    };
+
+    let url = upload(&config, PNG, "shot.png").unwrap();
+    assert_eq!(url, "https://files.example/abc.png");
+
+    let received = server.join().unwrap();
+    assert_eq!(received.request_line, "POST /upload?name=shot.png HTTP/1.1");
+    assert_eq!(received.header("Authorization"), Some("Bearer secret"));
+    let content_type = received.header("Content-Type").unwrap();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+
+    let body = received.body_text();
+    assert!(body.starts_with(&format!("--{boundary}\r\n")));
+    assert!(body.contains("Content-Disposition: form-data; name=\"album\"\r\n\r\nscreenshots\r\n"));
+    assert!(body.contains(
+        "Content-Disposition: form-data; name=\"image\"; filename=\"shot.png\"\r\nContent-Type: image/png\r\n\r\n"
+    ));
+    assert!(body.ends_with(&format!("\r\n--{boundary}--\r\n")));
+    let start = received
+        .body
+        .windows(PNG.len())
+        .position(|w| w == PNG)
+        .expect("file data in body");
+    assert!(start > 0);
+}
+
+#[test]
+fn binary_upload_with_regex() {
+    let (addr, server) = mock_server(
+        201,
+        "text/html",
+        "<html>Uploaded: <a href=\"https://i.example/x1y2\">link</a></html>",
+    );
+    let config = UploaderConfig {
+        url: format!("{addr}/put/{{filename}}"),
+        method: "put".to_string(),
+        body: UploadBody::Binary,
+        response_url: ResponseUrl::Regex {
+            pattern: r#"href="([^"]+)""#.to_string(),
+            group: 1,
+        },
+        ..UploaderConfig::default()
+    };
+
+    let url = upload(&config, PNG, "a.png").unwrap();
+    assert_eq!(url, "https://i.example/x1y2");
+
+    let received = server.join().unwrap();
+    assert_eq!(received.request_line, "PUT /put/a.png HTTP/1.1");
+    assert_eq!(received.header("Content-Type"), Some("image/png"));
+    assert_eq!(received.body, PNG);
+}
+
+#[test]
+fn file_name_is_encoded_in_the_url() {
+    let (addr, server) = mock_server(200, "text/plain", "https://i.example/1");
+    let config = UploaderConfig {
+        url: format!("{addr}/put/{{filename}}"),
+        method: "put".to_string(),
+        body: UploadBody::Binary,
+        ..UploaderConfig::default()
+    };
+
+    upload(&config, PNG, "my shot&1.png").unwrap();
+    let received = server.join().unwrap();
+    assert_eq!(received.request_line, "PUT /put/my%20shot%261.png HTTP/1.1");
+}
+
+#[test]
+fn plain_response_body_is_the_link() {
+    let (addr, server) = mock_server(200, "text/plain", "https://short.example/q\n");
+    let config = UploaderConfig {
+        url: addr,
+        ..UploaderConfig::default()
+    };
+
+    assert_eq!(
+        upload(&config, PNG, "a.png").unwrap(),
+        "https://short.example/q"
+    );
+    server.join().unwrap();
+}
+
+#[test]
+fn http_error_is_reported() {
+    let (addr, server) = mock_server(403, "text/plain", "invalid token");
+    let config = UploaderConfig {
+        url: addr,
+        ..UploaderConfig::default()
+    };
+
+    let err = upload(&config, PNG, "a.png").unwrap_err().to_string();
+    assert!(err.contains("403"), "{err}");
+    assert!(err.contains("invalid token"), "{err}");
+    server.join().unwrap();
+}
+
+#[test]
+fn missing_json_field_is_reported() {
+    let (addr, server) = mock_server(200, "application/json", r#"{"success": false}"#);
+    let config = UploaderConfig {
+        url: addr,
+        response_url: ResponseUrl::Json {
+            path: "data.link".to_string(),
+        },
+        ..UploaderConfig::default()
+    };
+
+    let err = upload(&config, PNG, "a.png").unwrap_err().to_string();
+    assert!(err.contains("data.link"), "{err}");
+    server.join().unwrap();
+}
+
+#[test]
+fn import_sxcu() {
+    let config = UploaderConfig::from_sxcu(
+        r#"{
+            "Version": "15.0.0",
+            "Name": "Internal host",
+            "DestinationType": "ImageUploader",
+            "RequestMethod": "POST",
+            "RequestURL": "https://files.internal/api/upload",
+            "Parameters": {"expires": "7 days"},
+            "Headers": {"X-Token": "abc"},
+            "Body": "MultipartFormData",
+            "Arguments": {"folder": "shots"},
+            "FileFormName": "upload",
+            "URL": "$json:result.url$"
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.name, "Internal host");
+    assert_eq!(
+        config.url,
+        "https://files.internal/api/upload?expires=7%20days"
+    );
+    assert_eq!(config.headers["X-Token"], "abc");
+    assert_eq!(config.fields["folder"], "shots");
+    assert_eq!(config.file_field, "upload");
+    assert_eq!(config.body, UploadBody::Multipart);
+    assert_eq!(
+        config.response_url,
+        ResponseUrl::Json {
+            path: "result.url".to_string()
+        }
+    );
+
+    let config = UploaderConfig::from_sxcu(
+        r#"{
+            "RequestURL": "https://paste.example/",
+            "Body": "Binary",
+            "RegexList": ["id=(\\w+)", "url=(\\S+)"],
+            "URL": "{regex:2|1}"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.body, UploadBody::Binary);
+    assert_eq!(
+        config.response_url,
+        ResponseUrl::Regex {
+            pattern: "url=(\\S+)".to_string(),
+            group: 1
+        }
+    );
+    assert_eq!(
+        config
+            .response_url
+            .extract("ok url=https://p.example/1")
+            .unwrap(),
+        "https://p.example/1"
+    );
+
+    assert!(UploaderConfig::from_sxcu(r#"{"RequestURL": "x", "Body": "FormURLEncoded"}"#).is_err());
+    assert!(
+        UploaderConfig::from_sxcu(r#"{"RequestURL": "x", "URL": "https://h/$json:id$.png"}"#)
+            .is_err()
+    );
+}